@@ -0,0 +1,176 @@
+use crate::protocol::EffectSpec;
+use crate::protocol::LayerSpec;
+use crate::protocol::Request;
+use crate::protocol::Response;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::net::TcpStream;
+use std::net::ToSocketAddrs;
+
+/// An error that may occur while talking to the daemon.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ClientError {
+    /// The connection failed.
+    Io(std::io::Error),
+
+    /// A message could not be encoded or decoded.
+    Json(serde_json::Error),
+
+    /// The daemon closed the connection.
+    Closed,
+
+    /// The daemon rejected a request.
+    Daemon {
+        /// The reason the daemon gave.
+        message: String,
+    },
+
+    /// The daemon sent a response that did not fit the request.
+    UnexpectedResponse(Response),
+}
+
+impl std::fmt::Display for ClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(_) => "failed to talk to the daemon".fmt(f),
+            Self::Json(_) => "invalid message".fmt(f),
+            Self::Closed => "the daemon closed the connection".fmt(f),
+            Self::Daemon { message } => write!(f, "the daemon rejected the request: {message}"),
+            Self::UnexpectedResponse(response) => write!(f, "unexpected response {response:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Json(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ClientError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for ClientError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json(error)
+    }
+}
+
+/// A connection to a [`Daemon`](crate::Daemon).
+///
+/// The lighting of the client is removed when this is dropped.
+#[derive(Debug)]
+pub struct Client {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    id: u64,
+}
+
+impl Client {
+    /// Connect to the daemon and register with a name and priority.
+    ///
+    /// # Errors
+    /// Returns an error if the daemon could not be reached or rejected the client.
+    pub fn connect<A>(address: A, name: &str, priority: i32) -> Result<Self, ClientError>
+    where
+        A: ToSocketAddrs,
+    {
+        let writer = TcpStream::connect(address)?;
+        writer.set_nodelay(true)?;
+        let reader = BufReader::new(writer.try_clone()?);
+
+        let mut client = Self {
+            reader,
+            writer,
+            id: 0,
+        };
+        match client.request(&Request::Register {
+            name: name.into(),
+            priority,
+        })? {
+            Response::Registered { client: id } => client.id = id,
+            response => return Err(ClientError::UnexpectedResponse(response)),
+        }
+
+        Ok(client)
+    }
+
+    /// Get the id that the daemon gave this client.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Change the priority of this client.
+    ///
+    /// # Errors
+    /// Returns an error if the request failed.
+    pub fn set_priority(&mut self, priority: i32) -> Result<(), ClientError> {
+        self.request_ok(&Request::SetPriority { priority })
+    }
+
+    /// Replace the lighting of this client with a layer.
+    ///
+    /// # Errors
+    /// Returns an error if the request failed.
+    pub fn set_layer(&mut self, layer: &LayerSpec) -> Result<(), ClientError> {
+        self.request_ok(&Request::SetLayer {
+            layer: layer.clone(),
+        })
+    }
+
+    /// Replace the lighting of this client with an effect.
+    ///
+    /// # Errors
+    /// Returns an error if the request failed.
+    pub fn set_effect(&mut self, effect: &EffectSpec) -> Result<(), ClientError> {
+        self.request_ok(&Request::SetEffect {
+            effect: effect.clone(),
+        })
+    }
+
+    /// Remove the lighting of this client.
+    ///
+    /// # Errors
+    /// Returns an error if the request failed.
+    pub fn clear(&mut self) -> Result<(), ClientError> {
+        self.request_ok(&Request::Clear)
+    }
+
+    /// Send a request and wait for the response.
+    ///
+    /// # Errors
+    /// Returns an error if the request could not be sent, or the daemon rejected it.
+    pub fn request(&mut self, request: &Request) -> Result<Response, ClientError> {
+        let mut line = serde_json::to_string(request)?;
+        line.push('\n');
+        self.writer.write_all(line.as_bytes())?;
+        self.writer.flush()?;
+
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(ClientError::Closed);
+        }
+
+        match serde_json::from_str(&line)? {
+            Response::Error { message } => Err(ClientError::Daemon { message }),
+            response => Ok(response),
+        }
+    }
+
+    /// Send a request that the daemon answers with [`Response::Ok`].
+    fn request_ok(&mut self, request: &Request) -> Result<(), ClientError> {
+        match self.request(request)? {
+            Response::Ok => Ok(()),
+            response => Err(ClientError::UnexpectedResponse(response)),
+        }
+    }
+}
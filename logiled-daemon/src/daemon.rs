@@ -0,0 +1,569 @@
+use crate::protocol::Request;
+use crate::protocol::Response;
+use logitech_led_sdk::Backend;
+use logitech_led_sdk::BlendMode;
+use logitech_led_sdk::Compositor;
+use logitech_led_sdk::Effect;
+use logitech_led_sdk::Error;
+use logitech_led_sdk::Frame;
+use logitech_led_sdk::Layer;
+use logitech_led_sdk::LayerId;
+use logitech_led_sdk::Sdk;
+use logitech_led_sdk::DEFAULT_FRAME_RATE;
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::net::SocketAddr;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::Instant;
+
+/// The address that the daemon listens on by default.
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:47800";
+
+/// How often the listener checks whether the daemon was stopped.
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The name that the daemon initializes the sdk with.
+pub const APP_NAME: &str = "logiled-daemon";
+
+/// Something that happened on a connection.
+enum Event {
+    /// A connection sent a request.
+    Request {
+        connection: u64,
+        request: Request,
+        reply: mpsc::Sender<Response>,
+    },
+
+    /// A connection closed.
+    Disconnected { connection: u64 },
+}
+
+/// A registered client.
+struct Client {
+    name: String,
+    layer: LayerId,
+    effect: Option<(Box<dyn Effect>, Instant)>,
+}
+
+/// A handle that stops a [`Daemon`], which can be sent to other threads.
+#[derive(Debug, Clone, Default)]
+pub struct StopHandle {
+    stop_requested: Arc<AtomicBool>,
+}
+
+impl StopHandle {
+    /// Ask the daemon to stop.
+    ///
+    /// The daemon stops within a frame.
+    pub fn stop(&self) {
+        self.stop_requested.store(true, Ordering::SeqCst);
+    }
+
+    fn is_stop_requested(&self) -> bool {
+        self.stop_requested.load(Ordering::SeqCst)
+    }
+}
+
+/// A daemon that owns the sdk and shares it between many clients.
+///
+/// Each client gets one layer in a [`Compositor`], at the client's priority.
+/// The daemon renders client effects and flushes the compositor at a fixed frame rate,
+/// so only keys that changed are sent to the sdk.
+pub struct Daemon {
+    compositor: Compositor,
+    clients: HashMap<u64, Client>,
+    sender: mpsc::Sender<Event>,
+    receiver: mpsc::Receiver<Event>,
+    stop_handle: StopHandle,
+    frame_interval: Duration,
+}
+
+impl Daemon {
+    /// Create a new daemon with no clients.
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+
+        Self {
+            compositor: Compositor::new(),
+            clients: HashMap::new(),
+            sender,
+            receiver,
+            stop_handle: StopHandle::default(),
+            frame_interval: Duration::from_secs(1) / DEFAULT_FRAME_RATE,
+        }
+    }
+
+    /// Get a handle that stops this daemon.
+    pub fn stop_handle(&self) -> StopHandle {
+        self.stop_handle.clone()
+    }
+
+    /// Accept clients from a listener on a background thread.
+    ///
+    /// The thread exits when the daemon is stopped.
+    ///
+    /// # Errors
+    /// Returns an error if the listener could not be made non-blocking.
+    pub fn listen(&self, listener: TcpListener) -> std::io::Result<()> {
+        spawn_listener(listener, self.sender.clone(), self.stop_handle.clone())
+    }
+
+    /// Serve clients until stopped.
+    ///
+    /// If sending the lighting fails, the error is printed and everything is sent again on the next frame.
+    /// Only the first error of a run of failing frames is printed.
+    pub fn run(&mut self, sdk: &Sdk) {
+        let mut is_failing = false;
+        let mut next_frame = Instant::now();
+        while !self.stop_handle.is_stop_requested() {
+            let now = Instant::now();
+            if now >= next_frame {
+                self.render(now);
+                match self.compositor.flush(sdk) {
+                    Ok(_) => is_failing = false,
+                    Err(error) => {
+                        if !is_failing {
+                            eprintln!("failed to set the lighting: {error}");
+                        }
+                        is_failing = true;
+                    }
+                }
+
+                next_frame += self.frame_interval;
+                if next_frame < now {
+                    next_frame = now + self.frame_interval;
+                }
+            }
+
+            let timeout = next_frame.saturating_duration_since(Instant::now());
+            if let Ok(event) = self.receiver.recv_timeout(timeout) {
+                self.handle_event(event);
+            }
+        }
+    }
+
+    /// Run a daemon on a new thread, using a backend instead of the native sdk.
+    ///
+    /// This is meant for tests, with a `MockBackend` from the sdk's `mock` feature as an in-memory fake of the sdk.
+    ///
+    /// # Errors
+    /// Returns an error if the listener could not be used.
+    pub fn spawn<B>(backend: B, listener: TcpListener) -> std::io::Result<DaemonHandle>
+    where
+        B: Backend + Send + 'static,
+    {
+        let address = listener.local_addr()?;
+        let (sender, receiver) = mpsc::channel();
+        let stop_handle = StopHandle::default();
+        spawn_listener(listener, sender.clone(), stop_handle.clone())?;
+
+        let thread_stop_handle = stop_handle.clone();
+        let thread = std::thread::spawn(move || {
+            let sdk = Sdk::new_with_backend_and_name(backend, APP_NAME)?;
+            let mut daemon = Self {
+                sender,
+                receiver,
+                stop_handle: thread_stop_handle,
+                ..Self::new()
+            };
+            daemon.run(&sdk);
+            Ok(())
+        });
+
+        Ok(DaemonHandle {
+            address,
+            stop_handle,
+            thread: Some(thread),
+        })
+    }
+
+    /// Render the effects of every client into their layers.
+    fn render(&mut self, now: Instant) {
+        for client in self.clients.values_mut() {
+            let (effect, start) = match client.effect.as_mut() {
+                Some(effect) => effect,
+                None => continue,
+            };
+            let layer = match self.compositor.layer_mut(client.layer) {
+                Some(layer) => layer,
+                None => continue,
+            };
+
+            let mut frame = Frame::new();
+            effect.render(now.saturating_duration_since(*start), &mut frame);
+
+            layer.fill(frame.background());
+            for (key, color) in frame.iter() {
+                layer.set(key, color);
+            }
+        }
+    }
+
+    fn handle_event(&mut self, event: Event) {
+        match event {
+            Event::Request {
+                connection,
+                request,
+                reply,
+            } => {
+                let response = match self.handle_request(connection, request) {
+                    Ok(response) => response,
+                    Err(message) => Response::Error { message },
+                };
+
+                // The connection may have closed while waiting.
+                let _ = reply.send(response);
+            }
+            Event::Disconnected { connection } => {
+                if let Some(client) = self.clients.remove(&connection) {
+                    self.compositor.remove_layer(client.layer);
+                }
+            }
+        }
+    }
+
+    fn handle_request(&mut self, connection: u64, request: Request) -> Result<Response, String> {
+        if let Request::Register { name, priority } = request {
+            if let Some(client) = self.clients.get(&connection) {
+                return Err(format!("already registered as \"{}\"", client.name));
+            }
+
+            let layer = self
+                .compositor
+                .add_layer(priority, Layer::new(BlendMode::Normal));
+            self.clients.insert(
+                connection,
+                Client {
+                    name,
+                    layer,
+                    effect: None,
+                },
+            );
+
+            return Ok(Response::Registered { client: connection });
+        }
+
+        let client = self
+            .clients
+            .get_mut(&connection)
+            .ok_or("the first request must register the client")?;
+        match request {
+            Request::Register { .. } => unreachable!("registration is handled above"),
+            Request::SetPriority { priority } => {
+                self.compositor.set_priority(client.layer, priority);
+            }
+            Request::SetLayer { layer } => {
+                client.effect = None;
+                if let Some(client_layer) = self.compositor.layer_mut(client.layer) {
+                    *client_layer = layer.to_layer();
+                }
+            }
+            Request::SetEffect { effect } => {
+                client.effect = Some((effect.to_effect(), Instant::now()));
+                if let Some(client_layer) = self.compositor.layer_mut(client.layer) {
+                    *client_layer = Layer::new(BlendMode::Normal);
+                }
+            }
+            Request::Clear => {
+                client.effect = None;
+                if let Some(client_layer) = self.compositor.layer_mut(client.layer) {
+                    client_layer.clear();
+                }
+            }
+        }
+
+        Ok(Response::Ok)
+    }
+}
+
+impl Default for Daemon {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A daemon running on another thread, from [`Daemon::spawn`].
+///
+/// Dropping this stops the daemon and waits for it to exit.
+#[derive(Debug)]
+pub struct DaemonHandle {
+    address: SocketAddr,
+    stop_handle: StopHandle,
+    thread: Option<JoinHandle<Result<(), Error>>>,
+}
+
+impl DaemonHandle {
+    /// Get the address that the daemon is listening on.
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Stop the daemon and wait for it to exit.
+    ///
+    /// # Errors
+    /// Returns an error if the daemon could not initialize the sdk.
+    pub fn stop(mut self) -> Result<(), Error> {
+        self.join()
+    }
+
+    fn join(&mut self) -> Result<(), Error> {
+        self.stop_handle.stop();
+        match self.thread.take() {
+            Some(thread) => thread.join().expect("daemon thread panicked"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for DaemonHandle {
+    fn drop(&mut self) {
+        let _ = self.join();
+    }
+}
+
+/// Accept connections on a background thread until stopped.
+fn spawn_listener(
+    listener: TcpListener,
+    sender: mpsc::Sender<Event>,
+    stop_handle: StopHandle,
+) -> std::io::Result<()> {
+    listener.set_nonblocking(true)?;
+
+    std::thread::spawn(move || {
+        let mut next_connection = 0;
+        while !stop_handle.is_stop_requested() {
+            let stream = match listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => {
+                    std::thread::sleep(ACCEPT_POLL_INTERVAL);
+                    continue;
+                }
+                Err(_) => continue,
+            };
+
+            let connection = next_connection;
+            next_connection += 1;
+
+            let sender = sender.clone();
+            std::thread::spawn(move || serve_connection(stream, connection, sender));
+        }
+    });
+
+    Ok(())
+}
+
+/// Forward the requests of a connection to the daemon, and write back the responses.
+fn serve_connection(stream: TcpStream, connection: u64, sender: mpsc::Sender<Event>) {
+    let _ = forward_requests(&stream, connection, &sender);
+    let _ = sender.send(Event::Disconnected { connection });
+}
+
+fn forward_requests(
+    stream: &TcpStream,
+    connection: u64,
+    sender: &mpsc::Sender<Event>,
+) -> std::io::Result<()> {
+    // Accepted streams may inherit non-blocking mode from the listener.
+    stream.set_nonblocking(false)?;
+    let reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str(&line) {
+            Ok(request) => {
+                let (reply, response) = mpsc::channel();
+                let event = Event::Request {
+                    connection,
+                    request,
+                    reply,
+                };
+                if sender.send(event).is_err() {
+                    break;
+                }
+                match response.recv() {
+                    Ok(response) => response,
+                    Err(_) => break,
+                }
+            }
+            Err(error) => Response::Error {
+                message: format!("invalid request: {error}"),
+            },
+        };
+
+        let mut response = serde_json::to_string(&response)?;
+        response.push('\n');
+        writer.write_all(response.as_bytes())?;
+        writer.flush()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Client;
+    use crate::ClientError;
+    use crate::EffectSpec;
+    use crate::LayerSpec;
+    use logitech_led_sdk::ColorPercent;
+    use logitech_led_sdk::KeyName;
+    use logitech_led_sdk::MockBackend;
+
+    /// Wait for a condition that the daemon makes true on a later frame.
+    fn wait_for<F>(mut condition: F)
+    where
+        F: FnMut() -> bool,
+    {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !condition() {
+            assert!(
+                Instant::now() < deadline,
+                "timed out waiting for the daemon"
+            );
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    fn spawn() -> (MockBackend, DaemonHandle) {
+        let backend = MockBackend::new();
+        let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind");
+        let daemon = Daemon::spawn(backend.clone(), listener).expect("failed to spawn daemon");
+        (backend, daemon)
+    }
+
+    #[test]
+    fn priorities() {
+        let (backend, daemon) = spawn();
+
+        let mut background =
+            Client::connect(daemon.address(), "background", 0).expect("failed to connect");
+        let mut layer = LayerSpec::new();
+        layer.fill = Some(ColorPercent::BLUE);
+        background.set_layer(&layer).expect("failed to set layer");
+
+        let mut alert = Client::connect(daemon.address(), "alert", 10).expect("failed to connect");
+        let mut layer = LayerSpec::new();
+        layer.keys.insert(KeyName::W, ColorPercent::RED);
+        alert.set_layer(&layer).expect("failed to set layer");
+
+        wait_for(|| {
+            backend.key_color(KeyName::W) == Some(ColorPercent::RED)
+                && backend.key_color(KeyName::A) == Some(ColorPercent::BLUE)
+        });
+
+        // Moving the background above the alert hides it.
+        background.set_priority(20).expect("failed to set priority");
+        wait_for(|| backend.key_color(KeyName::W) == Some(ColorPercent::BLUE));
+
+        // The lighting of a client goes away when it disconnects.
+        background.set_priority(0).expect("failed to set priority");
+        wait_for(|| backend.key_color(KeyName::W) == Some(ColorPercent::RED));
+        drop(alert);
+        wait_for(|| backend.key_color(KeyName::W) == Some(ColorPercent::BLUE));
+
+        background.clear().expect("failed to clear");
+        wait_for(|| backend.key_color(KeyName::W) == Some(ColorPercent::BLACK));
+
+        daemon.stop().expect("daemon failed");
+    }
+
+    #[test]
+    fn effects() {
+        let (backend, daemon) = spawn();
+
+        let mut client = Client::connect(daemon.address(), "effect", 0).expect("failed to connect");
+        client
+            .set_effect(&EffectSpec::Breathing {
+                color: ColorPercent::WHITE,
+                period_ms: 1000,
+            })
+            .expect("failed to set effect");
+
+        // The effect changes the lighting over time.
+        wait_for(|| backend.lighting().is_some());
+        let first = backend.lighting();
+        wait_for(|| backend.lighting() != first);
+
+        daemon.stop().expect("daemon failed");
+    }
+
+    #[test]
+    fn sdk_errors() {
+        let (backend, daemon) = spawn();
+        backend.set_failing("LogiLedSetLighting", true);
+
+        let mut client = Client::connect(daemon.address(), "client", 0).expect("failed to connect");
+        let mut layer = LayerSpec::new();
+        layer.fill = Some(ColorPercent::BLUE);
+        client.set_layer(&layer).expect("failed to set layer");
+        wait_for(|| {
+            backend
+                .calls()
+                .iter()
+                .any(|call| call.function() == "LogiLedSetLighting")
+        });
+
+        // The daemon keeps running, and sends everything again once the sdk works.
+        backend.set_failing("LogiLedSetLighting", false);
+        wait_for(|| backend.lighting() == Some(ColorPercent::BLUE));
+
+        daemon.stop().expect("daemon failed");
+    }
+
+    #[test]
+    fn errors() {
+        let (_backend, daemon) = spawn();
+
+        let mut stream = TcpStream::connect(daemon.address()).expect("failed to connect");
+        let mut reader = BufReader::new(stream.try_clone().expect("failed to clone stream"));
+        let mut request = |line: &str| {
+            writeln!(stream, "{line}").expect("failed to write");
+            let mut response = String::new();
+            reader.read_line(&mut response).expect("failed to read");
+            serde_json::from_str::<Response>(&response).expect("invalid response")
+        };
+
+        assert!(matches!(
+            request(r#"{ "type": "clear" }"#),
+            Response::Error { message } if message.contains("must register")
+        ));
+        assert!(matches!(
+            request("not json"),
+            Response::Error { message } if message.starts_with("invalid request")
+        ));
+        assert!(matches!(
+            request(r#"{ "type": "register", "name": "raw", "priority": 0 }"#),
+            Response::Registered { .. }
+        ));
+        assert!(matches!(
+            request(r#"{ "type": "register", "name": "raw", "priority": 0 }"#),
+            Response::Error { message } if message.contains("already registered as \"raw\"")
+        ));
+
+        let mut client = Client::connect(daemon.address(), "client", 0).expect("failed to connect");
+        assert!(matches!(
+            client.request(&Request::Register {
+                name: "again".into(),
+                priority: 0
+            }),
+            Err(ClientError::Daemon { .. })
+        ));
+
+        daemon.stop().expect("daemon failed");
+    }
+}
@@ -0,0 +1,20 @@
+//! A daemon that shares Logitech LED lighting between many processes.
+//!
+//! Only one [`Sdk`](logitech_led_sdk::Sdk) can exist in a process, and processes that use the sdk at the same time overwrite each other.
+//! The [`Daemon`] owns the only sdk session and composites the lighting of every [`Client`] by priority.
+//! Clients talk to it over a local TCP socket with the messages in [`protocol`].
+
+mod client;
+mod daemon;
+pub mod protocol;
+
+pub use self::client::Client;
+pub use self::client::ClientError;
+pub use self::daemon::Daemon;
+pub use self::daemon::DaemonHandle;
+pub use self::daemon::StopHandle;
+pub use self::daemon::APP_NAME;
+pub use self::daemon::DEFAULT_ADDRESS;
+pub use self::protocol::BlendModeSpec;
+pub use self::protocol::EffectSpec;
+pub use self::protocol::LayerSpec;
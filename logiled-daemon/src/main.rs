@@ -0,0 +1,36 @@
+use anyhow::Context;
+use argh::FromArgs;
+use logiled_daemon::Daemon;
+use logiled_daemon::APP_NAME;
+use logiled_daemon::DEFAULT_ADDRESS;
+use logitech_led_sdk::Sdk;
+use std::net::TcpListener;
+
+#[derive(Debug, FromArgs)]
+#[argh(description = "Share Logitech LED lighting between many processes.")]
+struct Options {
+    #[argh(
+        option,
+        default = "DEFAULT_ADDRESS.into()",
+        description = "the address to listen on"
+    )]
+    address: String,
+}
+
+fn main() -> anyhow::Result<()> {
+    let options: Options = argh::from_env();
+
+    let listener = TcpListener::bind(&options.address)
+        .with_context(|| format!("failed to listen on \"{}\"", options.address))?;
+    let sdk = Sdk::new_with_name(APP_NAME).context("failed to initialize the sdk")?;
+
+    let mut daemon = Daemon::new();
+    daemon
+        .listen(listener)
+        .context("failed to accept clients")?;
+    eprintln!("Listening on \"{}\"", options.address);
+
+    daemon.run(&sdk);
+
+    Ok(())
+}
@@ -0,0 +1,305 @@
+//! The messages sent between clients and the daemon.
+//!
+//! Each message is one line of JSON.
+//! A client connects over TCP, sends [`Request::Register`], and then sends any other requests.
+//! The daemon answers every request with one [`Response`].
+//! When the connection closes, the client's lighting is removed.
+
+use logitech_led_sdk::effects;
+use logitech_led_sdk::effects::Direction;
+use logitech_led_sdk::BlendMode;
+use logitech_led_sdk::ColorPercent;
+use logitech_led_sdk::Effect;
+use logitech_led_sdk::KeyName;
+use logitech_led_sdk::Layer;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// A request from a client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Request {
+    /// Register the client.
+    ///
+    /// This must be the first request.
+    Register {
+        /// The name of the client, used in error messages.
+        name: String,
+
+        /// The priority of the client.
+        ///
+        /// The lighting of clients with a higher priority is drawn over clients with a lower priority.
+        priority: i32,
+    },
+
+    /// Change the priority of the client.
+    SetPriority {
+        /// The new priority.
+        priority: i32,
+    },
+
+    /// Replace the lighting of the client with a layer.
+    SetLayer {
+        /// The layer.
+        layer: LayerSpec,
+    },
+
+    /// Replace the lighting of the client with an effect that the daemon renders.
+    SetEffect {
+        /// The effect.
+        effect: EffectSpec,
+    },
+
+    /// Remove the lighting of the client.
+    Clear,
+}
+
+/// A response from the daemon.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Response {
+    /// The client was registered.
+    Registered {
+        /// The id of the client.
+        client: u64,
+    },
+
+    /// The request succeeded.
+    Ok,
+
+    /// The request failed.
+    Error {
+        /// What went wrong.
+        message: String,
+    },
+}
+
+/// A layer of key colors.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LayerSpec {
+    /// The color of every key that is not in `keys`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fill: Option<ColorPercent>,
+
+    /// The colors of single keys.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub keys: HashMap<KeyName, ColorPercent>,
+
+    /// The opacity of the layer, from 0 to 1.
+    #[serde(default = "default_opacity")]
+    pub opacity: f32,
+
+    /// How the layer is combined with the layers below it.
+    #[serde(default)]
+    pub blend_mode: BlendModeSpec,
+}
+
+impl LayerSpec {
+    /// Create a new, empty, fully opaque layer.
+    pub fn new() -> Self {
+        Self {
+            fill: None,
+            keys: HashMap::new(),
+            opacity: 1.0,
+            blend_mode: BlendModeSpec::Normal,
+        }
+    }
+
+    /// Convert this into a compositor layer.
+    pub(crate) fn to_layer(&self) -> Layer {
+        let mut layer = Layer::new(self.blend_mode.into());
+        layer.opacity = self.opacity;
+        if let Some(fill) = self.fill {
+            layer.fill(fill);
+        }
+        for (key, color) in self.keys.iter() {
+            layer.set(*key, *color);
+        }
+
+        layer
+    }
+}
+
+impl Default for LayerSpec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn default_opacity() -> f32 {
+    1.0
+}
+
+/// How a layer is combined with the layers below it, like [`BlendMode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlendModeSpec {
+    /// [`BlendMode::Normal`]
+    #[default]
+    Normal,
+
+    /// [`BlendMode::Add`]
+    Add,
+
+    /// [`BlendMode::Multiply`]
+    Multiply,
+
+    /// [`BlendMode::Screen`]
+    Screen,
+}
+
+impl From<BlendModeSpec> for BlendMode {
+    fn from(blend_mode: BlendModeSpec) -> Self {
+        match blend_mode {
+            BlendModeSpec::Normal => Self::Normal,
+            BlendModeSpec::Add => Self::Add,
+            BlendModeSpec::Multiply => Self::Multiply,
+            BlendModeSpec::Screen => Self::Screen,
+        }
+    }
+}
+
+/// An effect from [`effects`] that the daemon renders for a client.
+///
+/// Times are in milliseconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum EffectSpec {
+    /// [`effects::ColorCycle`]
+    ColorCycle {
+        /// The time for one cycle.
+        period_ms: u64,
+    },
+
+    /// [`effects::Breathing`]
+    Breathing {
+        /// The color.
+        color: ColorPercent,
+
+        /// The time for one breath.
+        period_ms: u64,
+    },
+
+    /// [`effects::Wave`]
+    Wave {
+        /// Whether the wave moves top to bottom instead of left to right.
+        #[serde(default)]
+        vertical: bool,
+
+        /// The time for the wave to move one wavelength.
+        period_ms: u64,
+    },
+
+    /// [`effects::GradientSweep`]
+    GradientSweep {
+        /// The starting color.
+        from: ColorPercent,
+
+        /// The ending color.
+        to: ColorPercent,
+
+        /// The time for one sweep there and back.
+        period_ms: u64,
+    },
+
+    /// [`effects::Twinkle`]
+    Twinkle {
+        /// The color of keys that are not twinkling.
+        base: ColorPercent,
+
+        /// The color of twinkling keys.
+        color: ColorPercent,
+
+        /// The random seed.
+        #[serde(default)]
+        seed: u64,
+    },
+
+    /// [`effects::Fire`]
+    Fire {
+        /// The random seed.
+        #[serde(default)]
+        seed: u64,
+    },
+}
+
+impl EffectSpec {
+    /// Create the effect.
+    pub(crate) fn to_effect(&self) -> Box<dyn Effect> {
+        match *self {
+            Self::ColorCycle { period_ms } => {
+                Box::new(effects::ColorCycle::new(Duration::from_millis(period_ms)))
+            }
+            Self::Breathing { color, period_ms } => Box::new(effects::Breathing::new(
+                color,
+                Duration::from_millis(period_ms),
+            )),
+            Self::Wave {
+                vertical,
+                period_ms,
+            } => {
+                let direction = if vertical {
+                    Direction::Vertical
+                } else {
+                    Direction::Horizontal
+                };
+                let mut wave = effects::Wave::new(direction);
+                wave.period = Duration::from_millis(period_ms);
+                Box::new(wave)
+            }
+            Self::GradientSweep {
+                from,
+                to,
+                period_ms,
+            } => Box::new(effects::GradientSweep::new(
+                from,
+                to,
+                Duration::from_millis(period_ms),
+            )),
+            Self::Twinkle { base, color, seed } => {
+                Box::new(effects::Twinkle::new(base, color, seed))
+            }
+            Self::Fire { seed } => Box::new(effects::Fire::new(seed)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn messages() {
+        let request: Request = serde_json::from_str(
+            r#"{ "type": "set_layer", "layer": { "fill": "blue", "keys": { "W": "red" }, "blend_mode": "add" } }"#,
+        )
+        .expect("failed to parse request");
+        let mut layer = LayerSpec::new();
+        layer.fill = Some(ColorPercent::BLUE);
+        layer.keys.insert(KeyName::W, ColorPercent::RED);
+        layer.blend_mode = BlendModeSpec::Add;
+        assert_eq!(request, Request::SetLayer { layer });
+
+        let json = serde_json::to_string(&Request::SetEffect {
+            effect: EffectSpec::Breathing {
+                color: ColorPercent::RED,
+                period_ms: 2000,
+            },
+        })
+        .expect("failed to serialize request");
+        assert_eq!(
+            json,
+            r##"{"type":"set_effect","effect":{"type":"breathing","color":"#ff0000","period_ms":2000}}"##
+        );
+
+        assert_eq!(
+            serde_json::to_string(&Response::Registered { client: 3 })
+                .expect("failed to serialize response"),
+            r#"{"type":"registered","client":3}"#
+        );
+        assert!(serde_json::from_str::<Request>(r#"{ "type": "reboot" }"#).is_err());
+    }
+}
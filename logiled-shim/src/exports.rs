@@ -0,0 +1,588 @@
+#![allow(clippy::missing_safety_doc)]
+
+//! The bodies of the exported functions.
+//!
+//! Each function converts the raw arguments of the sdk function of the same name,
+//! calls the backend, and writes any results back through the argument pointers.
+//! Calls with null pointers or invalid values fail without reaching the backend.
+//!
+//! # Safety
+//! Every pointer must either be null or valid for the sdk function of the same name.
+
+use crate::Shim;
+use logitech_led_sdk::sys;
+use logitech_led_sdk::ColorPercent;
+use logitech_led_sdk::DeviceType;
+use logitech_led_sdk::KeyName;
+use logitech_led_sdk::KeyboardBitmap;
+use logitech_led_sdk::TargetDevice;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::os::raw::c_int;
+use std::os::raw::c_uchar;
+
+/// Make a color from percentages, clamping them to 0-100.
+fn color(red: c_int, green: c_int, blue: c_int) -> ColorPercent {
+    let channel = |value: c_int| value.clamp(0, 100) as u8;
+    ColorPercent::new_percent(channel(red), channel(green), channel(blue))
+        .expect("clamped channels are valid percentages")
+}
+
+/// Get the key name with the value of a `LogiLed::KeyName`.
+fn key_name(key: c_int) -> Option<KeyName> {
+    KeyName::iter().find(|key_name| *key_name as c_int == key)
+}
+
+/// Get the device type with the value of a `LogiLed::DeviceType`.
+fn device_type(device: c_int) -> Option<DeviceType> {
+    DeviceType::iter().find(|device_type| *device_type as c_int == device)
+}
+
+/// Read a NUL-terminated UTF-16 string.
+///
+/// Invalid UTF-16 is replaced with the replacement character.
+unsafe fn read_wide(value: *const u16) -> Option<String> {
+    if value.is_null() {
+        return None;
+    }
+
+    let mut len = 0;
+    while *value.add(len) != 0 {
+        len += 1;
+    }
+
+    Some(String::from_utf16_lossy(std::slice::from_raw_parts(
+        value, len,
+    )))
+}
+
+/// Read a UTF-16 string from a buffer, stopping at the first NUL.
+unsafe fn read_wide_buffer(buffer: *const u16, len: c_int) -> Option<String> {
+    let len = usize::try_from(len).ok()?;
+    if buffer.is_null() || len == 0 {
+        return None;
+    }
+
+    let buffer = std::slice::from_raw_parts(buffer, len);
+    let len = buffer.iter().position(|c| *c == 0).unwrap_or(buffer.len());
+    Some(String::from_utf16_lossy(&buffer[..len]))
+}
+
+/// Write a string into a UTF-16 buffer, truncating it to leave room for a NUL.
+///
+/// # Returns
+/// Returns the number of code units written, not counting the NUL.
+unsafe fn write_wide_buffer(value: &str, buffer: *mut u16, len: usize) -> usize {
+    let buffer = std::slice::from_raw_parts_mut(buffer, len);
+    let mut written = 0;
+    for (slot, c) in buffer[..len - 1].iter_mut().zip(value.encode_utf16()) {
+        *slot = c;
+        written += 1;
+    }
+    buffer[written] = 0;
+
+    written
+}
+
+/// Write a value through a pointer, if it is not null.
+unsafe fn write<T>(pointer: *mut T, value: T) {
+    if let Some(pointer) = pointer.as_mut() {
+        *pointer = value;
+    }
+}
+
+pub unsafe fn init(shim: &Shim) -> bool {
+    shim.call(false, |backend| backend.init())
+}
+
+pub unsafe fn init_with_name(shim: &Shim, name: *const c_char) -> bool {
+    if name.is_null() {
+        return false;
+    }
+    let name = CStr::from_ptr(name);
+
+    shim.call(false, |backend| backend.init_with_name(name))
+}
+
+pub unsafe fn get_sdk_version(
+    shim: &Shim,
+    major: *mut c_int,
+    minor: *mut c_int,
+    build: *mut c_int,
+) -> bool {
+    match shim.call(None, |backend| backend.get_sdk_version()) {
+        Some((major_num, minor_num, build_num)) => {
+            write(major, major_num);
+            write(minor, minor_num);
+            write(build, build_num);
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn get_config_option_number(shim: &Shim, path: *const u16, value: *mut f64) -> bool {
+    let (path, default) = match (read_wide(path), value.as_mut()) {
+        (Some(path), Some(value)) => (path, *value),
+        _ => return false,
+    };
+
+    match shim.call(None, |backend| {
+        backend.get_config_option_number(&path, default)
+    }) {
+        Some(number) => {
+            *value = number;
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn get_config_option_bool(shim: &Shim, path: *const u16, value: *mut bool) -> bool {
+    let (path, default) = match (read_wide(path), value.as_mut()) {
+        (Some(path), Some(value)) => (path, *value),
+        _ => return false,
+    };
+
+    match shim.call(None, |backend| {
+        backend.get_config_option_bool(&path, default)
+    }) {
+        Some(enabled) => {
+            *value = enabled;
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn get_config_option_color(
+    shim: &Shim,
+    path: *const u16,
+    red: *mut c_int,
+    green: *mut c_int,
+    blue: *mut c_int,
+) -> bool {
+    let path = match read_wide(path) {
+        Some(path) if !red.is_null() && !green.is_null() && !blue.is_null() => path,
+        _ => return false,
+    };
+    let default = (*red, *green, *blue);
+
+    match shim.call(None, |backend| {
+        backend.get_config_option_color(&path, default)
+    }) {
+        Some((r, g, b)) => {
+            *red = r;
+            *green = g;
+            *blue = b;
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn get_config_option_rect(
+    shim: &Shim,
+    path: *const u16,
+    x: *mut c_int,
+    y: *mut c_int,
+    width: *mut c_int,
+    height: *mut c_int,
+) -> bool {
+    let path = match read_wide(path) {
+        Some(path) if !x.is_null() && !y.is_null() && !width.is_null() && !height.is_null() => path,
+        _ => return false,
+    };
+    let default = (*x, *y, *width, *height);
+
+    match shim.call(None, |backend| {
+        backend.get_config_option_rect(&path, default)
+    }) {
+        Some((rect_x, rect_y, rect_width, rect_height)) => {
+            *x = rect_x;
+            *y = rect_y;
+            *width = rect_width;
+            *height = rect_height;
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn get_config_option_string(
+    shim: &Shim,
+    path: *const u16,
+    value: *mut u16,
+    buffer_size: c_int,
+) -> bool {
+    let (path, default) = match (read_wide(path), read_wide_buffer(value, buffer_size)) {
+        (Some(path), Some(default)) => (path, default),
+        _ => return false,
+    };
+
+    match shim.call(None, |backend| {
+        backend.get_config_option_string(&path, &default)
+    }) {
+        Some(string) => {
+            write_wide_buffer(&string, value, buffer_size as usize);
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn get_config_option_key_input(
+    shim: &Shim,
+    path: *const u16,
+    value: *mut u16,
+    buffer_size: c_int,
+) -> bool {
+    let (path, default) = match (read_wide(path), read_wide_buffer(value, buffer_size)) {
+        (Some(path), Some(default)) => (path, default),
+        _ => return false,
+    };
+
+    match shim.call(None, |backend| {
+        backend.get_config_option_key_input(&path, &default)
+    }) {
+        Some(key_input) => {
+            write_wide_buffer(&key_input, value, buffer_size as usize);
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn get_config_option_select(
+    shim: &Shim,
+    path: *const u16,
+    value: *mut u16,
+    value_size: *mut c_int,
+    values: *const u16,
+    values_size: c_int,
+) -> bool {
+    let buffer_size = match value_size.as_ref() {
+        Some(value_size) => *value_size,
+        None => return false,
+    };
+    let (path, default) = match (read_wide(path), read_wide_buffer(value, buffer_size)) {
+        (Some(path), Some(default)) => (path, default),
+        _ => return false,
+    };
+
+    // The options are a list of NUL-terminated strings, terminated by an empty string.
+    let values_size = match usize::try_from(values_size) {
+        Ok(values_size) if !values.is_null() => values_size,
+        _ => return false,
+    };
+    let options: Vec<String> = std::slice::from_raw_parts(values, values_size)
+        .split(|c| *c == 0)
+        .take_while(|option| !option.is_empty())
+        .map(String::from_utf16_lossy)
+        .collect();
+    if !options.contains(&default) {
+        return false;
+    }
+    let options: Vec<&str> = options.iter().map(String::as_str).collect();
+
+    match shim.call(None, |backend| {
+        backend.get_config_option_select(&path, &default, &options)
+    }) {
+        Some(selected) => {
+            let written = write_wide_buffer(&selected, value, buffer_size as usize);
+            *value_size = written as c_int + 1;
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn get_config_option_range(
+    shim: &Shim,
+    path: *const u16,
+    value: *mut c_int,
+    min: c_int,
+    max: c_int,
+) -> bool {
+    let (path, default) = match (read_wide(path), value.as_mut()) {
+        (Some(path), Some(value)) if min <= max && (min..=max).contains(value) => (path, *value),
+        _ => return false,
+    };
+
+    match shim.call(None, |backend| {
+        backend.get_config_option_range(&path, default, min, max)
+    }) {
+        Some(range) => {
+            *value = range;
+            true
+        }
+        None => false,
+    }
+}
+
+pub unsafe fn set_config_option_label(shim: &Shim, path: *const u16, label: *mut u16) -> bool {
+    match (read_wide(path), read_wide(label)) {
+        (Some(path), Some(label)) => shim.call(false, |backend| {
+            backend.set_config_option_label(&path, &label)
+        }),
+        _ => false,
+    }
+}
+
+pub unsafe fn set_target_device(shim: &Shim, target_device: c_int) -> bool {
+    let target_device = TargetDevice::from_bits_truncate(target_device as u32);
+    shim.call(false, |backend| backend.set_target_device(target_device))
+}
+
+pub unsafe fn save_current_lighting(shim: &Shim) -> bool {
+    shim.call(false, |backend| backend.save_current_lighting())
+}
+
+pub unsafe fn set_lighting(shim: &Shim, red: c_int, green: c_int, blue: c_int) -> bool {
+    let color = color(red, green, blue);
+    shim.call(false, |backend| backend.set_lighting(color))
+}
+
+pub unsafe fn restore_lighting(shim: &Shim) -> bool {
+    shim.call(false, |backend| backend.restore_lighting())
+}
+
+pub unsafe fn flash_lighting(
+    shim: &Shim,
+    red: c_int,
+    green: c_int,
+    blue: c_int,
+    duration: c_int,
+    interval: c_int,
+) -> bool {
+    let color = color(red, green, blue);
+    shim.call(false, |backend| {
+        backend.flash_lighting(color, duration, interval)
+    })
+}
+
+pub unsafe fn pulse_lighting(
+    shim: &Shim,
+    red: c_int,
+    green: c_int,
+    blue: c_int,
+    duration: c_int,
+    interval: c_int,
+) -> bool {
+    let color = color(red, green, blue);
+    shim.call(false, |backend| {
+        backend.pulse_lighting(color, duration, interval)
+    })
+}
+
+pub unsafe fn stop_effects(shim: &Shim) -> bool {
+    shim.call(false, |backend| backend.stop_effects())
+}
+
+pub unsafe fn set_lighting_from_bitmap(shim: &Shim, bitmap: *mut c_uchar) -> bool {
+    if bitmap.is_null() {
+        return false;
+    }
+    const BITMAP_SIZE: usize = sys::LOGI_LED_BITMAP_SIZE as usize;
+    let mut data = [0; BITMAP_SIZE];
+    data.copy_from_slice(std::slice::from_raw_parts(bitmap, BITMAP_SIZE));
+    let bitmap = KeyboardBitmap::from_bgra_bytes(data);
+
+    shim.call(false, |backend| backend.set_lighting_from_bitmap(&bitmap))
+}
+
+pub unsafe fn set_lighting_for_key_with_scan_code(
+    shim: &Shim,
+    key_code: c_int,
+    red: c_int,
+    green: c_int,
+    blue: c_int,
+) -> bool {
+    let color = color(red, green, blue);
+    shim.call(false, |backend| {
+        backend.set_lighting_for_key_with_scan_code(key_code, color)
+    })
+}
+
+pub unsafe fn set_lighting_for_key_with_hid_code(
+    shim: &Shim,
+    key_code: c_int,
+    red: c_int,
+    green: c_int,
+    blue: c_int,
+) -> bool {
+    let color = color(red, green, blue);
+    shim.call(false, |backend| {
+        backend.set_lighting_for_key_with_hid_code(key_code, color)
+    })
+}
+
+pub unsafe fn set_lighting_for_key_with_quartz_code(
+    shim: &Shim,
+    key_code: c_int,
+    red: c_int,
+    green: c_int,
+    blue: c_int,
+) -> bool {
+    let color = color(red, green, blue);
+    shim.call(false, |backend| {
+        backend.set_lighting_for_key_with_quartz_code(key_code, color)
+    })
+}
+
+pub unsafe fn set_lighting_for_key_with_key_name(
+    shim: &Shim,
+    key: c_int,
+    red: c_int,
+    green: c_int,
+    blue: c_int,
+) -> bool {
+    let key = match key_name(key) {
+        Some(key) => key,
+        None => return false,
+    };
+    let color = color(red, green, blue);
+
+    shim.call(false, |backend| {
+        backend.set_lighting_for_key_with_key_name(key, color)
+    })
+}
+
+pub unsafe fn save_lighting_for_key(shim: &Shim, key: c_int) -> bool {
+    match key_name(key) {
+        Some(key) => shim.call(false, |backend| backend.save_lighting_for_key(key)),
+        None => false,
+    }
+}
+
+pub unsafe fn restore_lighting_for_key(shim: &Shim, key: c_int) -> bool {
+    match key_name(key) {
+        Some(key) => shim.call(false, |backend| backend.restore_lighting_for_key(key)),
+        None => false,
+    }
+}
+
+pub unsafe fn exclude_keys_from_bitmap(shim: &Shim, keys: *mut c_int, count: c_int) -> bool {
+    let count = match usize::try_from(count) {
+        Ok(count) => count,
+        Err(_) => return false,
+    };
+    let mut key_list: Vec<KeyName> = Vec::new();
+    if count > 0 {
+        if keys.is_null() {
+            return false;
+        }
+        for key in std::slice::from_raw_parts(keys, count) {
+            match key_name(*key) {
+                Some(key) if !key_list.contains(&key) => key_list.push(key),
+                Some(_) => {}
+                None => return false,
+            }
+        }
+    }
+
+    shim.call(false, |backend| backend.exclude_keys_from_bitmap(&key_list))
+}
+
+pub unsafe fn flash_single_key(
+    shim: &Shim,
+    key: c_int,
+    red: c_int,
+    green: c_int,
+    blue: c_int,
+    duration: c_int,
+    interval: c_int,
+) -> bool {
+    let key = match key_name(key) {
+        Some(key) => key,
+        None => return false,
+    };
+    let color = color(red, green, blue);
+
+    shim.call(false, |backend| {
+        backend.flash_single_key(key, color, duration, interval)
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn pulse_single_key(
+    shim: &Shim,
+    key: c_int,
+    start_red: c_int,
+    start_green: c_int,
+    start_blue: c_int,
+    end_red: c_int,
+    end_green: c_int,
+    end_blue: c_int,
+    duration: c_int,
+    is_infinite: bool,
+) -> bool {
+    let key = match key_name(key) {
+        Some(key) => key,
+        None => return false,
+    };
+    let start_color = color(start_red, start_green, start_blue);
+    let end_color = color(end_red, end_green, end_blue);
+
+    shim.call(false, |backend| {
+        backend.pulse_single_key(key, start_color, end_color, duration, is_infinite)
+    })
+}
+
+pub unsafe fn stop_effects_on_key(shim: &Shim, key: c_int) -> bool {
+    match key_name(key) {
+        Some(key) => shim.call(false, |backend| backend.stop_effects_on_key(key)),
+        None => false,
+    }
+}
+
+pub unsafe fn set_lighting_for_target_zone(
+    shim: &Shim,
+    device: c_int,
+    zone: c_int,
+    red: c_int,
+    green: c_int,
+    blue: c_int,
+) -> bool {
+    let device = match device_type(device) {
+        Some(device) => device,
+        None => return false,
+    };
+    let color = color(red, green, blue);
+
+    shim.call(false, |backend| {
+        backend.set_lighting_for_target_zone(device, zone, color)
+    })
+}
+
+pub unsafe fn shutdown(shim: &Shim) {
+    shim.call((), |backend| backend.shutdown())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn wide_strings() {
+        let wide: Vec<u16> = "lighting/ćolor\0".encode_utf16().collect();
+        assert_eq!(
+            unsafe { read_wide(wide.as_ptr()) }.as_deref(),
+            Some("lighting/ćolor")
+        );
+        assert_eq!(unsafe { read_wide(std::ptr::null()) }, None);
+
+        let mut buffer = [u16::from(b'x'); 4];
+        assert_eq!(
+            unsafe { read_wide_buffer(buffer.as_ptr(), 4) }.as_deref(),
+            Some("xxxx")
+        );
+        let written = unsafe { write_wide_buffer("abcdef", buffer.as_mut_ptr(), 4) };
+        assert_eq!(written, 3);
+        assert_eq!(
+            unsafe { read_wide_buffer(buffer.as_ptr(), 4) }.as_deref(),
+            Some("abc")
+        );
+        assert_eq!(buffer[3], 0);
+    }
+}
@@ -0,0 +1,104 @@
+//! A drop-in replacement for the Logitech LED library.
+//!
+//! Programs that use the Logitech LED SDK call the `LogiLed*` functions of its library.
+//! This crate builds a library that exports every one of those functions with the same signature,
+//! and sends each call to a Rust [`Backend`](logitech_led_sdk::Backend) instead,
+//! so another program's lighting can be recorded, remapped or composited with other lighting.
+//!
+//! On Windows, the functions are exported with their MSVC-mangled names, like the real library.
+//! Elsewhere, they are exported with their plain names.
+//!
+//! With the default `exports` feature, the library uses a [`LogBackend`].
+//! To use another backend, depend on this crate without default features in a `cdylib` crate,
+//! and call [`export!`] once with an expression that makes the backend:
+//! ```ignore
+//! logiled_shim::export!(MyBackend::new());
+//! ```
+
+mod log_backend;
+mod shim;
+
+#[doc(hidden)]
+pub mod exports;
+
+pub use self::log_backend::LogBackend;
+pub use self::log_backend::LOG_ENV_VAR;
+pub use self::shim::Shim;
+
+#[doc(hidden)]
+pub use logitech_led_sdk as __sdk;
+
+/// Export every `LogiLed*` function, sending calls to the backend made by an expression.
+///
+/// The expression is evaluated on the first call, and must make a type that implements
+/// [`Backend`](logitech_led_sdk::Backend) and [`Send`].
+/// Calls are made one at a time, and a panicking call fails instead of unwinding into the caller.
+///
+/// This must only be used once in a library.
+#[macro_export]
+macro_rules! export {
+    ($backend:expr) => {
+        static __LOGILED_SHIM: $crate::Shim = $crate::Shim::new(|| ::std::boxed::Box::new($backend));
+
+        $crate::__export_functions! {
+            __LOGILED_SHIM;
+            fn LogiLedInit() -> bool = init, "?LogiLedInit@@YA_NXZ", "?LogiLedInit@@YA_NXZ";
+            fn LogiLedInitWithName(name: *const ::std::os::raw::c_char) -> bool = init_with_name, "?LogiLedInitWithName@@YA_NQEBD@Z", "?LogiLedInitWithName@@YA_NQBD@Z";
+            fn LogiLedGetSdkVersion(major: *mut ::std::os::raw::c_int, minor: *mut ::std::os::raw::c_int, build: *mut ::std::os::raw::c_int) -> bool = get_sdk_version, "?LogiLedGetSdkVersion@@YA_NPEAH00@Z", "?LogiLedGetSdkVersion@@YA_NPAH00@Z";
+            fn LogiLedGetConfigOptionNumber(path: *const u16, value: *mut f64) -> bool = get_config_option_number, "?LogiLedGetConfigOptionNumber@@YA_NPEB_WPEAN@Z", "?LogiLedGetConfigOptionNumber@@YA_NPB_WPAN@Z";
+            fn LogiLedGetConfigOptionBool(path: *const u16, value: *mut bool) -> bool = get_config_option_bool, "?LogiLedGetConfigOptionBool@@YA_NPEB_WPEA_N@Z", "?LogiLedGetConfigOptionBool@@YA_NPB_WPA_N@Z";
+            fn LogiLedGetConfigOptionColor(path: *const u16, red: *mut ::std::os::raw::c_int, green: *mut ::std::os::raw::c_int, blue: *mut ::std::os::raw::c_int) -> bool = get_config_option_color, "?LogiLedGetConfigOptionColor@@YA_NPEB_WPEAH11@Z", "?LogiLedGetConfigOptionColor@@YA_NPB_WPAH11@Z";
+            fn LogiLedGetConfigOptionRect(path: *const u16, x: *mut ::std::os::raw::c_int, y: *mut ::std::os::raw::c_int, width: *mut ::std::os::raw::c_int, height: *mut ::std::os::raw::c_int) -> bool = get_config_option_rect, "?LogiLedGetConfigOptionRect@@YA_NPEB_WPEAH111@Z", "?LogiLedGetConfigOptionRect@@YA_NPB_WPAH111@Z";
+            fn LogiLedGetConfigOptionString(path: *const u16, value: *mut u16, buffer_size: ::std::os::raw::c_int) -> bool = get_config_option_string, "?LogiLedGetConfigOptionString@@YA_NPEB_WPEA_WH@Z", "?LogiLedGetConfigOptionString@@YA_NPB_WPA_WH@Z";
+            fn LogiLedGetConfigOptionKeyInput(path: *const u16, value: *mut u16, buffer_size: ::std::os::raw::c_int) -> bool = get_config_option_key_input, "?LogiLedGetConfigOptionKeyInput@@YA_NPEB_WPEA_WH@Z", "?LogiLedGetConfigOptionKeyInput@@YA_NPB_WPA_WH@Z";
+            fn LogiLedGetConfigOptionSelect(path: *const u16, value: *mut u16, value_size: *mut ::std::os::raw::c_int, values: *const u16, values_size: ::std::os::raw::c_int) -> bool = get_config_option_select, "?LogiLedGetConfigOptionSelect@@YA_NPEB_WPEA_WPEAH0H@Z", "?LogiLedGetConfigOptionSelect@@YA_NPB_WPA_WPAH0H@Z";
+            fn LogiLedGetConfigOptionRange(path: *const u16, value: *mut ::std::os::raw::c_int, min: ::std::os::raw::c_int, max: ::std::os::raw::c_int) -> bool = get_config_option_range, "?LogiLedGetConfigOptionRange@@YA_NPEB_WPEAHHH@Z", "?LogiLedGetConfigOptionRange@@YA_NPB_WPAHHH@Z";
+            fn LogiLedSetConfigOptionLabel(path: *const u16, label: *mut u16) -> bool = set_config_option_label, "?LogiLedSetConfigOptionLabel@@YA_NPEB_WPEA_W@Z", "?LogiLedSetConfigOptionLabel@@YA_NPB_WPA_W@Z";
+            fn LogiLedSetTargetDevice(target_device: ::std::os::raw::c_int) -> bool = set_target_device, "?LogiLedSetTargetDevice@@YA_NH@Z", "?LogiLedSetTargetDevice@@YA_NH@Z";
+            fn LogiLedSaveCurrentLighting() -> bool = save_current_lighting, "?LogiLedSaveCurrentLighting@@YA_NXZ", "?LogiLedSaveCurrentLighting@@YA_NXZ";
+            fn LogiLedSetLighting(red: ::std::os::raw::c_int, green: ::std::os::raw::c_int, blue: ::std::os::raw::c_int) -> bool = set_lighting, "?LogiLedSetLighting@@YA_NHHH@Z", "?LogiLedSetLighting@@YA_NHHH@Z";
+            fn LogiLedRestoreLighting() -> bool = restore_lighting, "?LogiLedRestoreLighting@@YA_NXZ", "?LogiLedRestoreLighting@@YA_NXZ";
+            fn LogiLedFlashLighting(red: ::std::os::raw::c_int, green: ::std::os::raw::c_int, blue: ::std::os::raw::c_int, duration: ::std::os::raw::c_int, interval: ::std::os::raw::c_int) -> bool = flash_lighting, "?LogiLedFlashLighting@@YA_NHHHHH@Z", "?LogiLedFlashLighting@@YA_NHHHHH@Z";
+            fn LogiLedPulseLighting(red: ::std::os::raw::c_int, green: ::std::os::raw::c_int, blue: ::std::os::raw::c_int, duration: ::std::os::raw::c_int, interval: ::std::os::raw::c_int) -> bool = pulse_lighting, "?LogiLedPulseLighting@@YA_NHHHHH@Z", "?LogiLedPulseLighting@@YA_NHHHHH@Z";
+            fn LogiLedStopEffects() -> bool = stop_effects, "?LogiLedStopEffects@@YA_NXZ", "?LogiLedStopEffects@@YA_NXZ";
+            fn LogiLedSetLightingFromBitmap(bitmap: *mut ::std::os::raw::c_uchar) -> bool = set_lighting_from_bitmap, "?LogiLedSetLightingFromBitmap@@YA_NQEAE@Z", "?LogiLedSetLightingFromBitmap@@YA_NQAE@Z";
+            fn LogiLedSetLightingForKeyWithScanCode(key_code: ::std::os::raw::c_int, red: ::std::os::raw::c_int, green: ::std::os::raw::c_int, blue: ::std::os::raw::c_int) -> bool = set_lighting_for_key_with_scan_code, "?LogiLedSetLightingForKeyWithScanCode@@YA_NHHHH@Z", "?LogiLedSetLightingForKeyWithScanCode@@YA_NHHHH@Z";
+            fn LogiLedSetLightingForKeyWithHidCode(key_code: ::std::os::raw::c_int, red: ::std::os::raw::c_int, green: ::std::os::raw::c_int, blue: ::std::os::raw::c_int) -> bool = set_lighting_for_key_with_hid_code, "?LogiLedSetLightingForKeyWithHidCode@@YA_NHHHH@Z", "?LogiLedSetLightingForKeyWithHidCode@@YA_NHHHH@Z";
+            fn LogiLedSetLightingForKeyWithQuartzCode(key_code: ::std::os::raw::c_int, red: ::std::os::raw::c_int, green: ::std::os::raw::c_int, blue: ::std::os::raw::c_int) -> bool = set_lighting_for_key_with_quartz_code, "?LogiLedSetLightingForKeyWithQuartzCode@@YA_NHHHH@Z", "?LogiLedSetLightingForKeyWithQuartzCode@@YA_NHHHH@Z";
+            fn LogiLedSetLightingForKeyWithKeyName(key: ::std::os::raw::c_int, red: ::std::os::raw::c_int, green: ::std::os::raw::c_int, blue: ::std::os::raw::c_int) -> bool = set_lighting_for_key_with_key_name, "?LogiLedSetLightingForKeyWithKeyName@@YA_NW4KeyName@LogiLed@@HHH@Z", "?LogiLedSetLightingForKeyWithKeyName@@YA_NW4KeyName@LogiLed@@HHH@Z";
+            fn LogiLedSaveLightingForKey(key: ::std::os::raw::c_int) -> bool = save_lighting_for_key, "?LogiLedSaveLightingForKey@@YA_NW4KeyName@LogiLed@@@Z", "?LogiLedSaveLightingForKey@@YA_NW4KeyName@LogiLed@@@Z";
+            fn LogiLedRestoreLightingForKey(key: ::std::os::raw::c_int) -> bool = restore_lighting_for_key, "?LogiLedRestoreLightingForKey@@YA_NW4KeyName@LogiLed@@@Z", "?LogiLedRestoreLightingForKey@@YA_NW4KeyName@LogiLed@@@Z";
+            fn LogiLedExcludeKeysFromBitmap(keys: *mut ::std::os::raw::c_int, count: ::std::os::raw::c_int) -> bool = exclude_keys_from_bitmap, "?LogiLedExcludeKeysFromBitmap@@YA_NPEAW4KeyName@LogiLed@@H@Z", "?LogiLedExcludeKeysFromBitmap@@YA_NPAW4KeyName@LogiLed@@H@Z";
+            fn LogiLedFlashSingleKey(key: ::std::os::raw::c_int, red: ::std::os::raw::c_int, green: ::std::os::raw::c_int, blue: ::std::os::raw::c_int, duration: ::std::os::raw::c_int, interval: ::std::os::raw::c_int) -> bool = flash_single_key, "?LogiLedFlashSingleKey@@YA_NW4KeyName@LogiLed@@HHHHH@Z", "?LogiLedFlashSingleKey@@YA_NW4KeyName@LogiLed@@HHHHH@Z";
+            fn LogiLedPulseSingleKey(key: ::std::os::raw::c_int, start_red: ::std::os::raw::c_int, start_green: ::std::os::raw::c_int, start_blue: ::std::os::raw::c_int, end_red: ::std::os::raw::c_int, end_green: ::std::os::raw::c_int, end_blue: ::std::os::raw::c_int, duration: ::std::os::raw::c_int, is_infinite: bool) -> bool = pulse_single_key, "?LogiLedPulseSingleKey@@YA_NW4KeyName@LogiLed@@HHHHHHH_N@Z", "?LogiLedPulseSingleKey@@YA_NW4KeyName@LogiLed@@HHHHHHH_N@Z";
+            fn LogiLedStopEffectsOnKey(key: ::std::os::raw::c_int) -> bool = stop_effects_on_key, "?LogiLedStopEffectsOnKey@@YA_NW4KeyName@LogiLed@@@Z", "?LogiLedStopEffectsOnKey@@YA_NW4KeyName@LogiLed@@@Z";
+            fn LogiLedSetLightingForTargetZone(device: ::std::os::raw::c_int, zone: ::std::os::raw::c_int, red: ::std::os::raw::c_int, green: ::std::os::raw::c_int, blue: ::std::os::raw::c_int) -> bool = set_lighting_for_target_zone, "?LogiLedSetLightingForTargetZone@@YA_NW4DeviceType@LogiLed@@HHHH@Z", "?LogiLedSetLightingForTargetZone@@YA_NW4DeviceType@LogiLed@@HHHH@Z";
+            fn LogiLedShutdown() = shutdown, "?LogiLedShutdown@@YAXXZ", "?LogiLedShutdown@@YAXXZ";
+        }
+    };
+}
+
+/// Define the exported functions, each calling the function of the same name in [`exports`].
+///
+/// Key names and device types are taken as `c_int`s, which have the same layout as the sdk's enums,
+/// so values that are not valid enum variants can be rejected instead of being undefined behavior.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __export_functions {
+    ($shim:ident; $(fn $name:ident($($arg:ident: $ty:ty),*) $(-> $ret:ty)? = $function:ident, $x86_64:literal, $x86:literal;)*) => {
+        $(
+            /// # Safety
+            /// Pointers must be valid for the sdk function of the same name.
+            #[cfg_attr(not(target_os = "windows"), no_mangle)]
+            #[cfg_attr(all(target_os = "windows", not(target_arch = "x86")), export_name = $x86_64)]
+            #[cfg_attr(all(target_os = "windows", target_arch = "x86"), export_name = $x86)]
+            #[allow(non_snake_case, clippy::too_many_arguments)]
+            pub unsafe extern "C" fn $name($($arg: $ty),*) $(-> $ret)? {
+                $crate::exports::$function(&$shim, $($arg),*)
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "exports")]
+export!(LogBackend::from_env());
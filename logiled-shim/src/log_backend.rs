@@ -0,0 +1,319 @@
+use logitech_led_sdk::Backend;
+use logitech_led_sdk::ColorPercent;
+use logitech_led_sdk::DeviceType;
+use logitech_led_sdk::KeyName;
+use logitech_led_sdk::KeyboardBitmap;
+use logitech_led_sdk::TargetDevice;
+use std::ffi::CStr;
+use std::io::Write;
+use std::os::raw::c_int;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+/// The environment variable with the path of the file that [`LogBackend::from_env`] appends calls to.
+pub const LOG_ENV_VAR: &str = "LOGILED_SHIM_LOG";
+
+/// The sdk version that a [`LogBackend`] reports.
+const SDK_VERSION: (c_int, c_int, c_int) = (9, 0, 0);
+
+/// A backend that accepts every call, optionally writing each one as a line of text.
+///
+/// Config options always have their default values.
+/// Lines look like `LogiLedSetLighting(ColorPercent { r: 100, g: 0, b: 0 })`.
+pub struct LogBackend {
+    log: Option<Mutex<Box<dyn Write + Send>>>,
+}
+
+impl LogBackend {
+    /// Create a backend that does not write calls anywhere.
+    pub fn new() -> Self {
+        Self { log: None }
+    }
+
+    /// Create a backend that writes calls to a writer.
+    pub fn with_log<W>(log: W) -> Self
+    where
+        W: Write + Send + 'static,
+    {
+        Self {
+            log: Some(Mutex::new(Box::new(log))),
+        }
+    }
+
+    /// Create a backend that appends calls to the file named by [`LOG_ENV_VAR`].
+    ///
+    /// If the variable is not set or the file could not be opened, calls are not written anywhere.
+    pub fn from_env() -> Self {
+        let file = std::env::var_os(LOG_ENV_VAR).and_then(|path| {
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .ok()
+        });
+
+        match file {
+            Some(file) => Self::with_log(file),
+            None => Self::new(),
+        }
+    }
+
+    /// Write a call.
+    ///
+    /// Failing to write does not fail the call.
+    fn record(&self, call: std::fmt::Arguments<'_>) {
+        if let Some(log) = self.log.as_ref() {
+            let mut log = log.lock().unwrap_or_else(PoisonError::into_inner);
+            let _ = writeln!(log, "{call}").and_then(|_| log.flush());
+        }
+    }
+}
+
+impl Default for LogBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Backend for LogBackend {
+    fn init(&self) -> bool {
+        self.record(format_args!("LogiLedInit()"));
+        true
+    }
+
+    fn init_with_name(&self, name: &CStr) -> bool {
+        self.record(format_args!("LogiLedInitWithName({name:?})"));
+        true
+    }
+
+    fn get_sdk_version(&self) -> Option<(c_int, c_int, c_int)> {
+        self.record(format_args!("LogiLedGetSdkVersion()"));
+        Some(SDK_VERSION)
+    }
+
+    fn get_config_option_number(&self, path: &str, default: f64) -> Option<f64> {
+        self.record(format_args!(
+            "LogiLedGetConfigOptionNumber({path:?}, {default:?})"
+        ));
+        Some(default)
+    }
+
+    fn get_config_option_bool(&self, path: &str, default: bool) -> Option<bool> {
+        self.record(format_args!(
+            "LogiLedGetConfigOptionBool({path:?}, {default:?})"
+        ));
+        Some(default)
+    }
+
+    fn get_config_option_color(
+        &self,
+        path: &str,
+        default: (c_int, c_int, c_int),
+    ) -> Option<(c_int, c_int, c_int)> {
+        self.record(format_args!(
+            "LogiLedGetConfigOptionColor({path:?}, {default:?})"
+        ));
+        Some(default)
+    }
+
+    fn get_config_option_rect(
+        &self,
+        path: &str,
+        default: (c_int, c_int, c_int, c_int),
+    ) -> Option<(c_int, c_int, c_int, c_int)> {
+        self.record(format_args!(
+            "LogiLedGetConfigOptionRect({path:?}, {default:?})"
+        ));
+        Some(default)
+    }
+
+    fn get_config_option_string(&self, path: &str, default: &str) -> Option<String> {
+        self.record(format_args!(
+            "LogiLedGetConfigOptionString({path:?}, {default:?})"
+        ));
+        Some(default.into())
+    }
+
+    fn get_config_option_key_input(&self, path: &str, default: &str) -> Option<String> {
+        self.record(format_args!(
+            "LogiLedGetConfigOptionKeyInput({path:?}, {default:?})"
+        ));
+        Some(default.into())
+    }
+
+    fn get_config_option_select(
+        &self,
+        path: &str,
+        default: &str,
+        options: &[&str],
+    ) -> Option<String> {
+        self.record(format_args!(
+            "LogiLedGetConfigOptionSelect({path:?}, {default:?}, {options:?})"
+        ));
+        Some(default.into())
+    }
+
+    fn get_config_option_range(
+        &self,
+        path: &str,
+        default: c_int,
+        min: c_int,
+        max: c_int,
+    ) -> Option<c_int> {
+        self.record(format_args!(
+            "LogiLedGetConfigOptionRange({path:?}, {default}, {min}, {max})"
+        ));
+        Some(default)
+    }
+
+    fn set_config_option_label(&self, path: &str, label: &str) -> bool {
+        self.record(format_args!(
+            "LogiLedSetConfigOptionLabel({path:?}, {label:?})"
+        ));
+        true
+    }
+
+    fn set_target_device(&self, target_device: TargetDevice) -> bool {
+        self.record(format_args!("LogiLedSetTargetDevice({target_device:?})"));
+        true
+    }
+
+    fn save_current_lighting(&self) -> bool {
+        self.record(format_args!("LogiLedSaveCurrentLighting()"));
+        true
+    }
+
+    fn set_lighting(&self, color: ColorPercent) -> bool {
+        self.record(format_args!("LogiLedSetLighting({color:?})"));
+        true
+    }
+
+    fn restore_lighting(&self) -> bool {
+        self.record(format_args!("LogiLedRestoreLighting()"));
+        true
+    }
+
+    fn flash_lighting(&self, color: ColorPercent, duration: c_int, interval: c_int) -> bool {
+        self.record(format_args!(
+            "LogiLedFlashLighting({color:?}, {duration}, {interval})"
+        ));
+        true
+    }
+
+    fn pulse_lighting(&self, color: ColorPercent, duration: c_int, interval: c_int) -> bool {
+        self.record(format_args!(
+            "LogiLedPulseLighting({color:?}, {duration}, {interval})"
+        ));
+        true
+    }
+
+    fn stop_effects(&self) -> bool {
+        self.record(format_args!("LogiLedStopEffects()"));
+        true
+    }
+
+    fn set_lighting_from_bitmap(&self, bitmap: &KeyboardBitmap) -> bool {
+        // Only the keys that are lit are written, to keep lines short.
+        let keys: Vec<(KeyName, ColorPercent)> = KeyName::iter()
+            .filter_map(|key| Some((key, bitmap.get_key(key)?)))
+            .filter(|(_, color)| *color != ColorPercent::BLACK)
+            .collect();
+        self.record(format_args!("LogiLedSetLightingFromBitmap({keys:?})"));
+        true
+    }
+
+    fn set_lighting_for_key_with_scan_code(&self, scan_code: c_int, color: ColorPercent) -> bool {
+        self.record(format_args!(
+            "LogiLedSetLightingForKeyWithScanCode({scan_code}, {color:?})"
+        ));
+        true
+    }
+
+    fn set_lighting_for_key_with_hid_code(&self, hid_code: c_int, color: ColorPercent) -> bool {
+        self.record(format_args!(
+            "LogiLedSetLightingForKeyWithHidCode({hid_code}, {color:?})"
+        ));
+        true
+    }
+
+    fn set_lighting_for_key_with_quartz_code(
+        &self,
+        quartz_code: c_int,
+        color: ColorPercent,
+    ) -> bool {
+        self.record(format_args!(
+            "LogiLedSetLightingForKeyWithQuartzCode({quartz_code}, {color:?})"
+        ));
+        true
+    }
+
+    fn set_lighting_for_key_with_key_name(&self, key: KeyName, color: ColorPercent) -> bool {
+        self.record(format_args!(
+            "LogiLedSetLightingForKeyWithKeyName({key:?}, {color:?})"
+        ));
+        true
+    }
+
+    fn save_lighting_for_key(&self, key: KeyName) -> bool {
+        self.record(format_args!("LogiLedSaveLightingForKey({key:?})"));
+        true
+    }
+
+    fn restore_lighting_for_key(&self, key: KeyName) -> bool {
+        self.record(format_args!("LogiLedRestoreLightingForKey({key:?})"));
+        true
+    }
+
+    fn exclude_keys_from_bitmap(&self, keys: &[KeyName]) -> bool {
+        self.record(format_args!("LogiLedExcludeKeysFromBitmap({keys:?})"));
+        true
+    }
+
+    fn flash_single_key(
+        &self,
+        key: KeyName,
+        color: ColorPercent,
+        duration: c_int,
+        interval: c_int,
+    ) -> bool {
+        self.record(format_args!(
+            "LogiLedFlashSingleKey({key:?}, {color:?}, {duration}, {interval})"
+        ));
+        true
+    }
+
+    fn pulse_single_key(
+        &self,
+        key: KeyName,
+        start_color: ColorPercent,
+        end_color: ColorPercent,
+        duration: c_int,
+        is_infinite: bool,
+    ) -> bool {
+        self.record(format_args!(
+            "LogiLedPulseSingleKey({key:?}, {start_color:?}, {end_color:?}, {duration}, {is_infinite})"
+        ));
+        true
+    }
+
+    fn stop_effects_on_key(&self, key: KeyName) -> bool {
+        self.record(format_args!("LogiLedStopEffectsOnKey({key:?})"));
+        true
+    }
+
+    fn set_lighting_for_target_zone(
+        &self,
+        device: DeviceType,
+        zone: c_int,
+        color: ColorPercent,
+    ) -> bool {
+        self.record(format_args!(
+            "LogiLedSetLightingForTargetZone({device:?}, {zone}, {color:?})"
+        ));
+        true
+    }
+
+    fn shutdown(&self) {
+        self.record(format_args!("LogiLedShutdown()"));
+    }
+}
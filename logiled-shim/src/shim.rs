@@ -0,0 +1,57 @@
+use logitech_led_sdk::Backend;
+use std::panic::AssertUnwindSafe;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+/// The backend of the exported functions, made on the first call.
+///
+/// This is used by [`export!`](crate::export), and is not needed otherwise.
+pub struct Shim {
+    make_backend: fn() -> Box<dyn Backend + Send>,
+    backend: Mutex<Option<Box<dyn Backend + Send>>>,
+}
+
+impl Shim {
+    /// Create a shim that makes its backend with a function.
+    pub const fn new(make_backend: fn() -> Box<dyn Backend + Send>) -> Self {
+        Self {
+            make_backend,
+            backend: Mutex::new(None),
+        }
+    }
+
+    /// Call the backend, making it first if needed.
+    ///
+    /// # Returns
+    /// Returns `failed` if the call panicked.
+    pub fn call<F, R>(&self, failed: R, f: F) -> R
+    where
+        F: FnOnce(&dyn Backend) -> R,
+    {
+        let mut backend = self.backend.lock().unwrap_or_else(PoisonError::into_inner);
+
+        // Unwinding into a foreign caller is undefined behavior.
+        std::panic::catch_unwind(AssertUnwindSafe(|| {
+            let backend = backend.get_or_insert_with(self.make_backend);
+            f(&**backend)
+        }))
+        .unwrap_or(failed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use logitech_led_sdk::MockBackend;
+
+    #[test]
+    fn call() {
+        static SHIM: Shim = Shim::new(|| Box::new(MockBackend::new()));
+
+        assert!(SHIM.call(false, |backend| backend.init()));
+        assert!(SHIM.call(true, |_| panic!("backend panicked")));
+
+        // The backend is kept between calls, even after a panic.
+        assert!(SHIM.call(false, |backend| backend.stop_effects()));
+    }
+}
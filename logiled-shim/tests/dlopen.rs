@@ -0,0 +1,119 @@
+use logitech_led_sdk::sys::dynamic::Library;
+use logitech_led_sdk::ColorPercent;
+use logitech_led_sdk::DeviceType;
+use logitech_led_sdk::KeyName;
+use logitech_led_sdk::KeyboardBitmap;
+use std::path::PathBuf;
+
+/// Get the path of the shim library that cargo built next to the test binary.
+///
+/// `cargo test` only writes the library to the `deps` directory, not to the target directory like `cargo build`.
+fn shim_path() -> PathBuf {
+    let exe = std::env::current_exe().expect("failed to get test binary path");
+    let deps_dir = exe.parent().expect("failed to get deps directory");
+
+    deps_dir.join(format!(
+        "{}logiled_shim{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_SUFFIX
+    ))
+}
+
+/// Encode a string as NUL-terminated UTF-16.
+fn wide(value: &str) -> Vec<u16> {
+    value.encode_utf16().chain([0]).collect()
+}
+
+#[test]
+fn dlopen() {
+    let log_path =
+        std::env::temp_dir().join(format!("logiled-shim-test-{}.log", std::process::id()));
+    let _ = std::fs::remove_file(&log_path);
+    std::env::set_var(logiled_shim::LOG_ENV_VAR, &log_path);
+
+    let library = unsafe { Library::open(shim_path()) }.expect("failed to load the shim");
+    unsafe {
+        assert!((library.LogiLedInitWithName)(c"game".as_ptr()));
+
+        let (mut major, mut minor, mut build) = (0, 0, 0);
+        assert!((library.LogiLedGetSdkVersion)(
+            &mut major, &mut minor, &mut build
+        ));
+        assert_eq!((major, minor, build), (9, 0, 0));
+
+        // Config options keep their defaults.
+        let path = wide("lighting/name");
+        let mut value = wide("Test");
+        value.resize(32, 0);
+        assert!((library.LogiLedGetConfigOptionString)(
+            path.as_ptr(),
+            value.as_mut_ptr(),
+            32
+        ));
+        assert_eq!(value[..5], wide("Test"));
+
+        let path = wide("lighting/mode");
+        let mut value = wide("Flash");
+        let mut value_size = value.len() as i32;
+        let values: Vec<u16> = ["Solid", "Flash"]
+            .iter()
+            .flat_map(|option| wide(option))
+            .chain([0])
+            .collect();
+        assert!((library.LogiLedGetConfigOptionSelect)(
+            path.as_ptr(),
+            value.as_mut_ptr(),
+            &mut value_size,
+            values.as_ptr(),
+            values.len() as i32
+        ));
+        assert_eq!(value_size, 6);
+
+        assert!((library.LogiLedSetLighting)(100, 0, 0));
+        assert!((library.LogiLedSetLightingForKeyWithKeyName)(
+            KeyName::W,
+            0,
+            0,
+            100
+        ));
+
+        let mut bitmap = KeyboardBitmap::new();
+        assert!(bitmap.set_key(KeyName::ESC, ColorPercent::GREEN));
+        let mut bitmap = *bitmap.as_bgra_bytes();
+        assert!((library.LogiLedSetLightingFromBitmap)(bitmap.as_mut_ptr()));
+
+        let mut keys = [KeyName::W, KeyName::A, KeyName::W];
+        assert!((library.LogiLedExcludeKeysFromBitmap)(
+            keys.as_mut_ptr(),
+            keys.len() as i32
+        ));
+
+        assert!((library.LogiLedSetLightingForTargetZone)(
+            DeviceType::Mouse,
+            1,
+            0,
+            100,
+            0
+        ));
+        (library.LogiLedShutdown)();
+    }
+    drop(library);
+
+    let log = std::fs::read_to_string(&log_path).expect("failed to read log");
+    let _ = std::fs::remove_file(&log_path);
+    assert_eq!(
+        log.lines().collect::<Vec<_>>(),
+        [
+            "LogiLedInitWithName(\"game\")",
+            "LogiLedGetSdkVersion()",
+            "LogiLedGetConfigOptionString(\"lighting/name\", \"Test\")",
+            "LogiLedGetConfigOptionSelect(\"lighting/mode\", \"Flash\", [\"Solid\", \"Flash\"])",
+            "LogiLedSetLighting(ColorPercent { r: 100, g: 0, b: 0 })",
+            "LogiLedSetLightingForKeyWithKeyName(W, ColorPercent { r: 0, g: 0, b: 100 })",
+            "LogiLedSetLightingFromBitmap([(ESC, ColorPercent { r: 0, g: 100, b: 0 })])",
+            "LogiLedExcludeKeysFromBitmap([W, A])",
+            "LogiLedSetLightingForTargetZone(Mouse, 1, ColorPercent { r: 0, g: 100, b: 0 })",
+            "LogiLedShutdown()",
+        ]
+    );
+}
@@ -0,0 +1,777 @@
+use anyhow::bail;
+use anyhow::Context;
+use argh::FromArgs;
+use logitech_led_sdk::import::AspectMode;
+use logitech_led_sdk::import::ImageImporter;
+use logitech_led_sdk::import::ResizeFilter;
+use logitech_led_sdk::Animator;
+use logitech_led_sdk::Backend;
+use logitech_led_sdk::ColorPercent;
+use logitech_led_sdk::DeviceType;
+use logitech_led_sdk::KeyName;
+use logitech_led_sdk::MockBackend;
+use logitech_led_sdk::NativeBackend;
+use logitech_led_sdk::Profile;
+use logitech_led_sdk::RecordingSdk;
+use logitech_led_sdk::Replayer;
+use logitech_led_sdk::Sdk;
+use logitech_led_sdk::SimulatorBackend;
+use logitech_led_sdk::SimulatorDisplay;
+use logitech_led_sdk::Trace;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// The name that the tool initializes the sdk with.
+const APP_NAME: &str = "logiled";
+
+#[derive(Debug, FromArgs)]
+#[argh(description = "Control Logitech LED lighting.")]
+struct Options {
+    #[argh(
+        switch,
+        description = "print the sdk calls that would be made as a trace instead of making them"
+    )]
+    dry_run: bool,
+
+    #[argh(
+        option,
+        description = "how long to keep the lighting before exiting, in milliseconds; by default, lighting is kept until interrupted"
+    )]
+    hold: Option<u64>,
+
+    #[argh(option, description = "write every sdk call to a trace file")]
+    record: Option<PathBuf>,
+
+    #[argh(
+        switch,
+        description = "draw the lighting in the terminal instead of sending it to devices"
+    )]
+    simulate: bool,
+
+    #[argh(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand)]
+enum Command {
+    Version(VersionCommand),
+    Set(SetCommand),
+    Key(KeyCommand),
+    Zone(ZoneCommand),
+    Flash(FlashCommand),
+    Pulse(PulseCommand),
+    Stop(StopCommand),
+    Apply(ApplyCommand),
+    Replay(ReplayCommand),
+    Image(ImageCommand),
+}
+
+#[derive(Debug, FromArgs)]
+#[argh(
+    subcommand,
+    name = "version",
+    description = "print the version of the sdk"
+)]
+struct VersionCommand {}
+
+#[derive(Debug, FromArgs)]
+#[argh(
+    subcommand,
+    name = "set",
+    description = "set the color of every device"
+)]
+struct SetCommand {
+    #[argh(positional, description = "the color, like \"#ff8800\" or \"red\"")]
+    color: ColorPercent,
+}
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "key", description = "set the color of a key")]
+struct KeyCommand {
+    #[argh(
+        positional,
+        from_str_fn(parse_key),
+        description = "the key, like \"W\" or \"NUM_ENTER\""
+    )]
+    key: KeyName,
+
+    #[argh(positional, description = "the color, like \"#ff8800\" or \"red\"")]
+    color: ColorPercent,
+}
+
+#[derive(Debug, FromArgs)]
+#[argh(
+    subcommand,
+    name = "zone",
+    description = "set the color of a zone on a device"
+)]
+struct ZoneCommand {
+    #[argh(
+        positional,
+        from_str_fn(parse_device),
+        description = "the device type, like \"Mouse\" or \"Headset\""
+    )]
+    device: DeviceType,
+
+    #[argh(positional, description = "the zone, starting from 0")]
+    zone: u32,
+
+    #[argh(positional, description = "the color, like \"#ff8800\" or \"red\"")]
+    color: ColorPercent,
+}
+
+#[derive(Debug, FromArgs)]
+#[argh(
+    subcommand,
+    name = "flash",
+    description = "flash every device, or a single key"
+)]
+struct FlashCommand {
+    #[argh(positional, description = "the color, like \"#ff8800\" or \"red\"")]
+    color: ColorPercent,
+
+    #[argh(option, from_str_fn(parse_key), description = "flash only this key")]
+    key: Option<KeyName>,
+
+    #[argh(
+        option,
+        description = "how long to flash for, in milliseconds; flashes until interrupted if missing"
+    )]
+    duration: Option<u64>,
+
+    #[argh(
+        option,
+        default = "500",
+        description = "the time between flashes, in milliseconds"
+    )]
+    interval: u64,
+}
+
+#[derive(Debug, FromArgs)]
+#[argh(
+    subcommand,
+    name = "pulse",
+    description = "pulse every device, or a single key"
+)]
+struct PulseCommand {
+    #[argh(positional, description = "the color, like \"#ff8800\" or \"red\"")]
+    color: ColorPercent,
+
+    #[argh(option, from_str_fn(parse_key), description = "pulse only this key")]
+    key: Option<KeyName>,
+
+    #[argh(
+        option,
+        default = "ColorPercent::BLACK",
+        description = "the color that a single key pulses to, black by default"
+    )]
+    to: ColorPercent,
+
+    #[argh(
+        option,
+        description = "how long to pulse for, in milliseconds; pulses until interrupted if missing"
+    )]
+    duration: Option<u64>,
+
+    #[argh(
+        option,
+        default = "1000",
+        description = "the time between pulses, in milliseconds"
+    )]
+    interval: u64,
+}
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "stop", description = "stop all effects")]
+struct StopCommand {}
+
+#[derive(Debug, FromArgs)]
+#[argh(
+    subcommand,
+    name = "apply",
+    description = "apply a TOML or JSON lighting profile"
+)]
+struct ApplyCommand {
+    #[argh(positional, description = "the path to the profile")]
+    path: PathBuf,
+}
+
+#[derive(Debug, FromArgs)]
+#[argh(
+    subcommand,
+    name = "replay",
+    description = "make the sdk calls in a trace file again"
+)]
+struct ReplayCommand {
+    #[argh(positional, description = "the path to the trace")]
+    path: PathBuf,
+
+    #[argh(
+        option,
+        default = "1.0",
+        description = "how fast to replay the trace, where 2 is twice as fast and inf is as fast as possible"
+    )]
+    speed: f64,
+}
+
+#[derive(Debug, FromArgs)]
+#[argh(
+    subcommand,
+    name = "image",
+    description = "show a PNG, JPEG or GIF image on the keyboard, playing animated GIFs"
+)]
+struct ImageCommand {
+    #[argh(positional, description = "the path to the image")]
+    path: PathBuf,
+
+    #[argh(
+        option,
+        from_str_fn(parse_filter),
+        default = "ResizeFilter::default()",
+        description = "how to resample the image: nearest, triangle, catmull-rom, gaussian or lanczos3; triangle by default"
+    )]
+    filter: ResizeFilter,
+
+    #[argh(
+        option,
+        from_str_fn(parse_aspect_mode),
+        default = "AspectMode::default()",
+        description = "how to fit the image to the keyboard: stretch, fit or fill; fill by default"
+    )]
+    aspect: AspectMode,
+
+    #[argh(
+        option,
+        description = "how many times to play an animated GIF; by default, it loops until interrupted, or plays once with --dry-run"
+    )]
+    loops: Option<u32>,
+}
+
+/// The sdk that commands run on.
+enum ToolSdk {
+    Sdk(Sdk),
+
+    /// An sdk that writes its calls to a trace file.
+    Recording(RecordingSdk),
+}
+
+impl ToolSdk {
+    /// Initialize the sdk with a backend, recording its calls to a file if there is a path.
+    fn new<B>(backend: B, record: Option<&Path>) -> anyhow::Result<Self>
+    where
+        B: Backend + 'static,
+    {
+        match record {
+            Some(path) => {
+                let trace = create_trace(path)?;
+                let sdk = RecordingSdk::new_with_backend_and_name(backend, APP_NAME, trace)
+                    .context("failed to initialize the sdk")?;
+                Ok(Self::Recording(sdk))
+            }
+            None => {
+                let sdk = Sdk::new_with_backend_and_name(backend, APP_NAME)
+                    .context("failed to initialize the sdk")?;
+                Ok(Self::Sdk(sdk))
+            }
+        }
+    }
+
+    fn sdk(&self) -> &Sdk {
+        match self {
+            Self::Sdk(sdk) => sdk,
+            Self::Recording(sdk) => sdk,
+        }
+    }
+
+    /// Shut down the sdk.
+    ///
+    /// # Errors
+    /// Returns an error if the trace could not be written.
+    fn shutdown(self) -> anyhow::Result<()> {
+        match self {
+            Self::Sdk(sdk) => drop(sdk),
+            Self::Recording(sdk) => sdk.finish().context("failed to write the trace")?,
+        }
+
+        Ok(())
+    }
+}
+
+/// A writer that writes everything to two writers.
+struct Tee<A, B>(A, B);
+
+impl<A, B> Write for Tee<A, B>
+where
+    A: Write,
+    B: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write_all(buf)?;
+        self.1.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()?;
+        self.1.flush()
+    }
+}
+
+/// How long to keep the sdk open after a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Hold {
+    /// Exit right away.
+    Exit,
+
+    /// Wait for an effect to finish.
+    For(Duration),
+
+    /// Wait until interrupted.
+    Forever,
+}
+
+/// Create a trace file.
+fn create_trace(path: &Path) -> anyhow::Result<File> {
+    File::create(path).with_context(|| format!("failed to create \"{}\"", path.display()))
+}
+
+fn parse_key(value: &str) -> Result<KeyName, String> {
+    KeyName::from_name(value).ok_or_else(|| format!("\"{value}\" is not a key name"))
+}
+
+fn parse_device(value: &str) -> Result<DeviceType, String> {
+    DeviceType::from_name(value).ok_or_else(|| {
+        let names: Vec<_> = DeviceType::iter().map(|device| device.name()).collect();
+        format!(
+            "\"{value}\" is not a device type, expected one of {}",
+            names.join(", ")
+        )
+    })
+}
+
+fn parse_filter(value: &str) -> Result<ResizeFilter, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "nearest" => Ok(ResizeFilter::Nearest),
+        "triangle" => Ok(ResizeFilter::Triangle),
+        "catmull-rom" => Ok(ResizeFilter::CatmullRom),
+        "gaussian" => Ok(ResizeFilter::Gaussian),
+        "lanczos3" => Ok(ResizeFilter::Lanczos3),
+        _ => Err(format!(
+            "\"{value}\" is not a filter, expected one of nearest, triangle, catmull-rom, gaussian, lanczos3"
+        )),
+    }
+}
+
+fn parse_aspect_mode(value: &str) -> Result<AspectMode, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "stretch" => Ok(AspectMode::Stretch),
+        "fit" => Ok(AspectMode::Fit),
+        "fill" => Ok(AspectMode::Fill),
+        _ => Err(format!(
+            "\"{value}\" is not an aspect mode, expected one of stretch, fit, fill"
+        )),
+    }
+}
+
+/// Run a command.
+///
+/// # Returns
+/// Returns how long to keep the sdk open so the lighting stays visible.
+fn run(command: &Command, sdk: &Sdk) -> anyhow::Result<Hold> {
+    match command {
+        Command::Version(_) => {
+            let (major, minor, build) = sdk.get_version().context("failed to get the version")?;
+            println!("{major}.{minor}.{build}");
+
+            Ok(Hold::Exit)
+        }
+        Command::Set(command) => {
+            sdk.set_lighting(command.color)
+                .context("failed to set the lighting")?;
+
+            Ok(Hold::Forever)
+        }
+        Command::Key(command) => {
+            sdk.set_lighting_for_key_with_name(command.key, command.color)
+                .context("failed to set the lighting of the key")?;
+
+            Ok(Hold::Forever)
+        }
+        Command::Zone(command) => {
+            let zone_count = command.device.zone_count();
+            if command.zone >= zone_count {
+                bail!(
+                    "zone {} is out of range for the {} device type, expected a zone from 0 to {}",
+                    command.zone,
+                    command.device.name(),
+                    zone_count - 1
+                );
+            }
+
+            sdk.set_lighting_for_target_zone(command.device, command.zone, command.color)
+                .context("failed to set the lighting of the zone")?;
+
+            Ok(Hold::Forever)
+        }
+        Command::Flash(command) => {
+            let duration = command.duration.map(Duration::from_millis);
+            let interval = Duration::from_millis(command.interval);
+            match command.key {
+                Some(key) => sdk.flash_single_key(key, command.color, duration, interval),
+                None => sdk.flash_lighting(command.color, duration, interval),
+            }
+            .context("failed to flash")?;
+
+            Ok(duration.map_or(Hold::Forever, Hold::For))
+        }
+        Command::Pulse(command) => {
+            let duration = command.duration.map(Duration::from_millis);
+            let interval = Duration::from_millis(command.interval);
+            match command.key {
+                // A single key pulse lasts for its duration, or repeats forever with the interval as its length.
+                Some(key) => sdk.pulse_single_key(
+                    key,
+                    command.color,
+                    command.to,
+                    duration.unwrap_or(interval),
+                    duration.is_none(),
+                ),
+                None => sdk.pulse_lighting(command.color, duration, interval),
+            }
+            .context("failed to pulse")?;
+
+            Ok(duration.map_or(Hold::Forever, Hold::For))
+        }
+        Command::Stop(_) => {
+            sdk.stop_effects().context("failed to stop effects")?;
+
+            Ok(Hold::Exit)
+        }
+        Command::Apply(command) => {
+            let profile = Profile::load(&command.path)?;
+            profile.apply(sdk).context("failed to apply the profile")?;
+
+            Ok(Hold::Forever)
+        }
+        Command::Replay(command) => {
+            let trace = Trace::load(&command.path)?;
+            let mut replayer = Replayer::new();
+            replayer.set_speed(command.speed);
+            replayer
+                .replay(sdk, &trace)
+                .context("failed to replay the trace")?;
+
+            Ok(Hold::Forever)
+        }
+        Command::Image(command) => {
+            let mut importer = ImageImporter::new();
+            importer.set_filter(command.filter);
+            importer.set_aspect_mode(command.aspect);
+            let animation = importer
+                .load_animation(&command.path)
+                .context("failed to load the image")?;
+
+            let duration = animation.duration();
+            if animation.frames().len() <= 1 || duration.is_zero() {
+                let (bitmap, _) = animation.frames().first().context("the image is empty")?;
+                sdk.set_lighting_from_bitmap(bitmap)
+                    .context("failed to set the lighting")?;
+
+                return Ok(Hold::Forever);
+            }
+
+            let end = command.loops.map(|loops| duration * loops);
+            let mut animator = Animator::new();
+            animator.add_effect(animation);
+            animator.start();
+            while end.is_none_or(|end| animator.time() < end) {
+                animator
+                    .tick(sdk)
+                    .context("failed to send a frame of the animation")?;
+                if let Some(duration) = animator.time_until_next_frame() {
+                    std::thread::sleep(duration);
+                }
+            }
+
+            Ok(Hold::Forever)
+        }
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let mut options: Options = argh::from_env();
+
+    if options.dry_run {
+        return dry_run(&mut options, std::io::stdout());
+    }
+
+    if options.simulate {
+        let backend = SimulatorBackend::new();
+        let sdk = ToolSdk::new(backend.clone(), options.record.as_deref())?;
+
+        let display = SimulatorDisplay::new();
+        let stop_handle = display.stop_handle();
+        let display_thread =
+            std::thread::spawn(move || display.run(&backend, std::io::stdout().lock()));
+        let result = run_and_hold(&options, sdk);
+        stop_handle.stop();
+        display_thread
+            .join()
+            .expect("simulator display panicked")
+            .context("failed to draw the simulator")?;
+
+        return result;
+    }
+
+    let backend = NativeBackend::new().context("failed to initialize the sdk")?;
+    let sdk = ToolSdk::new(backend, options.record.as_deref())?;
+    run_and_hold(&options, sdk)
+}
+
+/// Run the command on a mock sdk, writing each call to `out` in the trace format as it is made,
+/// and to the trace file too if there is one.
+///
+/// Animated images play once unless a number of loops is given, and the lighting is not held,
+/// so a dry run always ends.
+fn dry_run<W>(options: &mut Options, out: W) -> anyhow::Result<()>
+where
+    W: Write + 'static,
+{
+    if let Command::Image(command) = &mut options.command {
+        command.loops.get_or_insert(1);
+    }
+
+    let trace: Box<dyn Write> = match options.record.as_deref() {
+        Some(path) => Box::new(Tee(out, create_trace(path)?)),
+        None => Box::new(out),
+    };
+    let sdk = RecordingSdk::new_with_backend_and_name(MockBackend::new(), APP_NAME, trace)
+        .context("failed to initialize the sdk")?;
+    let result = run(&options.command, &sdk);
+    sdk.finish().context("failed to write the trace")?;
+
+    result.map(|_| ())
+}
+
+/// Run the command, keep the lighting for as long as it should be kept, then shut down the sdk.
+fn run_and_hold(options: &Options, sdk: ToolSdk) -> anyhow::Result<()> {
+    let hold = match (run(&options.command, sdk.sdk())?, options.hold) {
+        (Hold::Exit, _) => Hold::Exit,
+        (_, Some(hold)) => Hold::For(Duration::from_millis(hold)),
+        (hold, None) => hold,
+    };
+
+    match hold {
+        Hold::Exit => {}
+        Hold::For(duration) => std::thread::sleep(duration),
+        Hold::Forever => {
+            eprintln!("Holding the lighting, press Ctrl+C to exit");
+            loop {
+                std::thread::park();
+            }
+        }
+    }
+
+    sdk.shutdown()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use logitech_led_sdk::MockCall;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::sync::PoisonError;
+
+    /// A writer that can be read after it is given away.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn parse(args: &[&str]) -> Options {
+        Options::from_args(&[APP_NAME], args).expect("failed to parse args")
+    }
+
+    fn run_mock(args: &[&str]) -> (anyhow::Result<Hold>, Vec<MockCall>) {
+        let options = parse(args);
+        let backend = MockBackend::new();
+        let sdk = Sdk::new_with_backend(backend.clone()).expect("failed to init mock sdk");
+        backend.take_calls();
+
+        let result = run(&options.command, &sdk);
+        (result, backend.take_calls())
+    }
+
+    #[test]
+    fn parse_args() {
+        let options = parse(&["--dry-run", "--hold", "100", "key", "num_enter", "#ff8800"]);
+        assert!(options.dry_run);
+        assert_eq!(options.hold, Some(100));
+        assert!(matches!(
+            options.command,
+            Command::Key(KeyCommand {
+                key: KeyName::NUM_ENTER,
+                ..
+            })
+        ));
+
+        let options = parse(&[
+            "image", "logo.gif", "--filter", "Nearest", "--aspect", "fit",
+        ]);
+        assert!(matches!(
+            options.command,
+            Command::Image(ImageCommand {
+                filter: ResizeFilter::Nearest,
+                aspect: AspectMode::Fit,
+                loops: None,
+                ..
+            })
+        ));
+
+        for args in [
+            &["key", "NUMENTER", "red"][..],
+            &["set", "#ff88"],
+            &["zone", "Monitor", "0", "red"],
+            &["image", "logo.png", "--filter", "bicubic"],
+            &["image", "logo.png", "--aspect", "crop"],
+        ] {
+            assert!(Options::from_args(&[APP_NAME], args).is_err(), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn commands() {
+        let (result, calls) = run_mock(&["set", "red"]);
+        assert_eq!(result.expect("failed to set"), Hold::Forever);
+        assert_eq!(
+            calls,
+            [MockCall::SetLighting {
+                color: ColorPercent::RED
+            }]
+        );
+
+        let (result, calls) = run_mock(&["zone", "Mouse", "1", "lime"]);
+        assert_eq!(result.expect("failed to set zone"), Hold::Forever);
+        assert_eq!(
+            calls,
+            [MockCall::SetLightingForTargetZone {
+                device: DeviceType::Mouse,
+                zone: 1,
+                color: ColorPercent::GREEN
+            }]
+        );
+
+        let (result, calls) = run_mock(&["zone", "Mouse", "3", "lime"]);
+        assert!(result.is_err());
+        assert!(calls.is_empty());
+
+        let (result, calls) = run_mock(&["flash", "blue", "--duration", "2000"]);
+        assert_eq!(
+            result.expect("failed to flash"),
+            Hold::For(Duration::from_secs(2))
+        );
+        assert_eq!(
+            calls,
+            [MockCall::FlashLighting {
+                color: ColorPercent::BLUE,
+                duration: 2000,
+                interval: 500
+            }]
+        );
+
+        let (result, calls) = run_mock(&["pulse", "white", "--key", "ESC", "--interval", "250"]);
+        assert_eq!(result.expect("failed to pulse"), Hold::Forever);
+        assert_eq!(
+            calls,
+            [MockCall::PulseSingleKey {
+                key: KeyName::ESC,
+                start_color: ColorPercent::WHITE,
+                end_color: ColorPercent::BLACK,
+                duration: 250,
+                is_infinite: true
+            }]
+        );
+
+        let (result, calls) = run_mock(&["stop"]);
+        assert_eq!(result.expect("failed to stop"), Hold::Exit);
+        assert_eq!(calls, [MockCall::StopEffects]);
+
+        let (result, calls) = run_mock(&["apply", "missing.toml"]);
+        assert!(result.is_err());
+        assert!(calls.is_empty());
+    }
+
+    #[test]
+    fn dry_run_trace() {
+        let buffer = SharedBuffer::default();
+        let mut options = parse(&["--dry-run", "key", "W", "red"]);
+        dry_run(&mut options, buffer.clone()).expect("failed to dry run");
+
+        let text = String::from_utf8(buffer.0.lock().expect("buffer poisoned").clone())
+            .expect("trace is not UTF-8");
+        let trace: Trace = text.parse().expect("failed to parse trace");
+        let lines: Vec<_> = trace
+            .events()
+            .iter()
+            .map(|event| {
+                let line = event.to_string();
+                line.split_once(' ').expect("missing time").1.to_string()
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                "LogiLedInitWithName \"logiled\" = true",
+                "LogiLedSetLightingForKeyWithKeyName W 100,0,0 = true",
+                "LogiLedShutdown",
+            ]
+        );
+
+        // Animations play once, so the dry run ends.
+        let mut options = parse(&["--dry-run", "image", "missing.gif"]);
+        assert!(dry_run(&mut options, SharedBuffer::default()).is_err());
+        assert!(matches!(
+            options.command,
+            Command::Image(ImageCommand { loops: Some(1), .. })
+        ));
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let path = std::env::temp_dir().join(format!("logiled-test-{}.trace", std::process::id()));
+        let path_arg = path.to_str().expect("temp path is not UTF-8");
+
+        let backend = MockBackend::new();
+        let sdk = ToolSdk::new(backend, Some(&path)).expect("failed to init mock sdk");
+        run(&parse(&["key", "W", "red"]).command, sdk.sdk()).expect("failed to set key");
+        sdk.shutdown().expect("failed to write trace");
+
+        let (result, calls) = run_mock(&["replay", path_arg, "--speed", "inf"]);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(result.expect("failed to replay"), Hold::Forever);
+        assert_eq!(
+            calls,
+            [MockCall::SetLightingForKeyWithKeyName {
+                key: KeyName::W,
+                color: ColorPercent::RED
+            }]
+        );
+    }
+}
@@ -1,5 +1,16 @@
 #[cfg(not(feature = "rustdoc"))]
 fn main() {
+    // The library only exists for Windows.
+    // On other platforms, only the types and constants are usable.
+    if std::env::var("CARGO_CFG_TARGET_OS").as_deref() != Ok("windows") {
+        return;
+    }
+
+    // The library is loaded at runtime instead.
+    if std::env::var_os("CARGO_FEATURE_DYNAMIC").is_some() {
+        return;
+    }
+
     let sdk_dir = std::env::var("LOGITECH_LED_SDK").expect("missing or invalid `LOGITECH_LED_SDK`");
     match std::env::var("CARGO_CFG_TARGET_ARCH")
         .expect("missing or invalid `CARGO_CFG_TARGET_ARCH`")
@@ -0,0 +1,106 @@
+use crate::LogiLed_DeviceType;
+
+/// Every device type, with its name and number of zones, in declaration order.
+const DEVICE_TYPES: &[(LogiLed_DeviceType, &str, u32)] = &[
+    (LogiLed_DeviceType::Keyboard, "Keyboard", 6),
+    (LogiLed_DeviceType::Mouse, "Mouse", 3),
+    (LogiLed_DeviceType::Mousemat, "Mousemat", 3),
+    (LogiLed_DeviceType::Headset, "Headset", 2),
+    (LogiLed_DeviceType::Speaker, "Speaker", 5),
+];
+
+impl LogiLed_DeviceType {
+    /// Iterate over every device type, in declaration order.
+    pub fn iter() -> impl Iterator<Item = Self> + Clone {
+        DEVICE_TYPES.iter().map(|(device_type, _, _)| *device_type)
+    }
+
+    /// Get the name of this device type, like `"Mousemat"`.
+    pub fn name(self) -> &'static str {
+        self.entry().1
+    }
+
+    /// Get the number of zones that [`LogiLedSetLightingForTargetZone`](crate::LogiLedSetLightingForTargetZone) accepts for this device type.
+    ///
+    /// Zones are numbered from 0.
+    /// Devices may have fewer zones than this; the sdk ignores zones that a device does not have.
+    pub fn zone_count(self) -> u32 {
+        self.entry().2
+    }
+
+    /// Get the device type with the given name, ignoring ASCII case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        DEVICE_TYPES
+            .iter()
+            .find(|(_, device_name, _)| device_name.eq_ignore_ascii_case(name))
+            .map(|(device_type, _, _)| *device_type)
+    }
+
+    /// Get the table entry for this device type.
+    fn entry(self) -> &'static (Self, &'static str, u32) {
+        DEVICE_TYPES
+            .iter()
+            .find(|(device_type, _, _)| *device_type == self)
+            .expect("every device type is in the table")
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for LogiLed_DeviceType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.name())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for LogiLed_DeviceType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        crate::serde::deserialize_name(deserializer, "a device type name", Self::from_name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn names() {
+        assert_eq!(LogiLed_DeviceType::iter().count(), 5);
+        for device_type in LogiLed_DeviceType::iter() {
+            assert_eq!(device_type.name(), format!("{device_type:?}"));
+            assert_eq!(
+                LogiLed_DeviceType::from_name(device_type.name()),
+                Some(device_type)
+            );
+        }
+        assert_eq!(
+            LogiLed_DeviceType::from_name("headset"),
+            Some(LogiLed_DeviceType::Headset)
+        );
+        assert_eq!(LogiLed_DeviceType::from_name("Monitor"), None);
+        assert_eq!(LogiLed_DeviceType::Keyboard.zone_count(), 6);
+        assert_eq!(LogiLed_DeviceType::Headset.zone_count(), 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        for device_type in LogiLed_DeviceType::iter() {
+            let json = serde_json::to_string(&device_type).expect("failed to serialize");
+            assert_eq!(json, format!("\"{}\"", device_type.name()));
+            let parsed: LogiLed_DeviceType =
+                serde_json::from_str(&json).expect("failed to deserialize");
+            assert_eq!(parsed, device_type);
+        }
+
+        let error = serde_json::from_str::<LogiLed_DeviceType>("\"Monitor\"")
+            .expect_err("deserialized an unknown device type");
+        assert!(error.to_string().contains("expected a device type name"));
+    }
+}
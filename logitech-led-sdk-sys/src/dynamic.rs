@@ -0,0 +1,240 @@
+//! Runtime loading of the Logitech LED library.
+//!
+//! This is only available with the `dynamic` feature.
+//! With it enabled, the library is not linked at build time, so the functions in the crate root will fail to link.
+//! Use a [`Library`] to call them instead.
+
+use crate::LogiLed_DeviceType;
+use crate::LogiLed_KeyName;
+use std::ffi::OsStr;
+use std::os::raw::c_char;
+use std::os::raw::c_int;
+use std::os::raw::c_uchar;
+use std::path::PathBuf;
+
+/// The default locations of the library, in the order they are tried.
+///
+/// The first entry is looked up using the system library search path.
+#[cfg(not(target_arch = "x86"))]
+pub const DEFAULT_LIBRARY_PATHS: &[&str] = &[
+    "LogitechLed.dll",
+    r"C:\Program Files\LGHUB\sdk_legacy_led_x64.dll",
+    r"C:\Program Files\Logitech Gaming Software\SDK\LED\x64\LogitechLed.dll",
+];
+
+/// The default locations of the library, in the order they are tried.
+///
+/// The first entry is looked up using the system library search path.
+#[cfg(target_arch = "x86")]
+pub const DEFAULT_LIBRARY_PATHS: &[&str] = &[
+    "LogitechLed.dll",
+    r"C:\Program Files\LGHUB\sdk_legacy_led_x86.dll",
+    r"C:\Program Files\Logitech Gaming Software\SDK\LED\x86\LogitechLed.dll",
+];
+
+/// An error that may occur while loading the library.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LoadError {
+    /// The library could not be opened.
+    Open {
+        /// The path that was opened.
+        path: PathBuf,
+
+        /// The error message from the system loader.
+        message: String,
+    },
+
+    /// The library was opened, but some functions could not be found in it.
+    MissingSymbols {
+        /// The path that was opened.
+        path: PathBuf,
+
+        /// The names of the missing functions.
+        symbols: Vec<&'static str>,
+    },
+
+    /// None of the [`DEFAULT_LIBRARY_PATHS`] could be opened.
+    NotFound,
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Open { path, message } => {
+                write!(f, "failed to open \"{}\": {message}", path.display())
+            }
+            Self::MissingSymbols { path, symbols } => {
+                write!(
+                    f,
+                    "\"{}\" is missing the functions: {}",
+                    path.display(),
+                    symbols.join(", ")
+                )
+            }
+            Self::NotFound => "failed to find the library in any of the default locations".fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// Look up a function by its plain name, falling back to its MSVC-mangled name.
+///
+/// # Safety
+/// `T` must be the correct function pointer type for the symbol.
+unsafe fn lookup<T: Copy>(library: &libloading::Library, names: [&str; 2]) -> Option<T> {
+    names
+        .iter()
+        .find_map(|name| library.get::<T>(name.as_bytes()).ok().map(|symbol| *symbol))
+}
+
+macro_rules! library {
+    ($(fn $name:ident($($arg:ty),*) $(-> $ret:ty)? = $x86_64:literal, $x86:literal;)*) => {
+        /// A loaded Logitech LED library.
+        ///
+        /// Each field is a function of the same name from the crate root.
+        /// The library stays loaded for as long as this is alive.
+        pub struct Library {
+            $(
+                #[doc = concat!("`", stringify!($name), "`")]
+                pub $name: unsafe extern "C" fn($($arg),*) $(-> $ret)?,
+            )*
+            _library: libloading::Library,
+        }
+
+        impl Library {
+            /// Load the library from a path.
+            ///
+            /// Functions are looked up by their plain names first, then by their MSVC-mangled names.
+            ///
+            /// # Errors
+            /// Returns an error if the library could not be opened or is missing any functions.
+            ///
+            /// # Safety
+            /// The library's initialization routines are run,
+            /// and its functions must match the signatures of the functions in the crate root.
+            pub unsafe fn open<P>(path: P) -> Result<Self, LoadError>
+            where
+                P: AsRef<OsStr>,
+            {
+                let path = path.as_ref();
+                let library = libloading::Library::new(path).map_err(|error| LoadError::Open {
+                    path: PathBuf::from(path),
+                    message: error.to_string(),
+                })?;
+
+                let mut missing = Vec::new();
+                $(
+                    let mangled = if cfg!(target_arch = "x86") { $x86 } else { $x86_64 };
+                    let $name = lookup::<unsafe extern "C" fn($($arg),*) $(-> $ret)?>(
+                        &library,
+                        [stringify!($name), mangled],
+                    );
+                    if $name.is_none() {
+                        missing.push(stringify!($name));
+                    }
+                )*
+
+                match ($($name,)*) {
+                    ($(Some($name),)*) => Ok(Self {
+                        $($name,)*
+                        _library: library,
+                    }),
+                    _ => Err(LoadError::MissingSymbols {
+                        path: PathBuf::from(path),
+                        symbols: missing,
+                    }),
+                }
+            }
+        }
+    };
+}
+
+library! {
+    fn LogiLedInit() -> bool = "?LogiLedInit@@YA_NXZ", "?LogiLedInit@@YA_NXZ";
+    fn LogiLedInitWithName(*const c_char) -> bool = "?LogiLedInitWithName@@YA_NQEBD@Z", "?LogiLedInitWithName@@YA_NQBD@Z";
+    fn LogiLedGetSdkVersion(*mut c_int, *mut c_int, *mut c_int) -> bool = "?LogiLedGetSdkVersion@@YA_NPEAH00@Z", "?LogiLedGetSdkVersion@@YA_NPAH00@Z";
+    fn LogiLedGetConfigOptionNumber(*const u16, *mut f64) -> bool = "?LogiLedGetConfigOptionNumber@@YA_NPEB_WPEAN@Z", "?LogiLedGetConfigOptionNumber@@YA_NPB_WPAN@Z";
+    fn LogiLedGetConfigOptionBool(*const u16, *mut bool) -> bool = "?LogiLedGetConfigOptionBool@@YA_NPEB_WPEA_N@Z", "?LogiLedGetConfigOptionBool@@YA_NPB_WPA_N@Z";
+    fn LogiLedGetConfigOptionColor(*const u16, *mut c_int, *mut c_int, *mut c_int) -> bool = "?LogiLedGetConfigOptionColor@@YA_NPEB_WPEAH11@Z", "?LogiLedGetConfigOptionColor@@YA_NPB_WPAH11@Z";
+    fn LogiLedGetConfigOptionRect(*const u16, *mut c_int, *mut c_int, *mut c_int, *mut c_int) -> bool = "?LogiLedGetConfigOptionRect@@YA_NPEB_WPEAH111@Z", "?LogiLedGetConfigOptionRect@@YA_NPB_WPAH111@Z";
+    fn LogiLedGetConfigOptionString(*const u16, *mut u16, c_int) -> bool = "?LogiLedGetConfigOptionString@@YA_NPEB_WPEA_WH@Z", "?LogiLedGetConfigOptionString@@YA_NPB_WPA_WH@Z";
+    fn LogiLedGetConfigOptionKeyInput(*const u16, *mut u16, c_int) -> bool = "?LogiLedGetConfigOptionKeyInput@@YA_NPEB_WPEA_WH@Z", "?LogiLedGetConfigOptionKeyInput@@YA_NPB_WPA_WH@Z";
+    fn LogiLedGetConfigOptionSelect(*const u16, *mut u16, *mut c_int, *const u16, c_int) -> bool = "?LogiLedGetConfigOptionSelect@@YA_NPEB_WPEA_WPEAH0H@Z", "?LogiLedGetConfigOptionSelect@@YA_NPB_WPA_WPAH0H@Z";
+    fn LogiLedGetConfigOptionRange(*const u16, *mut c_int, c_int, c_int) -> bool = "?LogiLedGetConfigOptionRange@@YA_NPEB_WPEAHHH@Z", "?LogiLedGetConfigOptionRange@@YA_NPB_WPAHHH@Z";
+    fn LogiLedSetConfigOptionLabel(*const u16, *mut u16) -> bool = "?LogiLedSetConfigOptionLabel@@YA_NPEB_WPEA_W@Z", "?LogiLedSetConfigOptionLabel@@YA_NPB_WPA_W@Z";
+    fn LogiLedSetTargetDevice(c_int) -> bool = "?LogiLedSetTargetDevice@@YA_NH@Z", "?LogiLedSetTargetDevice@@YA_NH@Z";
+    fn LogiLedSaveCurrentLighting() -> bool = "?LogiLedSaveCurrentLighting@@YA_NXZ", "?LogiLedSaveCurrentLighting@@YA_NXZ";
+    fn LogiLedSetLighting(c_int, c_int, c_int) -> bool = "?LogiLedSetLighting@@YA_NHHH@Z", "?LogiLedSetLighting@@YA_NHHH@Z";
+    fn LogiLedRestoreLighting() -> bool = "?LogiLedRestoreLighting@@YA_NXZ", "?LogiLedRestoreLighting@@YA_NXZ";
+    fn LogiLedFlashLighting(c_int, c_int, c_int, c_int, c_int) -> bool = "?LogiLedFlashLighting@@YA_NHHHHH@Z", "?LogiLedFlashLighting@@YA_NHHHHH@Z";
+    fn LogiLedPulseLighting(c_int, c_int, c_int, c_int, c_int) -> bool = "?LogiLedPulseLighting@@YA_NHHHHH@Z", "?LogiLedPulseLighting@@YA_NHHHHH@Z";
+    fn LogiLedStopEffects() -> bool = "?LogiLedStopEffects@@YA_NXZ", "?LogiLedStopEffects@@YA_NXZ";
+    fn LogiLedSetLightingFromBitmap(*mut c_uchar) -> bool = "?LogiLedSetLightingFromBitmap@@YA_NQEAE@Z", "?LogiLedSetLightingFromBitmap@@YA_NQAE@Z";
+    fn LogiLedSetLightingForKeyWithScanCode(c_int, c_int, c_int, c_int) -> bool = "?LogiLedSetLightingForKeyWithScanCode@@YA_NHHHH@Z", "?LogiLedSetLightingForKeyWithScanCode@@YA_NHHHH@Z";
+    fn LogiLedSetLightingForKeyWithHidCode(c_int, c_int, c_int, c_int) -> bool = "?LogiLedSetLightingForKeyWithHidCode@@YA_NHHHH@Z", "?LogiLedSetLightingForKeyWithHidCode@@YA_NHHHH@Z";
+    fn LogiLedSetLightingForKeyWithQuartzCode(c_int, c_int, c_int, c_int) -> bool = "?LogiLedSetLightingForKeyWithQuartzCode@@YA_NHHHH@Z", "?LogiLedSetLightingForKeyWithQuartzCode@@YA_NHHHH@Z";
+    fn LogiLedSetLightingForKeyWithKeyName(LogiLed_KeyName, c_int, c_int, c_int) -> bool = "?LogiLedSetLightingForKeyWithKeyName@@YA_NW4KeyName@LogiLed@@HHH@Z", "?LogiLedSetLightingForKeyWithKeyName@@YA_NW4KeyName@LogiLed@@HHH@Z";
+    fn LogiLedSaveLightingForKey(LogiLed_KeyName) -> bool = "?LogiLedSaveLightingForKey@@YA_NW4KeyName@LogiLed@@@Z", "?LogiLedSaveLightingForKey@@YA_NW4KeyName@LogiLed@@@Z";
+    fn LogiLedRestoreLightingForKey(LogiLed_KeyName) -> bool = "?LogiLedRestoreLightingForKey@@YA_NW4KeyName@LogiLed@@@Z", "?LogiLedRestoreLightingForKey@@YA_NW4KeyName@LogiLed@@@Z";
+    fn LogiLedExcludeKeysFromBitmap(*mut LogiLed_KeyName, c_int) -> bool = "?LogiLedExcludeKeysFromBitmap@@YA_NPEAW4KeyName@LogiLed@@H@Z", "?LogiLedExcludeKeysFromBitmap@@YA_NPAW4KeyName@LogiLed@@H@Z";
+    fn LogiLedFlashSingleKey(LogiLed_KeyName, c_int, c_int, c_int, c_int, c_int) -> bool = "?LogiLedFlashSingleKey@@YA_NW4KeyName@LogiLed@@HHHHH@Z", "?LogiLedFlashSingleKey@@YA_NW4KeyName@LogiLed@@HHHHH@Z";
+    fn LogiLedPulseSingleKey(LogiLed_KeyName, c_int, c_int, c_int, c_int, c_int, c_int, c_int, bool) -> bool = "?LogiLedPulseSingleKey@@YA_NW4KeyName@LogiLed@@HHHHHHH_N@Z", "?LogiLedPulseSingleKey@@YA_NW4KeyName@LogiLed@@HHHHHHH_N@Z";
+    fn LogiLedStopEffectsOnKey(LogiLed_KeyName) -> bool = "?LogiLedStopEffectsOnKey@@YA_NW4KeyName@LogiLed@@@Z", "?LogiLedStopEffectsOnKey@@YA_NW4KeyName@LogiLed@@@Z";
+    fn LogiLedSetLightingForTargetZone(LogiLed_DeviceType, c_int, c_int, c_int, c_int) -> bool = "?LogiLedSetLightingForTargetZone@@YA_NW4DeviceType@LogiLed@@HHHH@Z", "?LogiLedSetLightingForTargetZone@@YA_NW4DeviceType@LogiLed@@HHHH@Z";
+    fn LogiLedShutdown() = "?LogiLedShutdown@@YAXXZ", "?LogiLedShutdown@@YAXXZ";
+}
+
+impl Library {
+    /// Load the library from the first of the [`DEFAULT_LIBRARY_PATHS`] that can be opened.
+    ///
+    /// # Errors
+    /// Returns an error if none of the paths could be opened,
+    /// or if the first library that could be opened is missing any functions.
+    ///
+    /// # Safety
+    /// See [`Library::open`].
+    pub unsafe fn open_default() -> Result<Self, LoadError> {
+        for path in DEFAULT_LIBRARY_PATHS {
+            match Self::open(path) {
+                Ok(library) => return Ok(library),
+                Err(LoadError::Open { .. }) => {}
+                Err(error) => return Err(error),
+            }
+        }
+
+        Err(LoadError::NotFound)
+    }
+}
+
+impl std::fmt::Debug for Library {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Library").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn open_missing_library() {
+        let error = unsafe { Library::open("this-library-does-not-exist.dll") }
+            .expect_err("opened a library that does not exist");
+        assert!(matches!(error, LoadError::Open { .. }));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn open_wrong_library() {
+        let error = unsafe { Library::open("libc.so.6") }.expect_err("libc is not the sdk");
+        match error {
+            LoadError::MissingSymbols { symbols, .. } => {
+                assert_eq!(symbols.len(), 32);
+                assert_eq!(symbols[0], "LogiLedInit");
+                assert_eq!(symbols[31], "LogiLedShutdown");
+            }
+            error => panic!("unexpected error: {error}"),
+        }
+    }
+}
@@ -0,0 +1,439 @@
+use crate::LogiLed_KeyName;
+
+/// The codes that identify a key in each of the ways the sdk accepts.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct KeyCodes {
+    /// The key name.
+    pub key_name: LogiLed_KeyName,
+
+    /// The name of the key, as written in the sdk header without the `LogiLed::` prefix, like `"NUM_ENTER"`.
+    pub name: &'static str,
+
+    /// The set 1 scan code, with extended (`0xE0` prefixed) keys offset by `0x100`.
+    ///
+    /// This is the same as the key name's discriminant.
+    pub scan_code: Option<u32>,
+
+    /// The USB HID usage on the Keyboard/Keypad page (`0x07`).
+    pub hid_usage: Option<u32>,
+
+    /// The macOS virtual key code (`kVK_*`), as used by Quartz event services.
+    pub quartz_code: Option<u32>,
+}
+
+impl KeyCodes {
+    const fn new(
+        key_name: LogiLed_KeyName,
+        name: &'static str,
+        hid_usage: u32,
+        quartz_code: Option<u32>,
+    ) -> Self {
+        Self {
+            key_name,
+            name,
+            scan_code: Some(key_name as u32),
+            hid_usage: Some(hid_usage),
+            quartz_code,
+        }
+    }
+
+    const fn logitech(key_name: LogiLed_KeyName, name: &'static str) -> Self {
+        Self {
+            key_name,
+            name,
+            scan_code: None,
+            hid_usage: None,
+            quartz_code: None,
+        }
+    }
+}
+
+/// The codes of every key name, in declaration order.
+///
+/// The G keys, logo and badge are specific to Logitech devices, so they have no other codes.
+/// Keys without a Mac equivalent use the key in the same position on an Apple keyboard where one exists,
+/// like `F13` for print screen and `Help` for insert.
+pub const KEY_CODES: &[KeyCodes] = {
+    use LogiLed_KeyName::*;
+
+    &[
+        KeyCodes::new(ESC, "ESC", 0x29, Some(0x35)),
+        KeyCodes::new(F1, "F1", 0x3A, Some(0x7A)),
+        KeyCodes::new(F2, "F2", 0x3B, Some(0x78)),
+        KeyCodes::new(F3, "F3", 0x3C, Some(0x63)),
+        KeyCodes::new(F4, "F4", 0x3D, Some(0x76)),
+        KeyCodes::new(F5, "F5", 0x3E, Some(0x60)),
+        KeyCodes::new(F6, "F6", 0x3F, Some(0x61)),
+        KeyCodes::new(F7, "F7", 0x40, Some(0x62)),
+        KeyCodes::new(F8, "F8", 0x41, Some(0x64)),
+        KeyCodes::new(F9, "F9", 0x42, Some(0x65)),
+        KeyCodes::new(F10, "F10", 0x43, Some(0x6D)),
+        KeyCodes::new(F11, "F11", 0x44, Some(0x67)),
+        KeyCodes::new(F12, "F12", 0x45, Some(0x6F)),
+        KeyCodes::new(PRINT_SCREEN, "PRINT_SCREEN", 0x46, Some(0x69)),
+        KeyCodes::new(SCROLL_LOCK, "SCROLL_LOCK", 0x47, Some(0x6B)),
+        KeyCodes::new(PAUSE_BREAK, "PAUSE_BREAK", 0x48, Some(0x71)),
+        KeyCodes::new(TILDE, "TILDE", 0x35, Some(0x32)),
+        KeyCodes::new(ONE, "ONE", 0x1E, Some(0x12)),
+        KeyCodes::new(TWO, "TWO", 0x1F, Some(0x13)),
+        KeyCodes::new(THREE, "THREE", 0x20, Some(0x14)),
+        KeyCodes::new(FOUR, "FOUR", 0x21, Some(0x15)),
+        KeyCodes::new(FIVE, "FIVE", 0x22, Some(0x17)),
+        KeyCodes::new(SIX, "SIX", 0x23, Some(0x16)),
+        KeyCodes::new(SEVEN, "SEVEN", 0x24, Some(0x1A)),
+        KeyCodes::new(EIGHT, "EIGHT", 0x25, Some(0x1C)),
+        KeyCodes::new(NINE, "NINE", 0x26, Some(0x19)),
+        KeyCodes::new(ZERO, "ZERO", 0x27, Some(0x1D)),
+        KeyCodes::new(MINUS, "MINUS", 0x2D, Some(0x1B)),
+        KeyCodes::new(EQUALS, "EQUALS", 0x2E, Some(0x18)),
+        KeyCodes::new(BACKSPACE, "BACKSPACE", 0x2A, Some(0x33)),
+        KeyCodes::new(INSERT, "INSERT", 0x49, Some(0x72)),
+        KeyCodes::new(HOME, "HOME", 0x4A, Some(0x73)),
+        KeyCodes::new(PAGE_UP, "PAGE_UP", 0x4B, Some(0x74)),
+        KeyCodes::new(NUM_LOCK, "NUM_LOCK", 0x53, Some(0x47)),
+        KeyCodes::new(NUM_SLASH, "NUM_SLASH", 0x54, Some(0x4B)),
+        KeyCodes::new(NUM_ASTERISK, "NUM_ASTERISK", 0x55, Some(0x43)),
+        KeyCodes::new(NUM_MINUS, "NUM_MINUS", 0x56, Some(0x4E)),
+        KeyCodes::new(TAB, "TAB", 0x2B, Some(0x30)),
+        KeyCodes::new(Q, "Q", 0x14, Some(0x0C)),
+        KeyCodes::new(W, "W", 0x1A, Some(0x0D)),
+        KeyCodes::new(E, "E", 0x08, Some(0x0E)),
+        KeyCodes::new(R, "R", 0x15, Some(0x0F)),
+        KeyCodes::new(T, "T", 0x17, Some(0x11)),
+        KeyCodes::new(Y, "Y", 0x1C, Some(0x10)),
+        KeyCodes::new(U, "U", 0x18, Some(0x20)),
+        KeyCodes::new(I, "I", 0x0C, Some(0x22)),
+        KeyCodes::new(O, "O", 0x12, Some(0x1F)),
+        KeyCodes::new(P, "P", 0x13, Some(0x23)),
+        KeyCodes::new(OPEN_BRACKET, "OPEN_BRACKET", 0x2F, Some(0x21)),
+        KeyCodes::new(CLOSE_BRACKET, "CLOSE_BRACKET", 0x30, Some(0x1E)),
+        KeyCodes::new(BACKSLASH, "BACKSLASH", 0x31, Some(0x2A)),
+        KeyCodes::new(KEYBOARD_DELETE, "KEYBOARD_DELETE", 0x4C, Some(0x75)),
+        KeyCodes::new(END, "END", 0x4D, Some(0x77)),
+        KeyCodes::new(PAGE_DOWN, "PAGE_DOWN", 0x4E, Some(0x79)),
+        KeyCodes::new(NUM_SEVEN, "NUM_SEVEN", 0x5F, Some(0x59)),
+        KeyCodes::new(NUM_EIGHT, "NUM_EIGHT", 0x60, Some(0x5B)),
+        KeyCodes::new(NUM_NINE, "NUM_NINE", 0x61, Some(0x5C)),
+        KeyCodes::new(NUM_PLUS, "NUM_PLUS", 0x57, Some(0x45)),
+        KeyCodes::new(CAPS_LOCK, "CAPS_LOCK", 0x39, Some(0x39)),
+        KeyCodes::new(A, "A", 0x04, Some(0x00)),
+        KeyCodes::new(S, "S", 0x16, Some(0x01)),
+        KeyCodes::new(D, "D", 0x07, Some(0x02)),
+        KeyCodes::new(F, "F", 0x09, Some(0x03)),
+        KeyCodes::new(G, "G", 0x0A, Some(0x05)),
+        KeyCodes::new(H, "H", 0x0B, Some(0x04)),
+        KeyCodes::new(J, "J", 0x0D, Some(0x26)),
+        KeyCodes::new(K, "K", 0x0E, Some(0x28)),
+        KeyCodes::new(L, "L", 0x0F, Some(0x25)),
+        KeyCodes::new(SEMICOLON, "SEMICOLON", 0x33, Some(0x29)),
+        KeyCodes::new(APOSTROPHE, "APOSTROPHE", 0x34, Some(0x27)),
+        KeyCodes::new(ENTER, "ENTER", 0x28, Some(0x24)),
+        KeyCodes::new(NUM_FOUR, "NUM_FOUR", 0x5C, Some(0x56)),
+        KeyCodes::new(NUM_FIVE, "NUM_FIVE", 0x5D, Some(0x57)),
+        KeyCodes::new(NUM_SIX, "NUM_SIX", 0x5E, Some(0x58)),
+        KeyCodes::new(LEFT_SHIFT, "LEFT_SHIFT", 0xE1, Some(0x38)),
+        KeyCodes::new(Z, "Z", 0x1D, Some(0x06)),
+        KeyCodes::new(X, "X", 0x1B, Some(0x07)),
+        KeyCodes::new(C, "C", 0x06, Some(0x08)),
+        KeyCodes::new(V, "V", 0x19, Some(0x09)),
+        KeyCodes::new(B, "B", 0x05, Some(0x0B)),
+        KeyCodes::new(N, "N", 0x11, Some(0x2D)),
+        KeyCodes::new(M, "M", 0x10, Some(0x2E)),
+        KeyCodes::new(COMMA, "COMMA", 0x36, Some(0x2B)),
+        KeyCodes::new(PERIOD, "PERIOD", 0x37, Some(0x2F)),
+        KeyCodes::new(FORWARD_SLASH, "FORWARD_SLASH", 0x38, Some(0x2C)),
+        KeyCodes::new(RIGHT_SHIFT, "RIGHT_SHIFT", 0xE5, Some(0x3C)),
+        KeyCodes::new(ARROW_UP, "ARROW_UP", 0x52, Some(0x7E)),
+        KeyCodes::new(NUM_ONE, "NUM_ONE", 0x59, Some(0x53)),
+        KeyCodes::new(NUM_TWO, "NUM_TWO", 0x5A, Some(0x54)),
+        KeyCodes::new(NUM_THREE, "NUM_THREE", 0x5B, Some(0x55)),
+        KeyCodes::new(NUM_ENTER, "NUM_ENTER", 0x58, Some(0x4C)),
+        KeyCodes::new(LEFT_CONTROL, "LEFT_CONTROL", 0xE0, Some(0x3B)),
+        KeyCodes::new(LEFT_WINDOWS, "LEFT_WINDOWS", 0xE3, Some(0x37)),
+        KeyCodes::new(LEFT_ALT, "LEFT_ALT", 0xE2, Some(0x3A)),
+        KeyCodes::new(SPACE, "SPACE", 0x2C, Some(0x31)),
+        KeyCodes::new(RIGHT_ALT, "RIGHT_ALT", 0xE6, Some(0x3D)),
+        KeyCodes::new(RIGHT_WINDOWS, "RIGHT_WINDOWS", 0xE7, Some(0x36)),
+        KeyCodes::new(APPLICATION_SELECT, "APPLICATION_SELECT", 0x65, Some(0x6E)),
+        KeyCodes::new(RIGHT_CONTROL, "RIGHT_CONTROL", 0xE4, Some(0x3E)),
+        KeyCodes::new(ARROW_LEFT, "ARROW_LEFT", 0x50, Some(0x7B)),
+        KeyCodes::new(ARROW_DOWN, "ARROW_DOWN", 0x51, Some(0x7D)),
+        KeyCodes::new(ARROW_RIGHT, "ARROW_RIGHT", 0x4F, Some(0x7C)),
+        KeyCodes::new(NUM_ZERO, "NUM_ZERO", 0x62, Some(0x52)),
+        KeyCodes::new(NUM_PERIOD, "NUM_PERIOD", 0x63, Some(0x41)),
+        KeyCodes::logitech(G_1, "G_1"),
+        KeyCodes::logitech(G_2, "G_2"),
+        KeyCodes::logitech(G_3, "G_3"),
+        KeyCodes::logitech(G_4, "G_4"),
+        KeyCodes::logitech(G_5, "G_5"),
+        KeyCodes::logitech(G_6, "G_6"),
+        KeyCodes::logitech(G_7, "G_7"),
+        KeyCodes::logitech(G_8, "G_8"),
+        KeyCodes::logitech(G_9, "G_9"),
+        KeyCodes::logitech(G_LOGO, "G_LOGO"),
+        KeyCodes::logitech(G_BADGE, "G_BADGE"),
+    ]
+};
+
+impl LogiLed_KeyName {
+    /// Iterate over every key name, in declaration order.
+    pub fn iter() -> impl Iterator<Item = Self> + Clone {
+        KEY_CODES.iter().map(|codes| codes.key_name)
+    }
+
+    /// Get the codes for this key.
+    pub fn codes(self) -> &'static KeyCodes {
+        KEY_CODES
+            .iter()
+            .find(|codes| codes.key_name == self)
+            .expect("every key name is in the table")
+    }
+
+    /// Get the name of this key, like `"NUM_ENTER"`.
+    pub fn name(self) -> &'static str {
+        self.codes().name
+    }
+
+    /// Get the key with the given name, ignoring ASCII case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        KEY_CODES
+            .iter()
+            .find(|codes| codes.name.eq_ignore_ascii_case(name))
+            .map(|codes| codes.key_name)
+    }
+
+    /// Get the scan code for this key, if it has one.
+    pub fn scan_code(self) -> Option<u32> {
+        self.codes().scan_code
+    }
+
+    /// Get the key with the given scan code, if there is one.
+    ///
+    /// Extended keys are expected to be offset by `0x100`, like [`KeyCodes::scan_code`].
+    pub fn from_scan_code(scan_code: u32) -> Option<Self> {
+        KEY_CODES
+            .iter()
+            .find(|codes| codes.scan_code == Some(scan_code))
+            .map(|codes| codes.key_name)
+    }
+
+    /// Get the USB HID usage for this key, if it has one.
+    pub fn hid_usage(self) -> Option<u32> {
+        self.codes().hid_usage
+    }
+
+    /// Get the key with the given USB HID usage, if there is one.
+    pub fn from_hid_usage(hid_usage: u32) -> Option<Self> {
+        KEY_CODES
+            .iter()
+            .find(|codes| codes.hid_usage == Some(hid_usage))
+            .map(|codes| codes.key_name)
+    }
+
+    /// Get the macOS virtual key code for this key, if it has one.
+    pub fn quartz_code(self) -> Option<u32> {
+        self.codes().quartz_code
+    }
+
+    /// Get the key with the given macOS virtual key code, if there is one.
+    pub fn from_quartz_code(quartz_code: u32) -> Option<Self> {
+        KEY_CODES
+            .iter()
+            .find(|codes| codes.quartz_code == Some(quartz_code))
+            .map(|codes| codes.key_name)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for LogiLed_KeyName {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.name())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for LogiLed_KeyName {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        crate::serde::deserialize_name(deserializer, "a key name", Self::from_name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn quartz_codes() {
+        let mut seen = HashSet::new();
+        for codes in KEY_CODES {
+            if let Some(quartz_code) = codes.quartz_code {
+                assert!(
+                    seen.insert(quartz_code),
+                    "duplicate quartz code {quartz_code:#04X}"
+                );
+                assert_eq!(
+                    LogiLed_KeyName::from_quartz_code(quartz_code),
+                    Some(codes.key_name)
+                );
+            }
+        }
+
+        // kVK_ANSI_A, kVK_Return, kVK_Command and kVK_UpArrow
+        assert_eq!(
+            LogiLed_KeyName::from_quartz_code(0x00),
+            Some(LogiLed_KeyName::A)
+        );
+        assert_eq!(LogiLed_KeyName::ENTER.quartz_code(), Some(0x24));
+        assert_eq!(LogiLed_KeyName::LEFT_WINDOWS.quartz_code(), Some(0x37));
+        assert_eq!(LogiLed_KeyName::ARROW_UP.quartz_code(), Some(0x7E));
+        assert_eq!(LogiLed_KeyName::G_1.quartz_code(), None);
+        assert_eq!(LogiLed_KeyName::from_quartz_code(0xFF), None);
+    }
+
+    #[test]
+    fn iter() {
+        let keys: HashSet<_> = LogiLed_KeyName::iter().collect();
+        assert_eq!(keys.len(), KEY_CODES.len());
+        assert_eq!(keys.len(), 115);
+        assert_eq!(LogiLed_KeyName::iter().next(), Some(LogiLed_KeyName::ESC));
+        assert_eq!(
+            LogiLed_KeyName::iter().last(),
+            Some(LogiLed_KeyName::G_BADGE)
+        );
+    }
+
+    #[test]
+    fn names() {
+        for key in LogiLed_KeyName::iter() {
+            assert_eq!(key.name(), format!("{key:?}"));
+            assert_eq!(LogiLed_KeyName::from_name(key.name()), Some(key));
+        }
+
+        assert_eq!(
+            LogiLed_KeyName::from_name("num_enter"),
+            Some(LogiLed_KeyName::NUM_ENTER)
+        );
+        assert_eq!(LogiLed_KeyName::from_name("NUMENTER"), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        for key in LogiLed_KeyName::iter() {
+            let json = serde_json::to_string(&key).expect("failed to serialize");
+            assert_eq!(json, format!("\"{}\"", key.name()));
+            let parsed: LogiLed_KeyName =
+                serde_json::from_str(&json).expect("failed to deserialize");
+            assert_eq!(parsed, key);
+        }
+
+        let error = serde_json::from_str::<LogiLed_KeyName>("\"NUMENTER\"")
+            .expect_err("deserialized an unknown key name");
+        assert_eq!(
+            error.to_string(),
+            "invalid value: string \"NUMENTER\", expected a key name at line 1 column 10"
+        );
+    }
+
+    #[test]
+    fn scan_codes() {
+        let mut seen = HashSet::new();
+        for key in LogiLed_KeyName::iter() {
+            match key.scan_code() {
+                Some(scan_code) => {
+                    assert_eq!(scan_code, key as u32);
+                    assert!(seen.insert(scan_code));
+                    assert_eq!(LogiLed_KeyName::from_scan_code(scan_code), Some(key));
+                }
+                None => assert!((key as u32) >= LogiLed_KeyName::G_1 as u32),
+            }
+        }
+
+        assert_eq!(
+            LogiLed_KeyName::from_scan_code(0x1C),
+            Some(LogiLed_KeyName::ENTER)
+        );
+        assert_eq!(
+            LogiLed_KeyName::from_scan_code(0x11C),
+            Some(LogiLed_KeyName::NUM_ENTER)
+        );
+        assert_eq!(LogiLed_KeyName::from_scan_code(0), None);
+    }
+
+    #[test]
+    fn hid_usages() {
+        let mut seen = HashSet::new();
+        for key in LogiLed_KeyName::iter() {
+            if let Some(hid_usage) = key.hid_usage() {
+                // Keyboard/Keypad page: keys are 0x04-0x65, modifiers are 0xE0-0xE7.
+                assert!(
+                    (0x04..=0x65).contains(&hid_usage) || (0xE0..=0xE7).contains(&hid_usage),
+                    "{key:?} has an invalid HID usage {hid_usage:#04X}"
+                );
+                assert!(seen.insert(hid_usage));
+                assert_eq!(LogiLed_KeyName::from_hid_usage(hid_usage), Some(key));
+            }
+        }
+
+        // Letters and digits are contiguous.
+        let letters = [
+            LogiLed_KeyName::A,
+            LogiLed_KeyName::B,
+            LogiLed_KeyName::C,
+            LogiLed_KeyName::D,
+            LogiLed_KeyName::E,
+            LogiLed_KeyName::F,
+            LogiLed_KeyName::G,
+            LogiLed_KeyName::H,
+            LogiLed_KeyName::I,
+            LogiLed_KeyName::J,
+            LogiLed_KeyName::K,
+            LogiLed_KeyName::L,
+            LogiLed_KeyName::M,
+            LogiLed_KeyName::N,
+            LogiLed_KeyName::O,
+            LogiLed_KeyName::P,
+            LogiLed_KeyName::Q,
+            LogiLed_KeyName::R,
+            LogiLed_KeyName::S,
+            LogiLed_KeyName::T,
+            LogiLed_KeyName::U,
+            LogiLed_KeyName::V,
+            LogiLed_KeyName::W,
+            LogiLed_KeyName::X,
+            LogiLed_KeyName::Y,
+            LogiLed_KeyName::Z,
+        ];
+        for (i, key) in letters.into_iter().enumerate() {
+            assert_eq!(key.hid_usage(), Some(0x04 + i as u32));
+        }
+        let digits = [
+            LogiLed_KeyName::ONE,
+            LogiLed_KeyName::TWO,
+            LogiLed_KeyName::THREE,
+            LogiLed_KeyName::FOUR,
+            LogiLed_KeyName::FIVE,
+            LogiLed_KeyName::SIX,
+            LogiLed_KeyName::SEVEN,
+            LogiLed_KeyName::EIGHT,
+            LogiLed_KeyName::NINE,
+            LogiLed_KeyName::ZERO,
+        ];
+        for (i, key) in digits.into_iter().enumerate() {
+            assert_eq!(key.hid_usage(), Some(0x1E + i as u32));
+        }
+
+        assert_eq!(LogiLed_KeyName::F12.hid_usage(), Some(0x45));
+        assert_eq!(LogiLed_KeyName::NUM_ENTER.hid_usage(), Some(0x58));
+        assert_eq!(LogiLed_KeyName::RIGHT_WINDOWS.hid_usage(), Some(0xE7));
+        assert_eq!(LogiLed_KeyName::G_LOGO.hid_usage(), None);
+        assert_eq!(LogiLed_KeyName::from_hid_usage(0x32), None);
+    }
+}
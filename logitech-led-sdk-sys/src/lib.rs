@@ -1,18 +1,41 @@
+//! A low level Rust binding for the Logitech LED SDK.
+//!
+//! The library is only linked on Windows.
+//! On other platforms, only the types and constants are usable; calling any of the functions will fail to link.
+//!
+//! With the `dynamic` feature, the library is never linked.
+//! Instead, it can be loaded at runtime with [`dynamic::Library`].
+//!
+//! With the `serde` feature, [`LogiLed_KeyName`] and [`LogiLed_DeviceType`] implement `Serialize` and `Deserialize` as their names,
+//! like `"NUM_ENTER"` and `"Headset"`.
 #![allow(non_upper_case_globals)]
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 #![allow(clippy::unreadable_literal)]
 
-#[cfg(not(any(target_os = "windows", feature = "rustdoc")))]
-compile_error!("this library will only work on Windows");
-
-#[cfg(not(any(target_env = "msvc", feature = "rustdoc")))]
+#[cfg(all(
+    target_os = "windows",
+    not(any(target_env = "msvc", feature = "rustdoc"))
+))]
 compile_error!("this library will only work on the MSVC target environment");
 
-#[cfg(not(any(target_arch = "x86_64", target_arch = "x86", feature = "rustdoc")))]
+#[cfg(all(
+    target_os = "windows",
+    not(any(target_arch = "x86_64", target_arch = "x86", feature = "rustdoc"))
+))]
 compile_error!("this library will only work on x86 or x86_64");
 
-#[cfg(any(target_arch = "x86_64", feature = "rustdoc"))]
+mod device_type;
+#[cfg(feature = "dynamic")]
+pub mod dynamic;
+mod key_name;
+#[cfg(feature = "serde")]
+mod serde;
+
+pub use self::key_name::KeyCodes;
+pub use self::key_name::KEY_CODES;
+
+#[cfg(any(not(target_arch = "x86"), feature = "rustdoc"))]
 include!("bindings-x86_64.rs");
 
 #[cfg(all(target_arch = "x86", not(feature = "rustdoc")))]
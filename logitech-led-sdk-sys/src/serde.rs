@@ -0,0 +1,40 @@
+use serde::de::Error;
+use serde::de::Unexpected;
+use serde::de::Visitor;
+
+/// Deserialize a value from a name, using `from_name` to look it up.
+///
+/// `expecting` describes the value in error messages, like "a key name".
+pub(crate) fn deserialize_name<'de, D, T>(
+    deserializer: D,
+    expecting: &'static str,
+    from_name: fn(&str) -> Option<T>,
+) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct NameVisitor<T> {
+        expecting: &'static str,
+        from_name: fn(&str) -> Option<T>,
+    }
+
+    impl<T> Visitor<'_> for NameVisitor<T> {
+        type Value = T;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(self.expecting)
+        }
+
+        fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            (self.from_name)(value).ok_or_else(|| E::invalid_value(Unexpected::Str(value), &self))
+        }
+    }
+
+    deserializer.deserialize_str(NameVisitor {
+        expecting,
+        from_name,
+    })
+}
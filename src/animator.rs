@@ -0,0 +1,452 @@
+use crate::Clock;
+use crate::Error;
+use crate::Frame;
+use crate::KeyName;
+use crate::Sdk;
+use crate::SystemClock;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The frame rate of a new [`Animator`], in frames per second.
+pub const DEFAULT_FRAME_RATE: u32 = 30;
+
+/// A lighting effect, drawn frame by frame by an [`Animator`].
+///
+/// This is implemented for closures that take the same arguments as [`Effect::render`].
+pub trait Effect {
+    /// Draw the effect onto a frame.
+    ///
+    /// `time` is the time since the animation started, not counting time spent paused.
+    fn render(&mut self, time: Duration, frame: &mut Frame);
+
+    /// Handle a key press.
+    ///
+    /// `time` is the time since the animation started, like in [`Effect::render`].
+    /// This does nothing by default.
+    fn on_key_press(&mut self, _key: KeyName, _time: Duration) {}
+}
+
+impl<F> Effect for F
+where
+    F: FnMut(Duration, &mut Frame),
+{
+    fn render(&mut self, time: Duration, frame: &mut Frame) {
+        self(time, frame)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Stopped,
+    Running {
+        /// The clock time when the animation started, moved forward by any pauses.
+        start: Duration,
+    },
+    Paused {
+        /// The animation time when the animation was paused.
+        time: Duration,
+    },
+}
+
+/// A handle that stops an [`Animator`], or another loop that runs until stopped, from another thread.
+#[derive(Debug, Clone)]
+pub struct StopHandle {
+    stop_requested: Arc<AtomicBool>,
+}
+
+impl StopHandle {
+    /// Create a handle that sets a stop flag.
+    pub(crate) fn new(stop_requested: Arc<AtomicBool>) -> Self {
+        Self { stop_requested }
+    }
+
+    /// Ask the animator or loop to stop.
+    ///
+    /// An animator stops at its next tick.
+    /// A stop requested before [`Animator::run`] is called stops the run at its first tick.
+    pub fn stop(&self) {
+        self.stop_requested.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Runs effects at a fixed frame rate, sending each frame to an [`Sdk`].
+///
+/// Effects are drawn in the order they were added onto a frame that starts with every key turned off,
+/// so later effects draw over earlier ones.
+///
+/// The animator does not start a thread.
+/// Either call [`Animator::tick`] regularly from an existing loop, or call [`Animator::run`] to block until stopped.
+/// Time comes from a [`Clock`], so effects can be tested deterministically with a [`ManualClock`](crate::ManualClock).
+pub struct Animator<C = SystemClock> {
+    clock: C,
+    frame_interval: Duration,
+    effects: Vec<Box<dyn Effect>>,
+    state: State,
+    next_frame: Duration,
+    stop_requested: Arc<AtomicBool>,
+}
+
+impl Animator<SystemClock> {
+    /// Create a new stopped animator that follows real time.
+    pub fn new() -> Self {
+        Self::with_clock(SystemClock::new())
+    }
+}
+
+impl<C> Animator<C>
+where
+    C: Clock,
+{
+    /// Create a new stopped animator that uses the given clock.
+    pub fn with_clock(clock: C) -> Self {
+        let mut animator = Self {
+            clock,
+            frame_interval: Duration::ZERO,
+            effects: Vec::new(),
+            state: State::Stopped,
+            next_frame: Duration::ZERO,
+            stop_requested: Arc::new(AtomicBool::new(false)),
+        };
+        animator.set_frame_rate(DEFAULT_FRAME_RATE);
+        animator
+    }
+
+    /// Set the frame rate, in frames per second.
+    ///
+    /// A frame rate of 0 is treated as 1.
+    pub fn set_frame_rate(&mut self, frame_rate: u32) {
+        self.frame_interval = Duration::from_secs(1) / frame_rate.max(1);
+    }
+
+    /// Get the time between frames.
+    pub fn frame_interval(&self) -> Duration {
+        self.frame_interval
+    }
+
+    /// Add an effect, drawn over the effects that were added before it.
+    pub fn add_effect<E>(&mut self, effect: E)
+    where
+        E: Effect + 'static,
+    {
+        self.effects.push(Box::new(effect));
+    }
+
+    /// Remove all effects.
+    pub fn clear_effects(&mut self) {
+        self.effects.clear();
+    }
+
+    /// Get a handle that can stop this animator from another thread.
+    pub fn stop_handle(&self) -> StopHandle {
+        StopHandle::new(self.stop_requested.clone())
+    }
+
+    /// Start the animation from the beginning, or resume it if it is paused.
+    ///
+    /// This does nothing if the animation is already running.
+    pub fn start(&mut self) {
+        match self.state {
+            State::Stopped => {
+                self.state = State::Running {
+                    start: self.clock.now(),
+                };
+                self.next_frame = Duration::ZERO;
+            }
+            State::Paused { .. } => self.resume(),
+            State::Running { .. } => {}
+        }
+    }
+
+    /// Stop the animation.
+    ///
+    /// The lighting is left as it was after the last frame.
+    pub fn stop(&mut self) {
+        self.state = State::Stopped;
+    }
+
+    /// Pause the animation, keeping its current time.
+    ///
+    /// This does nothing if the animation is not running.
+    pub fn pause(&mut self) {
+        if let State::Running { .. } = self.state {
+            self.state = State::Paused { time: self.time() };
+        }
+    }
+
+    /// Resume a paused animation from where it was paused.
+    ///
+    /// This does nothing if the animation is not paused.
+    pub fn resume(&mut self) {
+        if let State::Paused { time } = self.state {
+            self.state = State::Running {
+                start: self.clock.now().saturating_sub(time),
+            };
+        }
+    }
+
+    /// Returns true if the animation is running.
+    pub fn is_running(&self) -> bool {
+        matches!(self.state, State::Running { .. })
+    }
+
+    /// Returns true if the animation is paused.
+    pub fn is_paused(&self) -> bool {
+        matches!(self.state, State::Paused { .. })
+    }
+
+    /// Get the time since the animation started, not counting time spent paused.
+    ///
+    /// This is zero if the animation is stopped.
+    pub fn time(&self) -> Duration {
+        match self.state {
+            State::Stopped => Duration::ZERO,
+            State::Running { start } => self.clock.now().saturating_sub(start),
+            State::Paused { time } => time,
+        }
+    }
+
+    /// Get the time until the next frame is due.
+    ///
+    /// # Returns
+    /// Returns None if the animation is not running.
+    pub fn time_until_next_frame(&self) -> Option<Duration> {
+        if !self.is_running() {
+            return None;
+        }
+
+        Some(self.next_frame.saturating_sub(self.time()))
+    }
+
+    /// Pass a key press to every effect.
+    ///
+    /// This does nothing if the animation is not running.
+    pub fn key_press(&mut self, key: KeyName) {
+        if !self.is_running() {
+            return;
+        }
+
+        let time = self.time();
+        for effect in self.effects.iter_mut() {
+            effect.on_key_press(key, time);
+        }
+    }
+
+    /// Draw every effect at the current time, without sending the frame anywhere.
+    pub fn render(&mut self) -> Frame {
+        let time = self.time();
+        let mut frame = Frame::new();
+        for effect in self.effects.iter_mut() {
+            effect.render(time, &mut frame);
+        }
+        frame
+    }
+
+    /// Draw and send a frame if the animation is running and a frame is due.
+    ///
+    /// If frames were missed, they are skipped rather than sent late.
+    ///
+    /// # Returns
+    /// Returns true if a frame was sent.
+    ///
+    /// # Errors
+    /// Returns an error if the frame could not be sent.
+    pub fn tick(&mut self, sdk: &Sdk) -> Result<bool, Error> {
+        if self.stop_requested.swap(false, Ordering::SeqCst) {
+            self.stop();
+        }
+
+        let time = self.time();
+        if !self.is_running() || time < self.next_frame {
+            return Ok(false);
+        }
+
+        self.next_frame += self.frame_interval;
+        if self.next_frame <= time {
+            self.next_frame = time + self.frame_interval;
+        }
+
+        let frame = self.render();
+        sdk.set_lighting_from_frame(&frame)?;
+
+        Ok(true)
+    }
+
+    /// Start the animation and send frames until it is stopped with a [`StopHandle`].
+    ///
+    /// # Errors
+    /// Returns an error if a frame could not be sent.
+    /// The animation is stopped when this returns.
+    pub fn run(&mut self, sdk: &Sdk) -> Result<(), Error> {
+        self.start();
+        while self.is_running() {
+            if let Err(e) = self.tick(sdk) {
+                self.stop();
+                self.stop_requested.store(false, Ordering::SeqCst);
+                return Err(e);
+            }
+
+            // Do not wait for the next frame if a stop was requested during this one.
+            if self.stop_requested.load(Ordering::SeqCst) {
+                continue;
+            }
+
+            if let Some(duration) = self.time_until_next_frame() {
+                self.clock.sleep(duration);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Animator<SystemClock> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> std::fmt::Debug for Animator<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Animator")
+            .field("frame_interval", &self.frame_interval)
+            .field("effects", &self.effects.len())
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::effects::Ripple;
+    use crate::ColorPercent;
+    use crate::ManualClock;
+    use crate::MockBackend;
+    use crate::MockCall;
+
+    /// Light the W key brighter as time goes on, at 1 percent per 10 milliseconds.
+    fn ramp(time: Duration, frame: &mut Frame) {
+        let percent = (time.as_millis() / 10).min(100) as u8;
+        frame.set(
+            KeyName::W,
+            ColorPercent::new_percent(percent, 0, 0).unwrap(),
+        );
+    }
+
+    #[test]
+    fn tick() {
+        let backend = MockBackend::new();
+        let sdk = Sdk::new_with_backend(backend.clone()).expect("failed to init mock sdk");
+        let clock = ManualClock::new();
+        let mut animator = Animator::with_clock(clock.clone());
+        animator.set_frame_rate(10);
+        animator.add_effect(ramp);
+
+        // Nothing is sent while stopped.
+        assert_eq!(animator.tick(&sdk), Ok(false));
+        assert_eq!(animator.time_until_next_frame(), None);
+
+        animator.start();
+        assert_eq!(animator.tick(&sdk), Ok(true));
+        assert_eq!(
+            backend.key_color(KeyName::W),
+            ColorPercent::new_percent(0, 0, 0)
+        );
+
+        clock.advance(Duration::from_millis(50));
+        assert_eq!(animator.tick(&sdk), Ok(false));
+        assert_eq!(
+            animator.time_until_next_frame(),
+            Some(Duration::from_millis(50))
+        );
+
+        clock.advance(Duration::from_millis(50));
+        assert_eq!(animator.tick(&sdk), Ok(true));
+        assert_eq!(
+            backend.key_color(KeyName::W),
+            ColorPercent::new_percent(10, 0, 0)
+        );
+
+        // Paused time does not count.
+        animator.pause();
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(animator.tick(&sdk), Ok(false));
+        animator.resume();
+        assert_eq!(animator.time(), Duration::from_millis(100));
+
+        // Missed frames are skipped.
+        clock.advance(Duration::from_millis(350));
+        assert_eq!(animator.tick(&sdk), Ok(true));
+        assert_eq!(
+            backend.key_color(KeyName::W),
+            ColorPercent::new_percent(45, 0, 0)
+        );
+        assert_eq!(
+            animator.time_until_next_frame(),
+            Some(Duration::from_millis(100))
+        );
+
+        animator.stop();
+        assert_eq!(animator.time(), Duration::ZERO);
+        assert_eq!(animator.tick(&sdk), Ok(false));
+    }
+
+    #[test]
+    fn key_press() {
+        let clock = ManualClock::new();
+        let mut animator = Animator::with_clock(clock.clone());
+        let white = ColorPercent::new_percent(100, 100, 100).unwrap();
+        animator.add_effect(Ripple::new(ColorPercent { r: 0, g: 0, b: 0 }, white));
+
+        // Key presses are ignored while stopped.
+        animator.key_press(KeyName::G);
+        animator.start();
+        assert_eq!(animator.render().iter().count(), 0);
+
+        clock.advance(Duration::from_secs(5));
+        animator.key_press(KeyName::G);
+        assert_eq!(animator.render().get(KeyName::G), white);
+    }
+
+    #[test]
+    fn run_until_stopped() {
+        let backend = MockBackend::new();
+        let sdk = Sdk::new_with_backend(backend.clone()).expect("failed to init mock sdk");
+        let clock = ManualClock::new();
+        let mut animator = Animator::with_clock(clock.clone());
+        animator.set_frame_rate(20);
+
+        let stop_handle = animator.stop_handle();
+        let mut frames = 0;
+        animator.add_effect(move |_time: Duration, _frame: &mut Frame| {
+            frames += 1;
+            if frames % 5 == 0 {
+                stop_handle.stop();
+            }
+        });
+
+        backend.take_calls();
+        animator.run(&sdk).expect("failed to run animator");
+        assert!(!animator.is_running());
+        assert_eq!(clock.now(), Duration::from_millis(200));
+
+        let frames = backend
+            .take_calls()
+            .into_iter()
+            .filter(|call| matches!(call, MockCall::SetLightingFromBitmap { .. }))
+            .count();
+        assert_eq!(frames, 5);
+
+        // A stop requested before running is not lost.
+        animator.stop_handle().stop();
+        animator.run(&sdk).expect("failed to run animator");
+        assert!(!animator.is_running());
+        assert!(backend.take_calls().is_empty());
+
+        backend.set_failing("LogiLedSetLighting", true);
+        assert!(animator.run(&sdk).is_err());
+        assert!(!animator.is_running());
+    }
+}
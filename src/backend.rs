@@ -0,0 +1,188 @@
+#[cfg(any(test, feature = "mock"))]
+mod mock;
+#[cfg(any(target_os = "windows", feature = "rustdoc", feature = "dynamic"))]
+mod native;
+#[cfg(feature = "simulator")]
+mod simulator;
+#[cfg(any(target_os = "windows", feature = "rustdoc", feature = "dynamic", test))]
+mod wide;
+
+#[cfg(any(test, feature = "mock"))]
+pub use self::mock::MockBackend;
+#[cfg(any(test, feature = "mock"))]
+pub use self::mock::MockCall;
+#[cfg(any(test, feature = "mock"))]
+pub use self::mock::MockConfigValue;
+#[cfg(any(target_os = "windows", feature = "rustdoc", feature = "dynamic"))]
+pub use self::native::NativeBackend;
+#[cfg(feature = "simulator")]
+pub use self::simulator::SimulatorBackend;
+
+use crate::ColorPercent;
+use crate::DeviceType;
+use crate::KeyName;
+use crate::KeyboardBitmap;
+use crate::TargetDevice;
+use std::ffi::CStr;
+use std::os::raw::c_int;
+
+/// An implementation of the sdk functions.
+///
+/// Every call that [`Sdk`](crate::Sdk) makes goes through a backend.
+/// Each function mirrors the sdk function of the same name,
+/// taking Rust types instead of raw pointers.
+/// Arguments have already been validated by [`Sdk`](crate::Sdk) by the time they get here.
+///
+/// Functions return false or None to signal that the call failed.
+pub trait Backend {
+    /// Initialize the sdk.
+    fn init(&self) -> bool;
+
+    /// Initialize the sdk with an application name.
+    fn init_with_name(&self, name: &CStr) -> bool;
+
+    /// Get the major, minor and build numbers of the sdk.
+    fn get_sdk_version(&self) -> Option<(c_int, c_int, c_int)>;
+
+    /// Get a number config option.
+    fn get_config_option_number(&self, path: &str, default: f64) -> Option<f64>;
+
+    /// Get a bool config option.
+    fn get_config_option_bool(&self, path: &str, default: bool) -> Option<bool>;
+
+    /// Get a color config option, as RGB values from 0-255.
+    fn get_config_option_color(
+        &self,
+        path: &str,
+        default: (c_int, c_int, c_int),
+    ) -> Option<(c_int, c_int, c_int)>;
+
+    /// Get a rectangle config option, as the x, y, width and height.
+    fn get_config_option_rect(
+        &self,
+        path: &str,
+        default: (c_int, c_int, c_int, c_int),
+    ) -> Option<(c_int, c_int, c_int, c_int)>;
+
+    /// Get a string config option.
+    fn get_config_option_string(&self, path: &str, default: &str) -> Option<String>;
+
+    /// Get a key input config option.
+    fn get_config_option_key_input(&self, path: &str, default: &str) -> Option<String>;
+
+    /// Get a select config option.
+    ///
+    /// The default is always one of the options.
+    fn get_config_option_select(
+        &self,
+        path: &str,
+        default: &str,
+        options: &[&str],
+    ) -> Option<String>;
+
+    /// Get a ranged integer config option.
+    ///
+    /// The default is always in the range.
+    fn get_config_option_range(
+        &self,
+        path: &str,
+        default: c_int,
+        min: c_int,
+        max: c_int,
+    ) -> Option<c_int>;
+
+    /// Set the label of a config option or group.
+    fn set_config_option_label(&self, path: &str, label: &str) -> bool;
+
+    /// Select the target devices.
+    fn set_target_device(&self, target_device: TargetDevice) -> bool;
+
+    /// Save the lighting of all devices.
+    fn save_current_lighting(&self) -> bool;
+
+    /// Set the lighting of all devices.
+    fn set_lighting(&self, color: ColorPercent) -> bool;
+
+    /// Restore the lighting of all devices saved by [`Backend::save_current_lighting`].
+    fn restore_lighting(&self) -> bool;
+
+    /// Flash the lighting of all devices.
+    ///
+    /// Times are in milliseconds.
+    fn flash_lighting(&self, color: ColorPercent, duration: c_int, interval: c_int) -> bool;
+
+    /// Pulse the lighting of all devices.
+    ///
+    /// Times are in milliseconds.
+    fn pulse_lighting(&self, color: ColorPercent, duration: c_int, interval: c_int) -> bool;
+
+    /// Stop all effects.
+    fn stop_effects(&self) -> bool;
+
+    /// Set the lighting of the keyboard from a bitmap.
+    fn set_lighting_from_bitmap(&self, bitmap: &KeyboardBitmap) -> bool;
+
+    /// Set the lighting of a key by scan code.
+    fn set_lighting_for_key_with_scan_code(&self, scan_code: c_int, color: ColorPercent) -> bool;
+
+    /// Set the lighting of a key by HID code.
+    fn set_lighting_for_key_with_hid_code(&self, hid_code: c_int, color: ColorPercent) -> bool;
+
+    /// Set the lighting of a key by macOS virtual key code.
+    fn set_lighting_for_key_with_quartz_code(
+        &self,
+        quartz_code: c_int,
+        color: ColorPercent,
+    ) -> bool;
+
+    /// Set the lighting of a key by key name.
+    fn set_lighting_for_key_with_key_name(&self, key: KeyName, color: ColorPercent) -> bool;
+
+    /// Save the lighting of a key.
+    fn save_lighting_for_key(&self, key: KeyName) -> bool;
+
+    /// Restore the lighting of a key.
+    fn restore_lighting_for_key(&self, key: KeyName) -> bool;
+
+    /// Set the keys that bitmaps will not affect.
+    ///
+    /// The list never contains duplicates.
+    fn exclude_keys_from_bitmap(&self, keys: &[KeyName]) -> bool;
+
+    /// Flash a key.
+    ///
+    /// Times are in milliseconds.
+    fn flash_single_key(
+        &self,
+        key: KeyName,
+        color: ColorPercent,
+        duration: c_int,
+        interval: c_int,
+    ) -> bool;
+
+    /// Pulse a key.
+    ///
+    /// The duration is in milliseconds.
+    fn pulse_single_key(
+        &self,
+        key: KeyName,
+        start_color: ColorPercent,
+        end_color: ColorPercent,
+        duration: c_int,
+        is_infinite: bool,
+    ) -> bool;
+
+    /// Stop all effects on a key.
+    fn stop_effects_on_key(&self, key: KeyName) -> bool;
+
+    /// Set the lighting of a zone on a device.
+    fn set_lighting_for_target_zone(
+        &self,
+        device: DeviceType,
+        zone: c_int,
+        color: ColorPercent,
+    ) -> bool;
+
+    /// Shut down the sdk.
+    fn shutdown(&self);
+}
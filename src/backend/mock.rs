@@ -0,0 +1,688 @@
+use super::Backend;
+use crate::ColorPercent;
+use crate::DeviceType;
+use crate::KeyName;
+use crate::KeyboardBitmap;
+use crate::TargetDevice;
+use crate::BITMAP_KEY_POSITIONS;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::ffi::CStr;
+use std::os::raw::c_int;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+
+/// A call made to a [`MockBackend`], with its arguments.
+///
+/// This is the same type as [`Call`](crate::Call).
+pub type MockCall = crate::Call;
+
+/// The value of a config option stored in a [`MockBackend`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockConfigValue {
+    Number(f64),
+    Bool(bool),
+    Color((c_int, c_int, c_int)),
+    Rect((c_int, c_int, c_int, c_int)),
+    String(String),
+    KeyInput(String),
+    Select(String),
+    Range(c_int),
+}
+
+/// The lighting of all devices, as saved by `LogiLedSaveCurrentLighting`.
+#[derive(Debug, Clone)]
+struct MockLighting {
+    lighting: Option<ColorPercent>,
+    keys: HashMap<KeyName, ColorPercent>,
+    zones: HashMap<(DeviceType, c_int), ColorPercent>,
+}
+
+#[derive(Debug)]
+struct MockState {
+    calls: Vec<MockCall>,
+    failing: HashSet<&'static str>,
+    sdk_version: (c_int, c_int, c_int),
+
+    initialized: bool,
+    name: Option<String>,
+    config: HashMap<String, MockConfigValue>,
+    config_labels: HashMap<String, String>,
+
+    target_device: TargetDevice,
+    lighting: Option<ColorPercent>,
+    keys: HashMap<KeyName, ColorPercent>,
+    saved_keys: HashMap<KeyName, Option<ColorPercent>>,
+    excluded_keys: Vec<KeyName>,
+    zones: HashMap<(DeviceType, c_int), ColorPercent>,
+    saved_lighting: Option<MockLighting>,
+}
+
+impl MockState {
+    fn key_color(&self, key: KeyName) -> Option<ColorPercent> {
+        self.keys.get(&key).copied().or(self.lighting)
+    }
+}
+
+/// An in-memory backend for testing.
+///
+/// This records every call made to it and simulates the resulting LED state,
+/// without needing LGS, LG HUB or even Windows.
+/// Clones share the same state, so keep a clone around to inspect a backend after giving it to an [`Sdk`](crate::Sdk).
+///
+/// Like the real sdk, every call fails until the backend is initialized.
+/// The simulated state is a simplification: the target device is recorded but not used to filter calls,
+/// and sdk-side effects like flashes and pulses do not change the steady-state colors.
+/// Keys set by scan code, HID code or macOS virtual key code are translated with [`KEY_CODES`](crate::KEY_CODES).
+#[derive(Debug, Clone)]
+pub struct MockBackend {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockBackend {
+    /// Create a new mock backend.
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MockState {
+                calls: Vec::new(),
+                failing: HashSet::new(),
+                sdk_version: (9, 0, 0),
+
+                initialized: false,
+                name: None,
+                config: HashMap::new(),
+                config_labels: HashMap::new(),
+
+                target_device: TargetDevice::All,
+                lighting: None,
+                keys: HashMap::new(),
+                saved_keys: HashMap::new(),
+                excluded_keys: Vec::new(),
+                zones: HashMap::new(),
+                saved_lighting: None,
+            })),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Record a call, returning the locked state if the call should go through.
+    fn call(&self, call: MockCall) -> Option<MutexGuard<'_, MockState>> {
+        let mut state = self.lock();
+        let function = call.function();
+        let is_init = matches!(call, MockCall::Init | MockCall::InitWithName { .. });
+        state.calls.push(call);
+
+        if state.failing.contains(function) || !(is_init || state.initialized) {
+            return None;
+        }
+
+        Some(state)
+    }
+
+    /// Get all recorded calls.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.lock().calls.clone()
+    }
+
+    /// Get and clear all recorded calls.
+    pub fn take_calls(&self) -> Vec<MockCall> {
+        std::mem::take(&mut self.lock().calls)
+    }
+
+    /// Make calls to the sdk function with the given name, like `"LogiLedSetLighting"`, fail or succeed.
+    pub fn set_failing(&self, function: &'static str, failing: bool) {
+        let mut state = self.lock();
+        if failing {
+            state.failing.insert(function);
+        } else {
+            state.failing.remove(function);
+        }
+    }
+
+    /// Set the version reported by the backend.
+    pub fn set_sdk_version(&self, major: c_int, minor: c_int, build: c_int) {
+        self.lock().sdk_version = (major, minor, build);
+    }
+
+    /// Returns true if the backend is initialized.
+    pub fn is_initialized(&self) -> bool {
+        self.lock().initialized
+    }
+
+    /// Get the application name passed at initialization, if any.
+    pub fn name(&self) -> Option<String> {
+        self.lock().name.clone()
+    }
+
+    /// Get the value of a config option.
+    pub fn config(&self, path: &str) -> Option<MockConfigValue> {
+        self.lock().config.get(path).cloned()
+    }
+
+    /// Set the value of a config option, simulating a user changing it.
+    pub fn set_config(&self, path: &str, value: MockConfigValue) {
+        self.lock().config.insert(path.to_string(), value);
+    }
+
+    /// Get the label of a config option or group.
+    pub fn config_label(&self, path: &str) -> Option<String> {
+        self.lock().config_labels.get(path).cloned()
+    }
+
+    /// Get the selected target devices.
+    pub fn target_device(&self) -> TargetDevice {
+        self.lock().target_device
+    }
+
+    /// Get the color last set for all devices, if any.
+    pub fn lighting(&self) -> Option<ColorPercent> {
+        self.lock().lighting
+    }
+
+    /// Get the color of a key, if it was set.
+    pub fn key_color(&self, key: KeyName) -> Option<ColorPercent> {
+        self.lock().key_color(key)
+    }
+
+    /// Get the color of a zone on a device, if it was set.
+    pub fn zone_color(&self, device: DeviceType, zone: c_int) -> Option<ColorPercent> {
+        let state = self.lock();
+        state.zones.get(&(device, zone)).copied().or(state.lighting)
+    }
+
+    /// Get the keys excluded from bitmaps.
+    pub fn excluded_keys(&self) -> Vec<KeyName> {
+        self.lock().excluded_keys.clone()
+    }
+
+    /// Get a config value, storing the default if it does not exist or has a different type.
+    fn get_config<T>(
+        &self,
+        call: MockCall,
+        path: &str,
+        default: T,
+        wrap: fn(T) -> MockConfigValue,
+        unwrap: fn(&MockConfigValue) -> Option<T>,
+    ) -> Option<T> {
+        let mut state = self.call(call)?;
+        if let Some(value) = state.config.get(path).and_then(unwrap) {
+            return Some(value);
+        }
+
+        let value = wrap(default);
+        let ret = unwrap(&value);
+        state.config.insert(path.to_string(), value);
+        ret
+    }
+}
+
+impl Default for MockBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Backend for MockBackend {
+    fn init(&self) -> bool {
+        match self.call(MockCall::Init) {
+            Some(mut state) => {
+                state.initialized = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn init_with_name(&self, name: &CStr) -> bool {
+        let name = name.to_string_lossy().into_owned();
+        match self.call(MockCall::InitWithName { name: name.clone() }) {
+            Some(mut state) => {
+                state.initialized = true;
+                state.name = Some(name);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn get_sdk_version(&self) -> Option<(c_int, c_int, c_int)> {
+        let state = self.call(MockCall::GetSdkVersion)?;
+        Some(state.sdk_version)
+    }
+
+    fn get_config_option_number(&self, path: &str, default: f64) -> Option<f64> {
+        let call = MockCall::GetConfigOptionNumber {
+            path: path.to_string(),
+            default,
+        };
+        self.get_config(
+            call,
+            path,
+            default,
+            MockConfigValue::Number,
+            |value| match value {
+                MockConfigValue::Number(value) => Some(*value),
+                _ => None,
+            },
+        )
+    }
+
+    fn get_config_option_bool(&self, path: &str, default: bool) -> Option<bool> {
+        let call = MockCall::GetConfigOptionBool {
+            path: path.to_string(),
+            default,
+        };
+        self.get_config(
+            call,
+            path,
+            default,
+            MockConfigValue::Bool,
+            |value| match value {
+                MockConfigValue::Bool(value) => Some(*value),
+                _ => None,
+            },
+        )
+    }
+
+    fn get_config_option_color(
+        &self,
+        path: &str,
+        default: (c_int, c_int, c_int),
+    ) -> Option<(c_int, c_int, c_int)> {
+        let call = MockCall::GetConfigOptionColor {
+            path: path.to_string(),
+            default,
+        };
+        self.get_config(
+            call,
+            path,
+            default,
+            MockConfigValue::Color,
+            |value| match value {
+                MockConfigValue::Color(value) => Some(*value),
+                _ => None,
+            },
+        )
+    }
+
+    fn get_config_option_rect(
+        &self,
+        path: &str,
+        default: (c_int, c_int, c_int, c_int),
+    ) -> Option<(c_int, c_int, c_int, c_int)> {
+        let call = MockCall::GetConfigOptionRect {
+            path: path.to_string(),
+            default,
+        };
+        self.get_config(
+            call,
+            path,
+            default,
+            MockConfigValue::Rect,
+            |value| match value {
+                MockConfigValue::Rect(value) => Some(*value),
+                _ => None,
+            },
+        )
+    }
+
+    fn get_config_option_string(&self, path: &str, default: &str) -> Option<String> {
+        let call = MockCall::GetConfigOptionString {
+            path: path.to_string(),
+            default: default.to_string(),
+        };
+        self.get_config(
+            call,
+            path,
+            default.to_string(),
+            MockConfigValue::String,
+            |value| match value {
+                MockConfigValue::String(value) => Some(value.clone()),
+                _ => None,
+            },
+        )
+    }
+
+    fn get_config_option_key_input(&self, path: &str, default: &str) -> Option<String> {
+        let call = MockCall::GetConfigOptionKeyInput {
+            path: path.to_string(),
+            default: default.to_string(),
+        };
+        self.get_config(
+            call,
+            path,
+            default.to_string(),
+            MockConfigValue::KeyInput,
+            |value| match value {
+                MockConfigValue::KeyInput(value) => Some(value.clone()),
+                _ => None,
+            },
+        )
+    }
+
+    fn get_config_option_select(
+        &self,
+        path: &str,
+        default: &str,
+        options: &[&str],
+    ) -> Option<String> {
+        let call = MockCall::GetConfigOptionSelect {
+            path: path.to_string(),
+            default: default.to_string(),
+            options: options.iter().map(|option| option.to_string()).collect(),
+        };
+        let mut state = self.call(call)?;
+        if let Some(MockConfigValue::Select(value)) = state.config.get(path) {
+            if options.contains(&value.as_str()) {
+                return Some(value.clone());
+            }
+        }
+
+        state.config.insert(
+            path.to_string(),
+            MockConfigValue::Select(default.to_string()),
+        );
+        Some(default.to_string())
+    }
+
+    fn get_config_option_range(
+        &self,
+        path: &str,
+        default: c_int,
+        min: c_int,
+        max: c_int,
+    ) -> Option<c_int> {
+        let call = MockCall::GetConfigOptionRange {
+            path: path.to_string(),
+            default,
+            min,
+            max,
+        };
+        let mut state = self.call(call)?;
+        if let Some(MockConfigValue::Range(value)) = state.config.get(path) {
+            return Some((*value).clamp(min, max));
+        }
+
+        state
+            .config
+            .insert(path.to_string(), MockConfigValue::Range(default));
+        Some(default)
+    }
+
+    fn set_config_option_label(&self, path: &str, label: &str) -> bool {
+        let call = MockCall::SetConfigOptionLabel {
+            path: path.to_string(),
+            label: label.to_string(),
+        };
+        match self.call(call) {
+            Some(mut state) => {
+                state
+                    .config_labels
+                    .insert(path.to_string(), label.to_string());
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn set_target_device(&self, target_device: TargetDevice) -> bool {
+        match self.call(MockCall::SetTargetDevice { target_device }) {
+            Some(mut state) => {
+                state.target_device = target_device;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn save_current_lighting(&self) -> bool {
+        match self.call(MockCall::SaveCurrentLighting) {
+            Some(mut state) => {
+                state.saved_lighting = Some(MockLighting {
+                    lighting: state.lighting,
+                    keys: state.keys.clone(),
+                    zones: state.zones.clone(),
+                });
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn set_lighting(&self, color: ColorPercent) -> bool {
+        match self.call(MockCall::SetLighting { color }) {
+            Some(mut state) => {
+                state.lighting = Some(color);
+                state.keys.clear();
+                state.zones.clear();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn restore_lighting(&self) -> bool {
+        match self.call(MockCall::RestoreLighting) {
+            Some(mut state) => {
+                if let Some(saved) = state.saved_lighting.clone() {
+                    state.lighting = saved.lighting;
+                    state.keys = saved.keys;
+                    state.zones = saved.zones;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn flash_lighting(&self, color: ColorPercent, duration: c_int, interval: c_int) -> bool {
+        self.call(MockCall::FlashLighting {
+            color,
+            duration,
+            interval,
+        })
+        .is_some()
+    }
+
+    fn pulse_lighting(&self, color: ColorPercent, duration: c_int, interval: c_int) -> bool {
+        self.call(MockCall::PulseLighting {
+            color,
+            duration,
+            interval,
+        })
+        .is_some()
+    }
+
+    fn stop_effects(&self) -> bool {
+        self.call(MockCall::StopEffects).is_some()
+    }
+
+    fn set_lighting_from_bitmap(&self, bitmap: &KeyboardBitmap) -> bool {
+        let call = MockCall::SetLightingFromBitmap {
+            bitmap: Box::new(bitmap.clone()),
+        };
+        match self.call(call) {
+            Some(mut state) => {
+                for (key, x, y) in BITMAP_KEY_POSITIONS.iter().copied() {
+                    if state.excluded_keys.contains(&key) {
+                        continue;
+                    }
+
+                    let color = bitmap.get(x, y).expect("key position in bounds");
+                    state.keys.insert(key, color);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn set_lighting_for_key_with_scan_code(&self, scan_code: c_int, color: ColorPercent) -> bool {
+        match self.call(MockCall::SetLightingForKeyWithScanCode { scan_code, color }) {
+            Some(mut state) => {
+                let key = u32::try_from(scan_code)
+                    .ok()
+                    .and_then(KeyName::from_scan_code);
+                if let Some(key) = key {
+                    state.keys.insert(key, color);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn set_lighting_for_key_with_hid_code(&self, hid_code: c_int, color: ColorPercent) -> bool {
+        match self.call(MockCall::SetLightingForKeyWithHidCode { hid_code, color }) {
+            Some(mut state) => {
+                let key = u32::try_from(hid_code)
+                    .ok()
+                    .and_then(KeyName::from_hid_usage);
+                if let Some(key) = key {
+                    state.keys.insert(key, color);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn set_lighting_for_key_with_quartz_code(
+        &self,
+        quartz_code: c_int,
+        color: ColorPercent,
+    ) -> bool {
+        match self.call(MockCall::SetLightingForKeyWithQuartzCode { quartz_code, color }) {
+            Some(mut state) => {
+                let key = u32::try_from(quartz_code)
+                    .ok()
+                    .and_then(KeyName::from_quartz_code);
+                if let Some(key) = key {
+                    state.keys.insert(key, color);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn set_lighting_for_key_with_key_name(&self, key: KeyName, color: ColorPercent) -> bool {
+        match self.call(MockCall::SetLightingForKeyWithKeyName { key, color }) {
+            Some(mut state) => {
+                state.keys.insert(key, color);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn save_lighting_for_key(&self, key: KeyName) -> bool {
+        match self.call(MockCall::SaveLightingForKey { key }) {
+            Some(mut state) => {
+                let color = state.key_color(key);
+                state.saved_keys.insert(key, color);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn restore_lighting_for_key(&self, key: KeyName) -> bool {
+        match self.call(MockCall::RestoreLightingForKey { key }) {
+            Some(mut state) => {
+                match state.saved_keys.get(&key).copied() {
+                    Some(Some(color)) => {
+                        state.keys.insert(key, color);
+                    }
+                    Some(None) => {
+                        state.keys.remove(&key);
+                    }
+                    None => {}
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn exclude_keys_from_bitmap(&self, keys: &[KeyName]) -> bool {
+        let call = MockCall::ExcludeKeysFromBitmap {
+            keys: keys.to_vec(),
+        };
+        match self.call(call) {
+            Some(mut state) => {
+                state.excluded_keys = keys.to_vec();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn flash_single_key(
+        &self,
+        key: KeyName,
+        color: ColorPercent,
+        duration: c_int,
+        interval: c_int,
+    ) -> bool {
+        self.call(MockCall::FlashSingleKey {
+            key,
+            color,
+            duration,
+            interval,
+        })
+        .is_some()
+    }
+
+    fn pulse_single_key(
+        &self,
+        key: KeyName,
+        start_color: ColorPercent,
+        end_color: ColorPercent,
+        duration: c_int,
+        is_infinite: bool,
+    ) -> bool {
+        self.call(MockCall::PulseSingleKey {
+            key,
+            start_color,
+            end_color,
+            duration,
+            is_infinite,
+        })
+        .is_some()
+    }
+
+    fn stop_effects_on_key(&self, key: KeyName) -> bool {
+        self.call(MockCall::StopEffectsOnKey { key }).is_some()
+    }
+
+    fn set_lighting_for_target_zone(
+        &self,
+        device: DeviceType,
+        zone: c_int,
+        color: ColorPercent,
+    ) -> bool {
+        match self.call(MockCall::SetLightingForTargetZone {
+            device,
+            zone,
+            color,
+        }) {
+            Some(mut state) => {
+                state.zones.insert((device, zone), color);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn shutdown(&self) {
+        if let Some(mut state) = self.call(MockCall::Shutdown) {
+            state.initialized = false;
+        }
+    }
+}
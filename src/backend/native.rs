@@ -0,0 +1,545 @@
+use super::wide::decode_wide;
+use super::wide::encode_wide;
+use super::wide::encode_wide_buffer;
+use super::Backend;
+#[cfg(not(feature = "dynamic"))]
+use crate::sys;
+use crate::ColorPercent;
+use crate::DeviceType;
+use crate::Error;
+use crate::KeyName;
+use crate::KeyboardBitmap;
+use crate::TargetDevice;
+use crate::CONFIG_STRING_CAPACITY;
+use crate::SDK_LOCK;
+use std::ffi::CStr;
+#[cfg(feature = "dynamic")]
+use std::ffi::OsStr;
+use std::os::raw::c_int;
+use std::sync::MutexGuard;
+use std::sync::TryLockError;
+
+/// Call an sdk function, either through the linked library or the loaded one.
+#[cfg(not(feature = "dynamic"))]
+macro_rules! sdk_call {
+    ($backend:expr, $function:ident($($arg:expr),* $(,)?)) => {
+        sys::$function($($arg),*)
+    };
+}
+
+/// Call an sdk function, either through the linked library or the loaded one.
+#[cfg(feature = "dynamic")]
+macro_rules! sdk_call {
+    ($backend:expr, $function:ident($($arg:expr),* $(,)?)) => {
+        ($backend.library.$function)($($arg),*)
+    };
+}
+
+/// A backend that calls into the Logitech LED library.
+///
+/// With the `dynamic` feature, the library is loaded at runtime instead of being linked.
+///
+/// This holds the [`SDK_LOCK`] for as long as it is alive.
+pub struct NativeBackend {
+    #[cfg(feature = "dynamic")]
+    library: crate::sys::dynamic::Library,
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl NativeBackend {
+    /// Create a new native backend.
+    ///
+    /// With the `dynamic` feature, the library is loaded from the first of the
+    /// [`DEFAULT_LIBRARY_PATHS`](crate::sys::dynamic::DEFAULT_LIBRARY_PATHS) that can be opened.
+    ///
+    /// # Errors
+    /// Returns an error if the sdk is already in use in this process,
+    /// or if the library could not be loaded.
+    pub fn new() -> Result<Self, Error> {
+        let guard = lock()?;
+
+        #[cfg(feature = "dynamic")]
+        let library =
+            unsafe { crate::sys::dynamic::Library::open_default() }.map_err(Error::Load)?;
+
+        Ok(Self {
+            #[cfg(feature = "dynamic")]
+            library,
+            _guard: guard,
+        })
+    }
+
+    /// Create a new native backend, loading the library from the given path.
+    ///
+    /// # Errors
+    /// Returns an error if the sdk is already in use in this process,
+    /// or if the library could not be loaded.
+    #[cfg(feature = "dynamic")]
+    pub fn from_library_path<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<OsStr>,
+    {
+        let guard = lock()?;
+        let library = unsafe { crate::sys::dynamic::Library::open(path) }.map_err(Error::Load)?;
+
+        Ok(Self {
+            library,
+            _guard: guard,
+        })
+    }
+}
+
+/// Take the [`SDK_LOCK`].
+fn lock() -> Result<MutexGuard<'static, ()>, Error> {
+    match SDK_LOCK.try_lock() {
+        Ok(guard) => Ok(guard),
+        Err(TryLockError::WouldBlock) => Err(Error::LockHeld),
+        Err(TryLockError::Poisoned(e)) => Ok(e.into_inner()),
+    }
+}
+
+impl std::fmt::Debug for NativeBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NativeBackend").finish_non_exhaustive()
+    }
+}
+
+impl Backend for NativeBackend {
+    fn init(&self) -> bool {
+        unsafe { sdk_call!(self, LogiLedInit()) }
+    }
+
+    fn init_with_name(&self, name: &CStr) -> bool {
+        unsafe { sdk_call!(self, LogiLedInitWithName(name.as_ptr())) }
+    }
+
+    fn get_sdk_version(&self) -> Option<(c_int, c_int, c_int)> {
+        let mut major = 0;
+        let mut minor = 0;
+        let mut build = 0;
+
+        let valid = unsafe {
+            sdk_call!(
+                self,
+                LogiLedGetSdkVersion(&mut major, &mut minor, &mut build)
+            )
+        };
+        if !valid {
+            return None;
+        }
+
+        Some((major, minor, build))
+    }
+
+    fn get_config_option_number(&self, path: &str, default: f64) -> Option<f64> {
+        let path = encode_wide(path)?;
+        let mut value = default;
+
+        let valid = unsafe {
+            sdk_call!(
+                self,
+                LogiLedGetConfigOptionNumber(path.as_ptr(), &mut value)
+            )
+        };
+        if !valid {
+            return None;
+        }
+
+        Some(value)
+    }
+
+    fn get_config_option_bool(&self, path: &str, default: bool) -> Option<bool> {
+        let path = encode_wide(path)?;
+        let mut value = default;
+
+        let valid =
+            unsafe { sdk_call!(self, LogiLedGetConfigOptionBool(path.as_ptr(), &mut value)) };
+        if !valid {
+            return None;
+        }
+
+        Some(value)
+    }
+
+    fn get_config_option_color(
+        &self,
+        path: &str,
+        default: (c_int, c_int, c_int),
+    ) -> Option<(c_int, c_int, c_int)> {
+        let path = encode_wide(path)?;
+        let (mut r, mut g, mut b) = default;
+
+        let valid = unsafe {
+            sdk_call!(
+                self,
+                LogiLedGetConfigOptionColor(path.as_ptr(), &mut r, &mut g, &mut b)
+            )
+        };
+        if !valid {
+            return None;
+        }
+
+        Some((r, g, b))
+    }
+
+    fn get_config_option_rect(
+        &self,
+        path: &str,
+        default: (c_int, c_int, c_int, c_int),
+    ) -> Option<(c_int, c_int, c_int, c_int)> {
+        let path = encode_wide(path)?;
+        let (mut x, mut y, mut width, mut height) = default;
+
+        let valid = unsafe {
+            sdk_call!(
+                self,
+                LogiLedGetConfigOptionRect(path.as_ptr(), &mut x, &mut y, &mut width, &mut height)
+            )
+        };
+        if !valid {
+            return None;
+        }
+
+        Some((x, y, width, height))
+    }
+
+    fn get_config_option_string(&self, path: &str, default: &str) -> Option<String> {
+        let path = encode_wide(path)?;
+        let mut buffer = encode_wide_buffer(default, CONFIG_STRING_CAPACITY)?;
+        let buffer_size = c_int::try_from(buffer.len()).ok()?;
+
+        let valid = unsafe {
+            sdk_call!(
+                self,
+                LogiLedGetConfigOptionString(path.as_ptr(), buffer.as_mut_ptr(), buffer_size)
+            )
+        };
+        if !valid {
+            return None;
+        }
+
+        Some(decode_wide(&buffer))
+    }
+
+    fn get_config_option_key_input(&self, path: &str, default: &str) -> Option<String> {
+        let path = encode_wide(path)?;
+        let mut buffer = encode_wide_buffer(default, CONFIG_STRING_CAPACITY)?;
+        let buffer_size = c_int::try_from(buffer.len()).ok()?;
+
+        let valid = unsafe {
+            sdk_call!(
+                self,
+                LogiLedGetConfigOptionKeyInput(path.as_ptr(), buffer.as_mut_ptr(), buffer_size)
+            )
+        };
+        if !valid {
+            return None;
+        }
+
+        Some(decode_wide(&buffer))
+    }
+
+    fn get_config_option_select(
+        &self,
+        path: &str,
+        default: &str,
+        options: &[&str],
+    ) -> Option<String> {
+        let path = encode_wide(path)?;
+
+        // The options are passed as a list of NUL-terminated strings, terminated by an empty string.
+        let mut values = Vec::new();
+        for option in options {
+            values.extend(encode_wide(option)?);
+        }
+        values.push(0);
+        let values_size = c_int::try_from(values.len()).ok()?;
+
+        let longest = options
+            .iter()
+            .map(|option| option.encode_utf16().count())
+            .max()
+            .unwrap_or(0);
+        let mut buffer = encode_wide_buffer(default, longest + 1)?;
+        let mut buffer_size = c_int::try_from(buffer.len()).ok()?;
+
+        let valid = unsafe {
+            sdk_call!(
+                self,
+                LogiLedGetConfigOptionSelect(
+                    path.as_ptr(),
+                    buffer.as_mut_ptr(),
+                    &mut buffer_size,
+                    values.as_ptr(),
+                    values_size,
+                )
+            )
+        };
+        if !valid {
+            return None;
+        }
+
+        Some(decode_wide(&buffer))
+    }
+
+    fn get_config_option_range(
+        &self,
+        path: &str,
+        default: c_int,
+        min: c_int,
+        max: c_int,
+    ) -> Option<c_int> {
+        let path = encode_wide(path)?;
+        let mut value = default;
+
+        let valid = unsafe {
+            sdk_call!(
+                self,
+                LogiLedGetConfigOptionRange(path.as_ptr(), &mut value, min, max)
+            )
+        };
+        if !valid {
+            return None;
+        }
+
+        Some(value)
+    }
+
+    fn set_config_option_label(&self, path: &str, label: &str) -> bool {
+        let (path, mut label) = match (encode_wide(path), encode_wide(label)) {
+            (Some(path), Some(label)) => (path, label),
+            _ => return false,
+        };
+
+        unsafe {
+            sdk_call!(
+                self,
+                LogiLedSetConfigOptionLabel(path.as_ptr(), label.as_mut_ptr())
+            )
+        }
+    }
+
+    fn set_target_device(&self, target_device: TargetDevice) -> bool {
+        // u32 -> i32, transmute
+        unsafe { sdk_call!(self, LogiLedSetTargetDevice(target_device.bits() as c_int)) }
+    }
+
+    fn save_current_lighting(&self) -> bool {
+        unsafe { sdk_call!(self, LogiLedSaveCurrentLighting()) }
+    }
+
+    fn set_lighting(&self, color: ColorPercent) -> bool {
+        unsafe {
+            sdk_call!(
+                self,
+                LogiLedSetLighting(color.r.into(), color.g.into(), color.b.into())
+            )
+        }
+    }
+
+    fn restore_lighting(&self) -> bool {
+        unsafe { sdk_call!(self, LogiLedRestoreLighting()) }
+    }
+
+    fn flash_lighting(&self, color: ColorPercent, duration: c_int, interval: c_int) -> bool {
+        unsafe {
+            sdk_call!(
+                self,
+                LogiLedFlashLighting(
+                    color.r.into(),
+                    color.g.into(),
+                    color.b.into(),
+                    duration,
+                    interval,
+                )
+            )
+        }
+    }
+
+    fn pulse_lighting(&self, color: ColorPercent, duration: c_int, interval: c_int) -> bool {
+        unsafe {
+            sdk_call!(
+                self,
+                LogiLedPulseLighting(
+                    color.r.into(),
+                    color.g.into(),
+                    color.b.into(),
+                    duration,
+                    interval,
+                )
+            )
+        }
+    }
+
+    fn stop_effects(&self) -> bool {
+        unsafe { sdk_call!(self, LogiLedStopEffects()) }
+    }
+
+    fn set_lighting_from_bitmap(&self, bitmap: &KeyboardBitmap) -> bool {
+        // The SDK takes a mutable pointer, so give it a copy.
+        let mut data = *bitmap.as_bgra_bytes();
+        unsafe { sdk_call!(self, LogiLedSetLightingFromBitmap(data.as_mut_ptr())) }
+    }
+
+    fn set_lighting_for_key_with_scan_code(&self, scan_code: c_int, color: ColorPercent) -> bool {
+        unsafe {
+            sdk_call!(
+                self,
+                LogiLedSetLightingForKeyWithScanCode(
+                    scan_code,
+                    color.r.into(),
+                    color.g.into(),
+                    color.b.into(),
+                )
+            )
+        }
+    }
+
+    fn set_lighting_for_key_with_hid_code(&self, hid_code: c_int, color: ColorPercent) -> bool {
+        unsafe {
+            sdk_call!(
+                self,
+                LogiLedSetLightingForKeyWithHidCode(
+                    hid_code,
+                    color.r.into(),
+                    color.g.into(),
+                    color.b.into(),
+                )
+            )
+        }
+    }
+
+    fn set_lighting_for_key_with_quartz_code(
+        &self,
+        quartz_code: c_int,
+        color: ColorPercent,
+    ) -> bool {
+        unsafe {
+            sdk_call!(
+                self,
+                LogiLedSetLightingForKeyWithQuartzCode(
+                    quartz_code,
+                    color.r.into(),
+                    color.g.into(),
+                    color.b.into(),
+                )
+            )
+        }
+    }
+
+    fn set_lighting_for_key_with_key_name(&self, key: KeyName, color: ColorPercent) -> bool {
+        unsafe {
+            sdk_call!(
+                self,
+                LogiLedSetLightingForKeyWithKeyName(
+                    key,
+                    color.r.into(),
+                    color.g.into(),
+                    color.b.into(),
+                )
+            )
+        }
+    }
+
+    fn save_lighting_for_key(&self, key: KeyName) -> bool {
+        unsafe { sdk_call!(self, LogiLedSaveLightingForKey(key)) }
+    }
+
+    fn restore_lighting_for_key(&self, key: KeyName) -> bool {
+        unsafe { sdk_call!(self, LogiLedRestoreLightingForKey(key)) }
+    }
+
+    fn exclude_keys_from_bitmap(&self, keys: &[KeyName]) -> bool {
+        let list_count = match c_int::try_from(keys.len()) {
+            Ok(list_count) => list_count,
+            Err(_) => return false,
+        };
+
+        // The SDK takes a mutable pointer, so give it a copy.
+        let mut key_list = keys.to_vec();
+        unsafe {
+            sdk_call!(
+                self,
+                LogiLedExcludeKeysFromBitmap(key_list.as_mut_ptr(), list_count)
+            )
+        }
+    }
+
+    fn flash_single_key(
+        &self,
+        key: KeyName,
+        color: ColorPercent,
+        duration: c_int,
+        interval: c_int,
+    ) -> bool {
+        unsafe {
+            sdk_call!(
+                self,
+                LogiLedFlashSingleKey(
+                    key,
+                    color.r.into(),
+                    color.g.into(),
+                    color.b.into(),
+                    duration,
+                    interval,
+                )
+            )
+        }
+    }
+
+    fn pulse_single_key(
+        &self,
+        key: KeyName,
+        start_color: ColorPercent,
+        end_color: ColorPercent,
+        duration: c_int,
+        is_infinite: bool,
+    ) -> bool {
+        unsafe {
+            sdk_call!(
+                self,
+                LogiLedPulseSingleKey(
+                    key,
+                    start_color.r.into(),
+                    start_color.g.into(),
+                    start_color.b.into(),
+                    end_color.r.into(),
+                    end_color.g.into(),
+                    end_color.b.into(),
+                    duration,
+                    is_infinite,
+                )
+            )
+        }
+    }
+
+    fn stop_effects_on_key(&self, key: KeyName) -> bool {
+        unsafe { sdk_call!(self, LogiLedStopEffectsOnKey(key)) }
+    }
+
+    fn set_lighting_for_target_zone(
+        &self,
+        device: DeviceType,
+        zone: c_int,
+        color: ColorPercent,
+    ) -> bool {
+        unsafe {
+            sdk_call!(
+                self,
+                LogiLedSetLightingForTargetZone(
+                    device,
+                    zone,
+                    color.r.into(),
+                    color.g.into(),
+                    color.b.into(),
+                )
+            )
+        }
+    }
+
+    fn shutdown(&self) {
+        unsafe {
+            sdk_call!(self, LogiLedShutdown());
+        }
+    }
+}
@@ -0,0 +1,735 @@
+use super::Backend;
+use crate::Clock;
+use crate::ColorPercent;
+use crate::DeviceType;
+use crate::KeyName;
+use crate::KeyboardBitmap;
+use crate::SystemClock;
+use crate::TargetDevice;
+use crate::BITMAP_KEY_POSITIONS;
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::os::raw::c_int;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+use std::time::Duration;
+
+/// The steady colors of every device, without effects.
+#[derive(Debug, Clone)]
+struct SimulatorLighting {
+    /// The color of keys that were not set one by one.
+    key_base: ColorPercent,
+    keys: HashMap<KeyName, ColorPercent>,
+
+    /// The color of zones that were not set one by one.
+    zone_base: ColorPercent,
+    zones: HashMap<(DeviceType, c_int), ColorPercent>,
+}
+
+impl SimulatorLighting {
+    fn key_color(&self, key: KeyName) -> ColorPercent {
+        self.keys.get(&key).copied().unwrap_or(self.key_base)
+    }
+
+    fn zone_color(&self, device: DeviceType, zone: c_int) -> ColorPercent {
+        self.zones
+            .get(&(device, zone))
+            .copied()
+            .unwrap_or(self.zone_base)
+    }
+}
+
+/// An effect played by the sdk.
+#[derive(Debug, Clone, Copy)]
+enum SimulatorEffect {
+    /// Show a color for the first half of every interval, and black for the second half.
+    Flash {
+        color: ColorPercent,
+        start: Duration,
+        duration: Option<Duration>,
+        interval: Duration,
+    },
+
+    /// Fade a color in and out once per interval.
+    Pulse {
+        color: ColorPercent,
+        start: Duration,
+        duration: Option<Duration>,
+        interval: Duration,
+    },
+
+    /// Fade from one color to another, and back again if it is infinite.
+    KeyPulse {
+        start_color: ColorPercent,
+        end_color: ColorPercent,
+        start: Duration,
+        duration: Duration,
+        is_infinite: bool,
+    },
+}
+
+impl SimulatorEffect {
+    /// Get the color of the effect at a time, or `None` if it is over.
+    fn color(&self, now: Duration) -> Option<ColorPercent> {
+        match *self {
+            Self::Flash {
+                color,
+                start,
+                duration,
+                interval,
+            } => {
+                let progress = effect_progress(now, start, duration, interval)?;
+                Some(if progress < 0.5 {
+                    color
+                } else {
+                    ColorPercent::BLACK
+                })
+            }
+            Self::Pulse {
+                color,
+                start,
+                duration,
+                interval,
+            } => {
+                let progress = effect_progress(now, start, duration, interval)?;
+                let brightness = 1.0 - (progress * 2.0 - 1.0).abs();
+                Some(ColorPercent::BLACK.lerp(color, brightness))
+            }
+            Self::KeyPulse {
+                start_color,
+                end_color,
+                start,
+                duration,
+                is_infinite,
+            } => {
+                let time = now.saturating_sub(start);
+                if duration.is_zero() {
+                    return is_infinite.then_some(start_color);
+                }
+
+                let cycles = time.as_secs_f32() / duration.as_secs_f32();
+                if !is_infinite && cycles >= 1.0 {
+                    return None;
+                }
+
+                // Infinite pulses go back and forth.
+                let t = cycles % 2.0;
+                let t = if t > 1.0 { 2.0 - t } else { t };
+                Some(start_color.lerp(end_color, t))
+            }
+        }
+    }
+}
+
+/// Get how far through its current interval an effect is, from 0 to 1.
+///
+/// # Returns
+/// Returns `None` if the effect is over.
+fn effect_progress(
+    now: Duration,
+    start: Duration,
+    duration: Option<Duration>,
+    interval: Duration,
+) -> Option<f32> {
+    let time = now.saturating_sub(start);
+    if duration.is_some_and(|duration| time >= duration) {
+        return None;
+    }
+    if interval.is_zero() {
+        return Some(0.0);
+    }
+
+    Some((time.as_nanos() % interval.as_nanos()) as f32 / interval.as_nanos() as f32)
+}
+
+/// Turn an sdk effect duration into a duration, where 0 means forever.
+fn effect_duration(millis: c_int) -> Option<Duration> {
+    match u64::try_from(millis) {
+        Ok(0) | Err(_) => None,
+        Ok(millis) => Some(Duration::from_millis(millis)),
+    }
+}
+
+/// Turn an sdk time into a duration, treating negative times as 0.
+fn millis(millis: c_int) -> Duration {
+    Duration::from_millis(u64::try_from(millis).unwrap_or(0))
+}
+
+#[derive(Debug)]
+struct SimulatorState {
+    /// Incremented whenever the lighting changes.
+    generation: u64,
+
+    initialized: bool,
+    target_device: TargetDevice,
+    lighting: SimulatorLighting,
+    saved_lighting: Option<SimulatorLighting>,
+    saved_keys: HashMap<KeyName, ColorPercent>,
+    excluded_keys: Vec<KeyName>,
+
+    /// The effect on every device, with the devices it was started on.
+    effect: Option<(TargetDevice, SimulatorEffect)>,
+    key_effects: HashMap<KeyName, SimulatorEffect>,
+}
+
+impl SimulatorState {
+    /// Returns true if the per-key keyboard is targeted.
+    fn targets_keys(&self) -> bool {
+        self.target_device.contains(TargetDevice::PerKeyRgb)
+    }
+
+    /// Returns true if zone devices are targeted.
+    fn targets_zones(&self) -> bool {
+        self.target_device.contains(TargetDevice::Rgb)
+    }
+
+    fn set_key(&mut self, key: Option<KeyName>, color: ColorPercent) {
+        if let Some(key) = key {
+            if self.targets_keys() {
+                self.lighting.keys.insert(key, color);
+            }
+        }
+    }
+
+    /// Start an effect on every targeted device.
+    fn start_effect(&mut self, effect: SimulatorEffect) {
+        self.effect = Some((self.target_device, effect));
+    }
+}
+
+/// Shared between clones of a [`SimulatorBackend`].
+#[derive(Debug)]
+struct Shared {
+    state: Mutex<SimulatorState>,
+    changed: Condvar,
+}
+
+/// A backend that simulates the lighting of a per-key keyboard, a mouse and a headset.
+///
+/// This works out the color of every key and zone that the calls made to it would produce,
+/// including the flash and pulse effects that the sdk plays itself.
+/// Use a [`SimulatorDisplay`](crate::SimulatorDisplay) to draw the lighting in a terminal.
+/// Clones share the same state, so keep a clone around to inspect a backend after giving it to an [`Sdk`](crate::Sdk).
+///
+/// The keyboard follows the [`TargetDevice::PerKeyRgb`] target, and every zone follows the [`TargetDevice::Rgb`] target.
+/// Flashes show their color for the first half of every interval, pulses fade in and out once per interval,
+/// and effects on single keys play over effects on every device.
+/// Config options always have their default values.
+/// Time comes from a [`Clock`], so effects can be tested deterministically with a [`ManualClock`](crate::ManualClock).
+#[derive(Debug, Clone)]
+pub struct SimulatorBackend<C = SystemClock> {
+    shared: Arc<Shared>,
+    clock: C,
+}
+
+impl SimulatorBackend<SystemClock> {
+    /// Create a new simulator that follows real time.
+    pub fn new() -> Self {
+        Self::with_clock(SystemClock::new())
+    }
+}
+
+impl<C> SimulatorBackend<C>
+where
+    C: Clock,
+{
+    /// Create a new simulator that uses the given clock for effects.
+    pub fn with_clock(clock: C) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                state: Mutex::new(SimulatorState {
+                    generation: 0,
+
+                    initialized: false,
+                    target_device: TargetDevice::All,
+                    lighting: SimulatorLighting {
+                        key_base: ColorPercent::BLACK,
+                        keys: HashMap::new(),
+                        zone_base: ColorPercent::BLACK,
+                        zones: HashMap::new(),
+                    },
+                    saved_lighting: None,
+                    saved_keys: HashMap::new(),
+                    excluded_keys: Vec::new(),
+
+                    effect: None,
+                    key_effects: HashMap::new(),
+                }),
+                changed: Condvar::new(),
+            }),
+            clock,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SimulatorState> {
+        self.shared
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Change the state if the simulator is initialized, waking anything waiting for a change.
+    ///
+    /// # Returns
+    /// Returns false if the simulator is not initialized.
+    fn update<F>(&self, f: F) -> bool
+    where
+        F: FnOnce(&mut SimulatorState, Duration),
+    {
+        let now = self.clock.now();
+        let mut state = self.lock();
+        if !state.initialized {
+            return false;
+        }
+
+        f(&mut state, now);
+        state.generation += 1;
+        self.shared.changed.notify_all();
+        true
+    }
+
+    /// Returns true if the simulator is initialized.
+    pub fn is_initialized(&self) -> bool {
+        self.lock().initialized
+    }
+
+    /// Get the color of a key right now, including effects.
+    pub fn key_color(&self, key: KeyName) -> ColorPercent {
+        let now = self.clock.now();
+        let state = self.lock();
+        if let Some(color) = state
+            .key_effects
+            .get(&key)
+            .and_then(|effect| effect.color(now))
+        {
+            return color;
+        }
+
+        match state.effect {
+            Some((target_device, effect)) if target_device.contains(TargetDevice::PerKeyRgb) => {
+                effect.color(now)
+            }
+            _ => None,
+        }
+        .unwrap_or_else(|| state.lighting.key_color(key))
+    }
+
+    /// Get the color of a zone on a device right now, including effects.
+    pub fn zone_color(&self, device: DeviceType, zone: c_int) -> ColorPercent {
+        let now = self.clock.now();
+        let state = self.lock();
+        match state.effect {
+            Some((target_device, effect)) if target_device.contains(TargetDevice::Rgb) => {
+                effect.color(now)
+            }
+            _ => None,
+        }
+        .unwrap_or_else(|| state.lighting.zone_color(device, zone))
+    }
+
+    /// Returns true if any effects are playing, so the lighting changes without any calls being made.
+    pub fn has_effects(&self) -> bool {
+        let now = self.clock.now();
+        let state = self.lock();
+        state
+            .effect
+            .iter()
+            .map(|(_, effect)| effect)
+            .chain(state.key_effects.values())
+            .any(|effect| effect.color(now).is_some())
+    }
+
+    /// Get a number that changes whenever a call changes the lighting.
+    pub fn generation(&self) -> u64 {
+        self.lock().generation
+    }
+
+    /// Wait until a call changes the lighting after `generation`, or until the timeout passes.
+    ///
+    /// # Returns
+    /// Returns the new generation.
+    pub fn wait_for_change(&self, generation: u64, timeout: Duration) -> u64 {
+        let state = self.lock();
+        let (state, _) = self
+            .shared
+            .changed
+            .wait_timeout_while(state, timeout, |state| state.generation == generation)
+            .unwrap_or_else(PoisonError::into_inner);
+        state.generation
+    }
+}
+
+impl Default for SimulatorBackend<SystemClock> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> Backend for SimulatorBackend<C>
+where
+    C: Clock,
+{
+    fn init(&self) -> bool {
+        let mut state = self.lock();
+        state.initialized = true;
+        state.generation += 1;
+        self.shared.changed.notify_all();
+        true
+    }
+
+    fn init_with_name(&self, _name: &CStr) -> bool {
+        self.init()
+    }
+
+    fn get_sdk_version(&self) -> Option<(c_int, c_int, c_int)> {
+        self.is_initialized().then_some((9, 0, 0))
+    }
+
+    fn get_config_option_number(&self, _path: &str, default: f64) -> Option<f64> {
+        self.is_initialized().then_some(default)
+    }
+
+    fn get_config_option_bool(&self, _path: &str, default: bool) -> Option<bool> {
+        self.is_initialized().then_some(default)
+    }
+
+    fn get_config_option_color(
+        &self,
+        _path: &str,
+        default: (c_int, c_int, c_int),
+    ) -> Option<(c_int, c_int, c_int)> {
+        self.is_initialized().then_some(default)
+    }
+
+    fn get_config_option_rect(
+        &self,
+        _path: &str,
+        default: (c_int, c_int, c_int, c_int),
+    ) -> Option<(c_int, c_int, c_int, c_int)> {
+        self.is_initialized().then_some(default)
+    }
+
+    fn get_config_option_string(&self, _path: &str, default: &str) -> Option<String> {
+        self.is_initialized().then(|| default.to_string())
+    }
+
+    fn get_config_option_key_input(&self, _path: &str, default: &str) -> Option<String> {
+        self.is_initialized().then(|| default.to_string())
+    }
+
+    fn get_config_option_select(
+        &self,
+        _path: &str,
+        default: &str,
+        _options: &[&str],
+    ) -> Option<String> {
+        self.is_initialized().then(|| default.to_string())
+    }
+
+    fn get_config_option_range(
+        &self,
+        _path: &str,
+        default: c_int,
+        _min: c_int,
+        _max: c_int,
+    ) -> Option<c_int> {
+        self.is_initialized().then_some(default)
+    }
+
+    fn set_config_option_label(&self, _path: &str, _label: &str) -> bool {
+        self.is_initialized()
+    }
+
+    fn set_target_device(&self, target_device: TargetDevice) -> bool {
+        self.update(|state, _| state.target_device = target_device)
+    }
+
+    fn save_current_lighting(&self) -> bool {
+        self.update(|state, _| state.saved_lighting = Some(state.lighting.clone()))
+    }
+
+    fn set_lighting(&self, color: ColorPercent) -> bool {
+        self.update(|state, _| {
+            if state.targets_keys() {
+                state.lighting.key_base = color;
+                state.lighting.keys.clear();
+            }
+            if state.targets_zones() {
+                state.lighting.zone_base = color;
+                state.lighting.zones.clear();
+            }
+        })
+    }
+
+    fn restore_lighting(&self) -> bool {
+        self.update(|state, _| {
+            if let Some(saved) = state.saved_lighting.clone() {
+                state.lighting = saved;
+            }
+        })
+    }
+
+    fn flash_lighting(&self, color: ColorPercent, duration: c_int, interval: c_int) -> bool {
+        self.update(|state, now| {
+            state.start_effect(SimulatorEffect::Flash {
+                color,
+                start: now,
+                duration: effect_duration(duration),
+                interval: millis(interval),
+            })
+        })
+    }
+
+    fn pulse_lighting(&self, color: ColorPercent, duration: c_int, interval: c_int) -> bool {
+        self.update(|state, now| {
+            state.start_effect(SimulatorEffect::Pulse {
+                color,
+                start: now,
+                duration: effect_duration(duration),
+                interval: millis(interval),
+            })
+        })
+    }
+
+    fn stop_effects(&self) -> bool {
+        self.update(|state, _| {
+            state.effect = None;
+            state.key_effects.clear();
+        })
+    }
+
+    fn set_lighting_from_bitmap(&self, bitmap: &KeyboardBitmap) -> bool {
+        self.update(|state, _| {
+            if !state.targets_keys() {
+                return;
+            }
+
+            for (key, x, y) in BITMAP_KEY_POSITIONS.iter().copied() {
+                if state.excluded_keys.contains(&key) {
+                    continue;
+                }
+
+                let color = bitmap.get(x, y).expect("key position in bounds");
+                state.lighting.keys.insert(key, color);
+            }
+        })
+    }
+
+    fn set_lighting_for_key_with_scan_code(&self, scan_code: c_int, color: ColorPercent) -> bool {
+        let key = u32::try_from(scan_code)
+            .ok()
+            .and_then(KeyName::from_scan_code);
+        self.update(|state, _| state.set_key(key, color))
+    }
+
+    fn set_lighting_for_key_with_hid_code(&self, hid_code: c_int, color: ColorPercent) -> bool {
+        let key = u32::try_from(hid_code)
+            .ok()
+            .and_then(KeyName::from_hid_usage);
+        self.update(|state, _| state.set_key(key, color))
+    }
+
+    fn set_lighting_for_key_with_quartz_code(
+        &self,
+        quartz_code: c_int,
+        color: ColorPercent,
+    ) -> bool {
+        let key = u32::try_from(quartz_code)
+            .ok()
+            .and_then(KeyName::from_quartz_code);
+        self.update(|state, _| state.set_key(key, color))
+    }
+
+    fn set_lighting_for_key_with_key_name(&self, key: KeyName, color: ColorPercent) -> bool {
+        self.update(|state, _| state.set_key(Some(key), color))
+    }
+
+    fn save_lighting_for_key(&self, key: KeyName) -> bool {
+        self.update(|state, _| {
+            let color = state.lighting.key_color(key);
+            state.saved_keys.insert(key, color);
+        })
+    }
+
+    fn restore_lighting_for_key(&self, key: KeyName) -> bool {
+        self.update(|state, _| {
+            if let Some(color) = state.saved_keys.get(&key).copied() {
+                state.lighting.keys.insert(key, color);
+            }
+        })
+    }
+
+    fn exclude_keys_from_bitmap(&self, keys: &[KeyName]) -> bool {
+        self.update(|state, _| state.excluded_keys = keys.to_vec())
+    }
+
+    fn flash_single_key(
+        &self,
+        key: KeyName,
+        color: ColorPercent,
+        duration: c_int,
+        interval: c_int,
+    ) -> bool {
+        self.update(|state, now| {
+            let effect = SimulatorEffect::Flash {
+                color,
+                start: now,
+                duration: effect_duration(duration),
+                interval: millis(interval),
+            };
+            state.key_effects.insert(key, effect);
+        })
+    }
+
+    fn pulse_single_key(
+        &self,
+        key: KeyName,
+        start_color: ColorPercent,
+        end_color: ColorPercent,
+        duration: c_int,
+        is_infinite: bool,
+    ) -> bool {
+        self.update(|state, now| {
+            let effect = SimulatorEffect::KeyPulse {
+                start_color,
+                end_color,
+                start: now,
+                duration: millis(duration),
+                is_infinite,
+            };
+            state.key_effects.insert(key, effect);
+        })
+    }
+
+    fn stop_effects_on_key(&self, key: KeyName) -> bool {
+        self.update(|state, _| {
+            state.key_effects.remove(&key);
+        })
+    }
+
+    fn set_lighting_for_target_zone(
+        &self,
+        device: DeviceType,
+        zone: c_int,
+        color: ColorPercent,
+    ) -> bool {
+        self.update(|state, _| {
+            state.lighting.zones.insert((device, zone), color);
+        })
+    }
+
+    fn shutdown(&self) {
+        let mut state = self.lock();
+        state.initialized = false;
+        state.generation += 1;
+        self.shared.changed.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ManualClock;
+    use crate::Sdk;
+
+    #[test]
+    fn lighting() {
+        let backend = SimulatorBackend::with_clock(ManualClock::new());
+        let sdk = Sdk::new_with_backend(backend.clone()).expect("failed to init simulator");
+
+        sdk.set_lighting(ColorPercent::BLUE)
+            .expect("failed to set lighting");
+        sdk.set_lighting_for_key_with_name(KeyName::W, ColorPercent::RED)
+            .expect("failed to set key");
+        sdk.set_lighting_for_target_zone(DeviceType::Mouse, 1, ColorPercent::GREEN)
+            .expect("failed to set zone");
+        assert_eq!(backend.key_color(KeyName::W), ColorPercent::RED);
+        assert_eq!(backend.key_color(KeyName::A), ColorPercent::BLUE);
+        assert_eq!(
+            backend.zone_color(DeviceType::Mouse, 1),
+            ColorPercent::GREEN
+        );
+        assert_eq!(
+            backend.zone_color(DeviceType::Headset, 0),
+            ColorPercent::BLUE
+        );
+
+        // Only per-key devices are targeted.
+        sdk.set_target(TargetDevice::PerKeyRgb)
+            .expect("failed to set target");
+        sdk.set_lighting(ColorPercent::WHITE)
+            .expect("failed to set lighting");
+        assert_eq!(backend.key_color(KeyName::W), ColorPercent::WHITE);
+        assert_eq!(
+            backend.zone_color(DeviceType::Headset, 0),
+            ColorPercent::BLUE
+        );
+
+        sdk.exclude_keys_from_bitmap([KeyName::ESC])
+            .expect("failed to exclude keys");
+        sdk.set_lighting_from_bitmap(&KeyboardBitmap::new_filled(ColorPercent::RED))
+            .expect("failed to set bitmap");
+        assert_eq!(backend.key_color(KeyName::ESC), ColorPercent::WHITE);
+        assert_eq!(backend.key_color(KeyName::Q), ColorPercent::RED);
+
+        let generation = backend.generation();
+        drop(sdk);
+        assert!(!backend.is_initialized());
+        assert!(backend.generation() > generation);
+    }
+
+    #[test]
+    fn effects() {
+        let clock = ManualClock::new();
+        let backend = SimulatorBackend::with_clock(clock.clone());
+        let sdk = Sdk::new_with_backend(backend.clone()).expect("failed to init simulator");
+        sdk.set_lighting(ColorPercent::BLUE)
+            .expect("failed to set lighting");
+        assert!(!backend.has_effects());
+
+        sdk.flash_lighting(
+            ColorPercent::RED,
+            Some(Duration::from_millis(1000)),
+            Duration::from_millis(200),
+        )
+        .expect("failed to flash");
+        assert!(backend.has_effects());
+        assert_eq!(backend.key_color(KeyName::W), ColorPercent::RED);
+        assert_eq!(backend.zone_color(DeviceType::Mouse, 0), ColorPercent::RED);
+        clock.advance(Duration::from_millis(150));
+        assert_eq!(backend.key_color(KeyName::W), ColorPercent::BLACK);
+
+        // Effects on single keys play over effects on every device.
+        sdk.pulse_single_key(
+            KeyName::W,
+            ColorPercent::BLACK,
+            ColorPercent::WHITE,
+            Duration::from_millis(100),
+            true,
+        )
+        .expect("failed to pulse key");
+        clock.advance(Duration::from_millis(50));
+        assert_eq!(
+            backend.key_color(KeyName::W),
+            ColorPercent::BLACK.lerp(ColorPercent::WHITE, 0.5)
+        );
+        clock.advance(Duration::from_millis(100));
+        assert_eq!(
+            backend.key_color(KeyName::W),
+            ColorPercent::BLACK.lerp(ColorPercent::WHITE, 0.5)
+        );
+
+        // The flash ends after its duration, and the steady color comes back.
+        clock.advance(Duration::from_millis(800));
+        assert_eq!(backend.key_color(KeyName::A), ColorPercent::BLUE);
+        assert!(backend.has_effects());
+
+        sdk.stop_effects_on_key(KeyName::W)
+            .expect("failed to stop effects on key");
+        assert_eq!(backend.key_color(KeyName::W), ColorPercent::BLUE);
+        assert!(!backend.has_effects());
+    }
+}
@@ -0,0 +1,53 @@
+/// Encode a string as NUL-terminated UTF-16.
+///
+/// # Returns
+/// Returns None if the string contains interior NULs.
+pub(crate) fn encode_wide(value: &str) -> Option<Vec<u16>> {
+    let mut wide: Vec<u16> = value.encode_utf16().collect();
+    if wide.contains(&0) {
+        return None;
+    }
+    wide.push(0);
+
+    Some(wide)
+}
+
+/// Encode a string as NUL-terminated UTF-16, padded with NULs to at least the given length.
+///
+/// # Returns
+/// Returns None if the string contains interior NULs.
+pub(crate) fn encode_wide_buffer(value: &str, len: usize) -> Option<Vec<u16>> {
+    let mut wide = encode_wide(value)?;
+    if wide.len() < len {
+        wide.resize(len, 0);
+    }
+
+    Some(wide)
+}
+
+/// Decode a possibly NUL-terminated UTF-16 buffer.
+///
+/// Invalid UTF-16 is replaced with the replacement character.
+pub(crate) fn decode_wide(buffer: &[u16]) -> String {
+    let len = buffer.iter().position(|c| *c == 0).unwrap_or(buffer.len());
+    String::from_utf16_lossy(&buffer[..len])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn wide_round_trip() {
+        let wide = encode_wide("lighting/ćolor").expect("failed to encode");
+        assert_eq!(wide.last(), Some(&0));
+        assert_eq!(decode_wide(&wide), "lighting/ćolor");
+
+        assert!(encode_wide("bad\0path").is_none());
+
+        let buffer = encode_wide_buffer("abc", 16).expect("failed to encode");
+        assert_eq!(buffer.len(), 16);
+        assert_eq!(decode_wide(&buffer), "abc");
+        assert_eq!(decode_wide(&[u16::from(b'a'), u16::from(b'b')]), "ab");
+    }
+}
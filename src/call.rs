@@ -0,0 +1,338 @@
+use crate::Backend;
+use crate::ColorPercent;
+use crate::DeviceType;
+use crate::KeyName;
+use crate::KeyboardBitmap;
+use crate::TargetDevice;
+use std::ffi::CString;
+use std::os::raw::c_int;
+
+/// A call to a [`Backend`], with its arguments.
+///
+/// Each variant is named after the sdk function it calls, without the `LogiLed` prefix.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Call {
+    Init,
+    InitWithName {
+        name: String,
+    },
+    GetSdkVersion,
+    GetConfigOptionNumber {
+        path: String,
+        default: f64,
+    },
+    GetConfigOptionBool {
+        path: String,
+        default: bool,
+    },
+    GetConfigOptionColor {
+        path: String,
+        default: (c_int, c_int, c_int),
+    },
+    GetConfigOptionRect {
+        path: String,
+        default: (c_int, c_int, c_int, c_int),
+    },
+    GetConfigOptionString {
+        path: String,
+        default: String,
+    },
+    GetConfigOptionKeyInput {
+        path: String,
+        default: String,
+    },
+    GetConfigOptionSelect {
+        path: String,
+        default: String,
+        options: Vec<String>,
+    },
+    GetConfigOptionRange {
+        path: String,
+        default: c_int,
+        min: c_int,
+        max: c_int,
+    },
+    SetConfigOptionLabel {
+        path: String,
+        label: String,
+    },
+    SetTargetDevice {
+        target_device: TargetDevice,
+    },
+    SaveCurrentLighting,
+    SetLighting {
+        color: ColorPercent,
+    },
+    RestoreLighting,
+    FlashLighting {
+        color: ColorPercent,
+        duration: c_int,
+        interval: c_int,
+    },
+    PulseLighting {
+        color: ColorPercent,
+        duration: c_int,
+        interval: c_int,
+    },
+    StopEffects,
+    SetLightingFromBitmap {
+        bitmap: Box<KeyboardBitmap>,
+    },
+    SetLightingForKeyWithScanCode {
+        scan_code: c_int,
+        color: ColorPercent,
+    },
+    SetLightingForKeyWithHidCode {
+        hid_code: c_int,
+        color: ColorPercent,
+    },
+    SetLightingForKeyWithQuartzCode {
+        quartz_code: c_int,
+        color: ColorPercent,
+    },
+    SetLightingForKeyWithKeyName {
+        key: KeyName,
+        color: ColorPercent,
+    },
+    SaveLightingForKey {
+        key: KeyName,
+    },
+    RestoreLightingForKey {
+        key: KeyName,
+    },
+    ExcludeKeysFromBitmap {
+        keys: Vec<KeyName>,
+    },
+    FlashSingleKey {
+        key: KeyName,
+        color: ColorPercent,
+        duration: c_int,
+        interval: c_int,
+    },
+    PulseSingleKey {
+        key: KeyName,
+        start_color: ColorPercent,
+        end_color: ColorPercent,
+        duration: c_int,
+        is_infinite: bool,
+    },
+    StopEffectsOnKey {
+        key: KeyName,
+    },
+    SetLightingForTargetZone {
+        device: DeviceType,
+        zone: c_int,
+        color: ColorPercent,
+    },
+    Shutdown,
+}
+
+impl Call {
+    /// Get the name of the sdk function for this call.
+    pub fn function(&self) -> &'static str {
+        match self {
+            Self::Init => "LogiLedInit",
+            Self::InitWithName { .. } => "LogiLedInitWithName",
+            Self::GetSdkVersion => "LogiLedGetSdkVersion",
+            Self::GetConfigOptionNumber { .. } => "LogiLedGetConfigOptionNumber",
+            Self::GetConfigOptionBool { .. } => "LogiLedGetConfigOptionBool",
+            Self::GetConfigOptionColor { .. } => "LogiLedGetConfigOptionColor",
+            Self::GetConfigOptionRect { .. } => "LogiLedGetConfigOptionRect",
+            Self::GetConfigOptionString { .. } => "LogiLedGetConfigOptionString",
+            Self::GetConfigOptionKeyInput { .. } => "LogiLedGetConfigOptionKeyInput",
+            Self::GetConfigOptionSelect { .. } => "LogiLedGetConfigOptionSelect",
+            Self::GetConfigOptionRange { .. } => "LogiLedGetConfigOptionRange",
+            Self::SetConfigOptionLabel { .. } => "LogiLedSetConfigOptionLabel",
+            Self::SetTargetDevice { .. } => "LogiLedSetTargetDevice",
+            Self::SaveCurrentLighting => "LogiLedSaveCurrentLighting",
+            Self::SetLighting { .. } => "LogiLedSetLighting",
+            Self::RestoreLighting => "LogiLedRestoreLighting",
+            Self::FlashLighting { .. } => "LogiLedFlashLighting",
+            Self::PulseLighting { .. } => "LogiLedPulseLighting",
+            Self::StopEffects => "LogiLedStopEffects",
+            Self::SetLightingFromBitmap { .. } => "LogiLedSetLightingFromBitmap",
+            Self::SetLightingForKeyWithScanCode { .. } => "LogiLedSetLightingForKeyWithScanCode",
+            Self::SetLightingForKeyWithHidCode { .. } => "LogiLedSetLightingForKeyWithHidCode",
+            Self::SetLightingForKeyWithQuartzCode { .. } => {
+                "LogiLedSetLightingForKeyWithQuartzCode"
+            }
+            Self::SetLightingForKeyWithKeyName { .. } => "LogiLedSetLightingForKeyWithKeyName",
+            Self::SaveLightingForKey { .. } => "LogiLedSaveLightingForKey",
+            Self::RestoreLightingForKey { .. } => "LogiLedRestoreLightingForKey",
+            Self::ExcludeKeysFromBitmap { .. } => "LogiLedExcludeKeysFromBitmap",
+            Self::FlashSingleKey { .. } => "LogiLedFlashSingleKey",
+            Self::PulseSingleKey { .. } => "LogiLedPulseSingleKey",
+            Self::StopEffectsOnKey { .. } => "LogiLedStopEffectsOnKey",
+            Self::SetLightingForTargetZone { .. } => "LogiLedSetLightingForTargetZone",
+            Self::Shutdown => "LogiLedShutdown",
+        }
+    }
+
+    /// Make this call on a backend.
+    ///
+    /// `LogiLedInitWithName` fails if the name contains interior NULs.
+    pub fn issue(&self, backend: &dyn Backend) -> CallResult {
+        match self {
+            Self::Init => CallResult::Success(backend.init()),
+            Self::InitWithName { name } => match CString::new(name.as_str()) {
+                Ok(name) => CallResult::Success(backend.init_with_name(&name)),
+                Err(_) => CallResult::Success(false),
+            },
+            Self::GetSdkVersion => backend
+                .get_sdk_version()
+                .map_or(CallResult::Failed, CallResult::Version),
+            Self::GetConfigOptionNumber { path, default } => backend
+                .get_config_option_number(path, *default)
+                .map_or(CallResult::Failed, CallResult::Number),
+            Self::GetConfigOptionBool { path, default } => backend
+                .get_config_option_bool(path, *default)
+                .map_or(CallResult::Failed, CallResult::Bool),
+            Self::GetConfigOptionColor { path, default } => backend
+                .get_config_option_color(path, *default)
+                .map_or(CallResult::Failed, CallResult::Color),
+            Self::GetConfigOptionRect { path, default } => backend
+                .get_config_option_rect(path, *default)
+                .map_or(CallResult::Failed, CallResult::Rect),
+            Self::GetConfigOptionString { path, default } => backend
+                .get_config_option_string(path, default)
+                .map_or(CallResult::Failed, CallResult::String),
+            Self::GetConfigOptionKeyInput { path, default } => backend
+                .get_config_option_key_input(path, default)
+                .map_or(CallResult::Failed, CallResult::String),
+            Self::GetConfigOptionSelect {
+                path,
+                default,
+                options,
+            } => {
+                let options: Vec<&str> = options.iter().map(String::as_str).collect();
+                backend
+                    .get_config_option_select(path, default, &options)
+                    .map_or(CallResult::Failed, CallResult::String)
+            }
+            Self::GetConfigOptionRange {
+                path,
+                default,
+                min,
+                max,
+            } => backend
+                .get_config_option_range(path, *default, *min, *max)
+                .map_or(CallResult::Failed, CallResult::Range),
+            Self::SetConfigOptionLabel { path, label } => {
+                CallResult::Success(backend.set_config_option_label(path, label))
+            }
+            Self::SetTargetDevice { target_device } => {
+                CallResult::Success(backend.set_target_device(*target_device))
+            }
+            Self::SaveCurrentLighting => CallResult::Success(backend.save_current_lighting()),
+            Self::SetLighting { color } => CallResult::Success(backend.set_lighting(*color)),
+            Self::RestoreLighting => CallResult::Success(backend.restore_lighting()),
+            Self::FlashLighting {
+                color,
+                duration,
+                interval,
+            } => CallResult::Success(backend.flash_lighting(*color, *duration, *interval)),
+            Self::PulseLighting {
+                color,
+                duration,
+                interval,
+            } => CallResult::Success(backend.pulse_lighting(*color, *duration, *interval)),
+            Self::StopEffects => CallResult::Success(backend.stop_effects()),
+            Self::SetLightingFromBitmap { bitmap } => {
+                CallResult::Success(backend.set_lighting_from_bitmap(bitmap))
+            }
+            Self::SetLightingForKeyWithScanCode { scan_code, color } => {
+                CallResult::Success(backend.set_lighting_for_key_with_scan_code(*scan_code, *color))
+            }
+            Self::SetLightingForKeyWithHidCode { hid_code, color } => {
+                CallResult::Success(backend.set_lighting_for_key_with_hid_code(*hid_code, *color))
+            }
+            Self::SetLightingForKeyWithQuartzCode { quartz_code, color } => CallResult::Success(
+                backend.set_lighting_for_key_with_quartz_code(*quartz_code, *color),
+            ),
+            Self::SetLightingForKeyWithKeyName { key, color } => {
+                CallResult::Success(backend.set_lighting_for_key_with_key_name(*key, *color))
+            }
+            Self::SaveLightingForKey { key } => {
+                CallResult::Success(backend.save_lighting_for_key(*key))
+            }
+            Self::RestoreLightingForKey { key } => {
+                CallResult::Success(backend.restore_lighting_for_key(*key))
+            }
+            Self::ExcludeKeysFromBitmap { keys } => {
+                CallResult::Success(backend.exclude_keys_from_bitmap(keys))
+            }
+            Self::FlashSingleKey {
+                key,
+                color,
+                duration,
+                interval,
+            } => CallResult::Success(backend.flash_single_key(*key, *color, *duration, *interval)),
+            Self::PulseSingleKey {
+                key,
+                start_color,
+                end_color,
+                duration,
+                is_infinite,
+            } => CallResult::Success(backend.pulse_single_key(
+                *key,
+                *start_color,
+                *end_color,
+                *duration,
+                *is_infinite,
+            )),
+            Self::StopEffectsOnKey { key } => {
+                CallResult::Success(backend.stop_effects_on_key(*key))
+            }
+            Self::SetLightingForTargetZone {
+                device,
+                zone,
+                color,
+            } => CallResult::Success(backend.set_lighting_for_target_zone(*device, *zone, *color)),
+            Self::Shutdown => {
+                backend.shutdown();
+                CallResult::Unit
+            }
+        }
+    }
+}
+
+/// What a [`Call`] returned.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CallResult {
+    /// The call does not return anything, like `LogiLedShutdown`.
+    Unit,
+
+    /// Whether a call that does not get a value succeeded.
+    Success(bool),
+
+    /// A call that gets a value failed.
+    Failed,
+
+    /// The major, minor and build numbers from `LogiLedGetSdkVersion`.
+    Version((c_int, c_int, c_int)),
+
+    /// The value of a number config option.
+    Number(f64),
+
+    /// The value of a bool config option.
+    Bool(bool),
+
+    /// The value of a color config option, as RGB values from 0-255.
+    Color((c_int, c_int, c_int)),
+
+    /// The value of a rectangle config option, as the x, y, width and height.
+    Rect((c_int, c_int, c_int, c_int)),
+
+    /// The value of a string, key input or select config option.
+    String(String),
+
+    /// The value of a ranged integer config option.
+    Range(c_int),
+}
+
+impl CallResult {
+    /// Returns true if the call failed.
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Success(false) | Self::Failed)
+    }
+}
@@ -1,3 +1,7 @@
+use crate::ParseColorError;
+use crate::Rgb8;
+use std::str::FromStr;
+
 /// A percent-based RGB Color.
 ///
 /// Values are from 0-100 NOT 0-255.
@@ -33,12 +37,360 @@ impl ColorPercent {
         Some(Self { r, g, b })
     }
 
+    /// Black
+    pub const BLACK: Self = Self { r: 0, g: 0, b: 0 };
+
+    /// White
+    pub const WHITE: Self = Self {
+        r: 100,
+        g: 100,
+        b: 100,
+    };
+
+    /// Red
+    pub const RED: Self = Self { r: 100, g: 0, b: 0 };
+
+    /// Green
+    ///
+    /// This is the CSS color `lime`, as the CSS color `green` is darker.
+    pub const GREEN: Self = Self { r: 0, g: 100, b: 0 };
+
+    /// Blue
+    pub const BLUE: Self = Self { r: 0, g: 0, b: 100 };
+
+    /// Yellow
+    pub const YELLOW: Self = Self {
+        r: 100,
+        g: 100,
+        b: 0,
+    };
+
+    /// Cyan
+    pub const CYAN: Self = Self {
+        r: 0,
+        g: 100,
+        b: 100,
+    };
+
+    /// Magenta
+    pub const MAGENTA: Self = Self {
+        r: 100,
+        g: 0,
+        b: 100,
+    };
+
+    /// Orange
+    pub const ORANGE: Self = Self {
+        r: 100,
+        g: 65,
+        b: 0,
+    };
+
     /// Creates a new color from RGB values, NOT percentage values.
+    ///
+    /// Values are rounded to the nearest percentage.
     pub fn new_rgb(r: u8, g: u8, b: u8) -> Self {
         Self {
-            r: ((u16::from(r) * 100) / 255) as u8,
-            g: ((u16::from(g) * 100) / 255) as u8,
-            b: ((u16::from(b) * 100) / 255) as u8,
+            r: byte_to_percent(r),
+            g: byte_to_percent(g),
+            b: byte_to_percent(b),
+        }
+    }
+
+    /// Convert this color to RGB values, rounding to the nearest value.
+    pub fn to_rgb(self) -> Rgb8 {
+        Rgb8::new(
+            percent_to_byte(self.r),
+            percent_to_byte(self.g),
+            percent_to_byte(self.b),
+        )
+    }
+
+    /// Mix two colors in sRGB, where a `t` of 0 is `self` and 1 is `to`.
+    ///
+    /// `t` is clamped from 0 to 1.
+    pub fn lerp(self, to: Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let [r, g, b] = [(self.r, to.r), (self.g, to.g), (self.b, to.b)].map(|(from, to)| {
+            let from = f32::from(from.min(100)) / 100.0;
+            let to = f32::from(to.min(100)) / 100.0;
+            from + ((to - from) * t)
+        });
+
+        Self::from_unit([r, g, b])
+    }
+
+    /// Mix two colors in linear light, where a `t` of 0 is `self` and 1 is `to`.
+    ///
+    /// This keeps the brightness of the mix closer to what the eye expects than [`ColorPercent::lerp`],
+    /// which looks dark halfway between two saturated colors.
+    /// `t` is clamped from 0 to 1.
+    pub fn lerp_linear(self, to: Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let from = self.to_unit().map(srgb_to_linear);
+        let to = to.to_unit().map(srgb_to_linear);
+        let [r, g, b] = [0, 1, 2].map(|i| linear_to_srgb(from[i] + ((to[i] - from[i]) * t)));
+
+        Self::from_unit([r, g, b])
+    }
+
+    /// Scale the brightness of this color by a factor from 0 to 1.
+    ///
+    /// The factor is clamped from 0 to 1.
+    pub fn scale(self, factor: f32) -> Self {
+        Self::BLACK.lerp(self, factor)
+    }
+
+    /// Get the channels as values from 0 to 1.
+    pub(crate) fn to_unit(self) -> [f32; 3] {
+        [self.r, self.g, self.b].map(|value| f32::from(value.min(100)) / 100.0)
+    }
+
+    /// Make a color from channels with values from 0 to 1, rounding to the nearest percentage.
+    pub(crate) fn from_unit([r, g, b]: [f32; 3]) -> Self {
+        let channel = |value: f32| (value.clamp(0.0, 1.0) * 100.0).round() as u8;
+        Self {
+            r: channel(r),
+            g: channel(g),
+            b: channel(b),
+        }
+    }
+}
+
+impl From<Rgb8> for ColorPercent {
+    fn from(color: Rgb8) -> Self {
+        Self::new_rgb(color.r, color.g, color.b)
+    }
+}
+
+impl From<ColorPercent> for Rgb8 {
+    fn from(color: ColorPercent) -> Self {
+        color.to_rgb()
+    }
+}
+
+impl FromStr for ColorPercent {
+    type Err = ParseColorError;
+
+    /// Parse a `#rrggbb` or `#rgb` hex color, or a CSS color name like `rebeccapurple`.
+    ///
+    /// Case and surrounding whitespace are ignored.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        input.parse::<Rgb8>().map(Self::from)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ColorPercent {
+    /// Serialize as a `"#rrggbb"` hex color in human-readable formats, and as `{ r, g, b }` percentages otherwise.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        if serializer.is_human_readable() {
+            return serializer.collect_str(&self.to_rgb());
+        }
+
+        let mut state = serializer.serialize_struct("ColorPercent", 3)?;
+        state.serialize_field("r", &self.r)?;
+        state.serialize_field("g", &self.g)?;
+        state.serialize_field("b", &self.b)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ColorPercent {
+    /// Deserialize from a hex color or CSS color name, or from `{ r, g, b }` percentages.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+        use serde::de::MapAccess;
+        use serde::de::Unexpected;
+
+        const FIELDS: &[&str] = &["r", "g", "b"];
+
+        struct ColorVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for ColorVisitor {
+            type Value = ColorPercent;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a hex color, a CSS color name, or a map of r, g and b percentages")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                value
+                    .parse()
+                    .map_err(|_| E::invalid_value(Unexpected::Str(value), &self))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut channels = [None; 3];
+                while let Some(key) = map.next_key::<std::borrow::Cow<'_, str>>()? {
+                    let index = FIELDS
+                        .iter()
+                        .position(|field| *field == key)
+                        .ok_or_else(|| A::Error::unknown_field(&key, FIELDS))?;
+                    if channels[index].is_some() {
+                        return Err(A::Error::duplicate_field(FIELDS[index]));
+                    }
+
+                    let value: u8 = map.next_value()?;
+                    if value > 100 {
+                        return Err(A::Error::invalid_value(
+                            Unexpected::Unsigned(value.into()),
+                            &"a percentage from 0 to 100",
+                        ));
+                    }
+                    channels[index] = Some(value);
+                }
+
+                let channel = |index: usize| {
+                    channels[index].ok_or_else(|| A::Error::missing_field(FIELDS[index]))
+                };
+
+                Ok(ColorPercent {
+                    r: channel(0)?,
+                    g: channel(1)?,
+                    b: channel(2)?,
+                })
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(ColorVisitor)
+        } else {
+            deserializer.deserialize_struct("ColorPercent", FIELDS, ColorVisitor)
+        }
+    }
+}
+
+/// Convert an sRGB channel from 0 to 1 to linear light.
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Convert a linear light channel from 0 to 1 to sRGB.
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        (1.055 * value.powf(1.0 / 2.4)) - 0.055
+    }
+}
+
+/// Convert a percentage to a color byte, rounding to the nearest value.
+pub(crate) fn percent_to_byte(value: u8) -> u8 {
+    ((u16::from(value.min(100)) * 255 + 50) / 100) as u8
+}
+
+/// Convert a color byte to a percentage, rounding to the nearest value.
+pub(crate) fn byte_to_percent(value: u8) -> u8 {
+    ((u16::from(value) * 100 + 127) / 255) as u8
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn percent_byte_round_trip() {
+        for percent in 0..=100 {
+            assert_eq!(byte_to_percent(percent_to_byte(percent)), percent);
+        }
+    }
+
+    #[test]
+    fn rgb() {
+        assert_eq!(
+            ColorPercent::new_rgb(255, 128, 0),
+            ColorPercent::new_percent(100, 50, 0).unwrap()
+        );
+        assert_eq!(
+            ColorPercent::new_rgb(254, 1, 2),
+            ColorPercent::new_percent(100, 0, 1).unwrap()
+        );
+        assert_eq!(ColorPercent::ORANGE.to_rgb(), Rgb8::new(255, 166, 0));
+        assert_eq!("#FFA500".parse(), Ok(ColorPercent::ORANGE));
+        assert_eq!("lime".parse(), Ok(ColorPercent::GREEN));
+        assert!("#ffa50".parse::<ColorPercent>().is_err());
+    }
+
+    #[test]
+    fn mixing() {
+        let red = ColorPercent::RED;
+        let blue = ColorPercent::BLUE;
+
+        assert_eq!(
+            red.lerp(blue, 0.5),
+            ColorPercent::new_percent(50, 0, 50).unwrap()
+        );
+        assert_eq!(red.lerp(blue, 2.0), blue);
+        assert_eq!(
+            red.lerp_linear(blue, 0.5),
+            ColorPercent::new_percent(74, 0, 74).unwrap()
+        );
+        assert_eq!(red.lerp_linear(blue, 0.0), red);
+        assert_eq!(red.lerp_linear(blue, 1.0), blue);
+        assert_eq!(
+            red.scale(0.25),
+            ColorPercent::new_percent(25, 0, 0).unwrap()
+        );
+        assert_eq!(ColorPercent::WHITE.scale(0.0), ColorPercent::BLACK);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        for percent in 0..=100 {
+            let color = ColorPercent::new_percent(percent, 100 - percent, percent / 2).unwrap();
+            let json = serde_json::to_string(&color).expect("failed to serialize");
+            let parsed: ColorPercent = serde_json::from_str(&json).expect("failed to deserialize");
+            assert_eq!(parsed, color);
+        }
+
+        assert_eq!(
+            serde_json::to_string(&ColorPercent::ORANGE).expect("failed to serialize"),
+            "\"#ffa600\""
+        );
+        let parse = |json: &str| serde_json::from_str::<ColorPercent>(json);
+        assert_eq!(
+            parse("\"#ff8800\"").expect("failed to parse hex"),
+            ColorPercent::new_rgb(0xFF, 0x88, 0x00)
+        );
+        assert_eq!(
+            parse("\"Lime\"").expect("failed to parse name"),
+            ColorPercent::GREEN
+        );
+        assert_eq!(
+            parse(r#"{ "r": 100, "g": 50, "b": 0 }"#).expect("failed to parse map"),
+            ColorPercent::new_percent(100, 50, 0).unwrap()
+        );
+
+        for json in [
+            "\"#ff88\"",
+            r#"{ "r": 101, "g": 50, "b": 0 }"#,
+            r#"{ "r": 100, "g": 50 }"#,
+            r#"{ "r": 100, "g": 50, "b": 0, "a": 0 }"#,
+            r#"{ "r": 100, "r": 50, "b": 0 }"#,
+            "[100, 50, 0]",
+        ] {
+            assert!(parse(json).is_err(), "{json}");
         }
     }
 }
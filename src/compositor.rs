@@ -0,0 +1,431 @@
+use crate::ColorPercent;
+use crate::Error;
+use crate::Frame;
+use crate::KeyName;
+use crate::Sdk;
+use std::collections::HashMap;
+
+/// How a layer's colors are combined with the colors below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BlendMode {
+    /// The layer's color replaces the color below it.
+    #[default]
+    Normal,
+
+    /// The colors are added together, making them brighter.
+    Add,
+
+    /// The colors are multiplied together, making them darker.
+    Multiply,
+
+    /// The inverted colors are multiplied together, making them brighter without oversaturating.
+    Screen,
+}
+
+impl BlendMode {
+    /// Blend one channel, where both values are from 0 to 1.
+    fn blend(self, below: f32, above: f32) -> f32 {
+        match self {
+            Self::Normal => above,
+            Self::Add => (below + above).min(1.0),
+            Self::Multiply => below * above,
+            Self::Screen => 1.0 - ((1.0 - below) * (1.0 - above)),
+        }
+    }
+
+    /// Blend a color over another, where an alpha of 0 leaves `below` unchanged.
+    fn blend_color(self, below: ColorPercent, above: ColorPercent, alpha: f32) -> ColorPercent {
+        let alpha = alpha.clamp(0.0, 1.0);
+        let mix = |below: u8, above: u8| {
+            let below = f32::from(below) / 100.0;
+            let above = f32::from(above) / 100.0;
+            let value = below + ((self.blend(below, above) - below) * alpha);
+            (value.clamp(0.0, 1.0) * 100.0).round() as u8
+        };
+
+        ColorPercent {
+            r: mix(below.r, above.r),
+            g: mix(below.g, above.g),
+            b: mix(below.b, above.b),
+        }
+    }
+}
+
+/// One layer of colors in a [`Compositor`].
+///
+/// A layer may cover every key with a fill color, only some keys, or both.
+/// Keys that are set explicitly are drawn instead of the fill color.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layer {
+    /// How this layer is combined with the layers below it.
+    pub blend_mode: BlendMode,
+
+    /// The opacity of the whole layer, from 0 to 1.
+    pub opacity: f32,
+
+    fill: Option<ColorPercent>,
+    keys: HashMap<KeyName, (ColorPercent, f32)>,
+}
+
+impl Layer {
+    /// Create a new, empty, fully opaque layer.
+    pub fn new(blend_mode: BlendMode) -> Self {
+        Self {
+            blend_mode,
+            opacity: 1.0,
+            fill: None,
+            keys: HashMap::new(),
+        }
+    }
+
+    /// Create a new, fully opaque layer that covers every key with a color.
+    pub fn new_filled(color: ColorPercent, blend_mode: BlendMode) -> Self {
+        let mut layer = Self::new(blend_mode);
+        layer.fill(color);
+        layer
+    }
+
+    /// Cover every key with a color, clearing any keys that were set explicitly.
+    pub fn fill(&mut self, color: ColorPercent) {
+        self.fill = Some(color);
+        self.keys.clear();
+    }
+
+    /// Remove every color from this layer.
+    pub fn clear(&mut self) {
+        self.fill = None;
+        self.keys.clear();
+    }
+
+    /// Set the color of a key.
+    pub fn set(&mut self, key: KeyName, color: ColorPercent) {
+        self.set_with_alpha(key, color, 1.0);
+    }
+
+    /// Set the color of a key, with an alpha from 0 to 1.
+    pub fn set_with_alpha(&mut self, key: KeyName, color: ColorPercent, alpha: f32) {
+        self.keys.insert(key, (color, alpha.clamp(0.0, 1.0)));
+    }
+
+    /// Remove the color of a key, so the fill color is used instead, if any.
+    pub fn remove(&mut self, key: KeyName) {
+        self.keys.remove(&key);
+    }
+
+    /// Get the color and alpha of a key.
+    ///
+    /// # Returns
+    /// Returns None if the layer does not cover the key.
+    pub fn get(&self, key: KeyName) -> Option<(ColorPercent, f32)> {
+        self.keys
+            .get(&key)
+            .copied()
+            .or_else(|| Some((self.fill?, 1.0)))
+    }
+
+    /// Blend this layer over a color.
+    fn blend_over(&self, below: ColorPercent, above: Option<(ColorPercent, f32)>) -> ColorPercent {
+        match above {
+            Some((color, alpha)) => {
+                self.blend_mode
+                    .blend_color(below, color, alpha * self.opacity.clamp(0.0, 1.0))
+            }
+            None => below,
+        }
+    }
+}
+
+/// A handle to a layer in a [`Compositor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LayerId(u64);
+
+/// A stack of layers that are flattened into a single frame.
+///
+/// Layers with a higher priority are drawn over layers with a lower priority.
+/// Layers with the same priority are drawn in the order they were added.
+/// Everything is drawn over black.
+#[derive(Debug, Clone, Default)]
+pub struct Compositor {
+    layers: Vec<(LayerId, i32, Layer)>,
+    next_id: u64,
+    sent: Option<Frame>,
+}
+
+impl Compositor {
+    /// Create a new compositor with no layers.
+    pub fn new() -> Self {
+        Self {
+            layers: Vec::new(),
+            next_id: 0,
+            sent: None,
+        }
+    }
+
+    /// Add a layer with a priority.
+    pub fn add_layer(&mut self, priority: i32, layer: Layer) -> LayerId {
+        let id = LayerId(self.next_id);
+        self.next_id += 1;
+
+        // Insert after every layer with the same or a lower priority.
+        let index = self
+            .layers
+            .iter()
+            .position(|(_, layer_priority, _)| *layer_priority > priority)
+            .unwrap_or(self.layers.len());
+        self.layers.insert(index, (id, priority, layer));
+
+        id
+    }
+
+    /// Remove a layer.
+    ///
+    /// # Returns
+    /// Returns None if the layer is not in this compositor.
+    pub fn remove_layer(&mut self, id: LayerId) -> Option<Layer> {
+        let index = self
+            .layers
+            .iter()
+            .position(|(layer_id, _, _)| *layer_id == id)?;
+        Some(self.layers.remove(index).2)
+    }
+
+    /// Get a layer.
+    pub fn layer(&self, id: LayerId) -> Option<&Layer> {
+        self.layers
+            .iter()
+            .find(|(layer_id, _, _)| *layer_id == id)
+            .map(|(_, _, layer)| layer)
+    }
+
+    /// Get a layer mutably.
+    pub fn layer_mut(&mut self, id: LayerId) -> Option<&mut Layer> {
+        self.layers
+            .iter_mut()
+            .find(|(layer_id, _, _)| *layer_id == id)
+            .map(|(_, _, layer)| layer)
+    }
+
+    /// Change the priority of a layer, moving it above any other layers with the new priority.
+    ///
+    /// # Returns
+    /// Returns false if the layer is not in this compositor.
+    pub fn set_priority(&mut self, id: LayerId, priority: i32) -> bool {
+        let layer = match self.remove_layer(id) {
+            Some(layer) => layer,
+            None => return false,
+        };
+
+        let index = self
+            .layers
+            .iter()
+            .position(|(_, layer_priority, _)| *layer_priority > priority)
+            .unwrap_or(self.layers.len());
+        self.layers.insert(index, (id, priority, layer));
+
+        true
+    }
+
+    /// Flatten every layer into a frame.
+    ///
+    /// The background of the frame is the blend of the fill colors,
+    /// which is what devices without per-key lighting show.
+    pub fn flatten(&self) -> Frame {
+        let black = ColorPercent { r: 0, g: 0, b: 0 };
+        let background = self.layers.iter().fold(black, |color, (_, _, layer)| {
+            layer.blend_over(color, layer.fill.map(|fill| (fill, 1.0)))
+        });
+
+        let mut frame = Frame::new_filled(background);
+        for key in KeyName::iter() {
+            let color = self.layers.iter().fold(black, |color, (_, _, layer)| {
+                layer.blend_over(color, layer.get(key))
+            });
+            if color != background {
+                frame.set(key, color);
+            }
+        }
+
+        frame
+    }
+
+    /// Flatten every layer and send the keys that changed since the last flush.
+    ///
+    /// The first flush, and any flush where the background changed,
+    /// sets every device with [`Sdk::set_lighting`] and then sends every key that differs from the background.
+    /// Otherwise, only the keys that changed are sent, one by one.
+    ///
+    /// # Returns
+    /// Returns the number of keys sent one by one.
+    ///
+    /// # Errors
+    /// Returns an error if any of the calls fail.
+    /// The next flush will then send everything again.
+    pub fn flush(&mut self, sdk: &Sdk) -> Result<usize, Error> {
+        let frame = self.flatten();
+        let sent = self.sent.take();
+
+        let sent = match sent {
+            Some(sent) if sent.background() == frame.background() => sent,
+            _ => {
+                sdk.set_lighting(frame.background())?;
+                Frame::new_filled(frame.background())
+            }
+        };
+
+        let mut count = 0;
+        for key in KeyName::iter() {
+            let color = frame.get(key);
+            if sent.get(key) != color {
+                sdk.set_lighting_for_key_with_name(key, color)?;
+                count += 1;
+            }
+        }
+
+        self.sent = Some(frame);
+        Ok(count)
+    }
+
+    /// Forget what was sent, so the next flush sends everything.
+    ///
+    /// Use this if something else changed the lighting.
+    pub fn invalidate(&mut self) {
+        self.sent = None;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MockBackend;
+    use crate::MockCall;
+
+    #[test]
+    fn blend_modes() {
+        let below = ColorPercent::new_percent(50, 20, 0).unwrap();
+        let above = ColorPercent::new_percent(50, 100, 40).unwrap();
+
+        assert_eq!(BlendMode::Normal.blend_color(below, above, 1.0), above);
+        assert_eq!(BlendMode::Normal.blend_color(below, above, 0.0), below);
+        assert_eq!(
+            BlendMode::Normal.blend_color(below, above, 0.5),
+            ColorPercent::new_percent(50, 60, 20).unwrap()
+        );
+        assert_eq!(
+            BlendMode::Add.blend_color(below, above, 1.0),
+            ColorPercent::new_percent(100, 100, 40).unwrap()
+        );
+        assert_eq!(
+            BlendMode::Multiply.blend_color(below, above, 1.0),
+            ColorPercent::new_percent(25, 20, 0).unwrap()
+        );
+        assert_eq!(
+            BlendMode::Screen.blend_color(below, above, 1.0),
+            ColorPercent::new_percent(75, 100, 40).unwrap()
+        );
+    }
+
+    #[test]
+    fn layers() {
+        let blue = ColorPercent::new_percent(0, 0, 100).unwrap();
+        let red = ColorPercent::new_percent(100, 0, 0).unwrap();
+        let green = ColorPercent::new_percent(0, 100, 0).unwrap();
+
+        let mut compositor = Compositor::new();
+        let alert = compositor.add_layer(10, Layer::new(BlendMode::Normal));
+        compositor.add_layer(0, Layer::new_filled(blue, BlendMode::Normal));
+        let status = compositor.add_layer(5, Layer::new(BlendMode::Add));
+
+        compositor.layer_mut(status).unwrap().set(KeyName::F1, red);
+        compositor
+            .layer_mut(alert)
+            .unwrap()
+            .set(KeyName::ESC, green);
+        compositor
+            .layer_mut(alert)
+            .unwrap()
+            .set_with_alpha(KeyName::F1, green, 0.5);
+
+        let frame = compositor.flatten();
+        assert_eq!(frame.background(), blue);
+        assert_eq!(frame.get(KeyName::W), blue);
+        assert_eq!(frame.get(KeyName::ESC), green);
+        assert_eq!(
+            frame.get(KeyName::F1),
+            ColorPercent::new_percent(50, 50, 50).unwrap()
+        );
+
+        // Moving the alert below the status overlay.
+        assert!(compositor.set_priority(alert, 1));
+        assert_eq!(
+            compositor.flatten().get(KeyName::F1),
+            ColorPercent::new_percent(100, 50, 50).unwrap()
+        );
+
+        compositor.layer_mut(status).unwrap().opacity = 0.0;
+        compositor.remove_layer(alert).unwrap();
+        assert_eq!(compositor.flatten(), Frame::new_filled(blue));
+        assert!(!compositor.set_priority(alert, 1));
+    }
+
+    #[test]
+    fn flush_changed_keys() {
+        let backend = MockBackend::new();
+        let sdk = Sdk::new_with_backend(backend.clone()).expect("failed to init mock sdk");
+        let blue = ColorPercent::new_percent(0, 0, 100).unwrap();
+        let red = ColorPercent::new_percent(100, 0, 0).unwrap();
+
+        let mut compositor = Compositor::new();
+        let base = compositor.add_layer(0, Layer::new_filled(blue, BlendMode::Normal));
+        let overlay = compositor.add_layer(1, Layer::new(BlendMode::Normal));
+        compositor.layer_mut(overlay).unwrap().set(KeyName::F1, red);
+
+        backend.take_calls();
+        assert_eq!(compositor.flush(&sdk), Ok(1));
+        assert_eq!(
+            backend.take_calls(),
+            [
+                MockCall::SetLighting { color: blue },
+                MockCall::SetLightingForKeyWithKeyName {
+                    key: KeyName::F1,
+                    color: red
+                },
+            ]
+        );
+        assert_eq!(backend.key_color(KeyName::W), Some(blue));
+        assert_eq!(backend.key_color(KeyName::F1), Some(red));
+
+        // Nothing changed.
+        assert_eq!(compositor.flush(&sdk), Ok(0));
+        assert!(backend.take_calls().is_empty());
+
+        // Only the changed keys are sent.
+        let layer = compositor.layer_mut(overlay).unwrap();
+        layer.remove(KeyName::F1);
+        layer.set(KeyName::F2, red);
+        assert_eq!(compositor.flush(&sdk), Ok(2));
+        assert_eq!(backend.key_color(KeyName::F1), Some(blue));
+        assert_eq!(backend.key_color(KeyName::F2), Some(red));
+        assert!(!backend
+            .take_calls()
+            .contains(&MockCall::SetLighting { color: blue }));
+
+        // A new background resends everything.
+        compositor.layer_mut(base).unwrap().fill(red);
+        assert_eq!(compositor.flush(&sdk), Ok(0));
+        assert_eq!(backend.take_calls(), [MockCall::SetLighting { color: red }]);
+
+        // Errors resend everything on the next flush.
+        backend.set_failing("LogiLedSetLighting", true);
+        compositor.invalidate();
+        assert!(compositor.flush(&sdk).is_err());
+        backend.set_failing("LogiLedSetLighting", false);
+        assert_eq!(compositor.flush(&sdk), Ok(0));
+        assert_eq!(
+            backend.take_calls(),
+            [
+                MockCall::SetLighting { color: red },
+                MockCall::SetLighting { color: red },
+            ]
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use crate::Rgb8;
+
+/// The CSS named colors, sorted by name so they can be binary searched.
+pub(crate) const CSS_COLORS: &[(&str, Rgb8)] = &[
+    ("aliceblue", Rgb8::new(0xf0, 0xf8, 0xff)),
+    ("antiquewhite", Rgb8::new(0xfa, 0xeb, 0xd7)),
+    ("aqua", Rgb8::new(0x00, 0xff, 0xff)),
+    ("aquamarine", Rgb8::new(0x7f, 0xff, 0xd4)),
+    ("azure", Rgb8::new(0xf0, 0xff, 0xff)),
+    ("beige", Rgb8::new(0xf5, 0xf5, 0xdc)),
+    ("bisque", Rgb8::new(0xff, 0xe4, 0xc4)),
+    ("black", Rgb8::new(0x00, 0x00, 0x00)),
+    ("blanchedalmond", Rgb8::new(0xff, 0xeb, 0xcd)),
+    ("blue", Rgb8::new(0x00, 0x00, 0xff)),
+    ("blueviolet", Rgb8::new(0x8a, 0x2b, 0xe2)),
+    ("brown", Rgb8::new(0xa5, 0x2a, 0x2a)),
+    ("burlywood", Rgb8::new(0xde, 0xb8, 0x87)),
+    ("cadetblue", Rgb8::new(0x5f, 0x9e, 0xa0)),
+    ("chartreuse", Rgb8::new(0x7f, 0xff, 0x00)),
+    ("chocolate", Rgb8::new(0xd2, 0x69, 0x1e)),
+    ("coral", Rgb8::new(0xff, 0x7f, 0x50)),
+    ("cornflowerblue", Rgb8::new(0x64, 0x95, 0xed)),
+    ("cornsilk", Rgb8::new(0xff, 0xf8, 0xdc)),
+    ("crimson", Rgb8::new(0xdc, 0x14, 0x3c)),
+    ("cyan", Rgb8::new(0x00, 0xff, 0xff)),
+    ("darkblue", Rgb8::new(0x00, 0x00, 0x8b)),
+    ("darkcyan", Rgb8::new(0x00, 0x8b, 0x8b)),
+    ("darkgoldenrod", Rgb8::new(0xb8, 0x86, 0x0b)),
+    ("darkgray", Rgb8::new(0xa9, 0xa9, 0xa9)),
+    ("darkgreen", Rgb8::new(0x00, 0x64, 0x00)),
+    ("darkgrey", Rgb8::new(0xa9, 0xa9, 0xa9)),
+    ("darkkhaki", Rgb8::new(0xbd, 0xb7, 0x6b)),
+    ("darkmagenta", Rgb8::new(0x8b, 0x00, 0x8b)),
+    ("darkolivegreen", Rgb8::new(0x55, 0x6b, 0x2f)),
+    ("darkorange", Rgb8::new(0xff, 0x8c, 0x00)),
+    ("darkorchid", Rgb8::new(0x99, 0x32, 0xcc)),
+    ("darkred", Rgb8::new(0x8b, 0x00, 0x00)),
+    ("darksalmon", Rgb8::new(0xe9, 0x96, 0x7a)),
+    ("darkseagreen", Rgb8::new(0x8f, 0xbc, 0x8f)),
+    ("darkslateblue", Rgb8::new(0x48, 0x3d, 0x8b)),
+    ("darkslategray", Rgb8::new(0x2f, 0x4f, 0x4f)),
+    ("darkslategrey", Rgb8::new(0x2f, 0x4f, 0x4f)),
+    ("darkturquoise", Rgb8::new(0x00, 0xce, 0xd1)),
+    ("darkviolet", Rgb8::new(0x94, 0x00, 0xd3)),
+    ("deeppink", Rgb8::new(0xff, 0x14, 0x93)),
+    ("deepskyblue", Rgb8::new(0x00, 0xbf, 0xff)),
+    ("dimgray", Rgb8::new(0x69, 0x69, 0x69)),
+    ("dimgrey", Rgb8::new(0x69, 0x69, 0x69)),
+    ("dodgerblue", Rgb8::new(0x1e, 0x90, 0xff)),
+    ("firebrick", Rgb8::new(0xb2, 0x22, 0x22)),
+    ("floralwhite", Rgb8::new(0xff, 0xfa, 0xf0)),
+    ("forestgreen", Rgb8::new(0x22, 0x8b, 0x22)),
+    ("fuchsia", Rgb8::new(0xff, 0x00, 0xff)),
+    ("gainsboro", Rgb8::new(0xdc, 0xdc, 0xdc)),
+    ("ghostwhite", Rgb8::new(0xf8, 0xf8, 0xff)),
+    ("gold", Rgb8::new(0xff, 0xd7, 0x00)),
+    ("goldenrod", Rgb8::new(0xda, 0xa5, 0x20)),
+    ("gray", Rgb8::new(0x80, 0x80, 0x80)),
+    ("green", Rgb8::new(0x00, 0x80, 0x00)),
+    ("greenyellow", Rgb8::new(0xad, 0xff, 0x2f)),
+    ("grey", Rgb8::new(0x80, 0x80, 0x80)),
+    ("honeydew", Rgb8::new(0xf0, 0xff, 0xf0)),
+    ("hotpink", Rgb8::new(0xff, 0x69, 0xb4)),
+    ("indianred", Rgb8::new(0xcd, 0x5c, 0x5c)),
+    ("indigo", Rgb8::new(0x4b, 0x00, 0x82)),
+    ("ivory", Rgb8::new(0xff, 0xff, 0xf0)),
+    ("khaki", Rgb8::new(0xf0, 0xe6, 0x8c)),
+    ("lavender", Rgb8::new(0xe6, 0xe6, 0xfa)),
+    ("lavenderblush", Rgb8::new(0xff, 0xf0, 0xf5)),
+    ("lawngreen", Rgb8::new(0x7c, 0xfc, 0x00)),
+    ("lemonchiffon", Rgb8::new(0xff, 0xfa, 0xcd)),
+    ("lightblue", Rgb8::new(0xad, 0xd8, 0xe6)),
+    ("lightcoral", Rgb8::new(0xf0, 0x80, 0x80)),
+    ("lightcyan", Rgb8::new(0xe0, 0xff, 0xff)),
+    ("lightgoldenrodyellow", Rgb8::new(0xfa, 0xfa, 0xd2)),
+    ("lightgray", Rgb8::new(0xd3, 0xd3, 0xd3)),
+    ("lightgreen", Rgb8::new(0x90, 0xee, 0x90)),
+    ("lightgrey", Rgb8::new(0xd3, 0xd3, 0xd3)),
+    ("lightpink", Rgb8::new(0xff, 0xb6, 0xc1)),
+    ("lightsalmon", Rgb8::new(0xff, 0xa0, 0x7a)),
+    ("lightseagreen", Rgb8::new(0x20, 0xb2, 0xaa)),
+    ("lightskyblue", Rgb8::new(0x87, 0xce, 0xfa)),
+    ("lightslategray", Rgb8::new(0x77, 0x88, 0x99)),
+    ("lightslategrey", Rgb8::new(0x77, 0x88, 0x99)),
+    ("lightsteelblue", Rgb8::new(0xb0, 0xc4, 0xde)),
+    ("lightyellow", Rgb8::new(0xff, 0xff, 0xe0)),
+    ("lime", Rgb8::new(0x00, 0xff, 0x00)),
+    ("limegreen", Rgb8::new(0x32, 0xcd, 0x32)),
+    ("linen", Rgb8::new(0xfa, 0xf0, 0xe6)),
+    ("magenta", Rgb8::new(0xff, 0x00, 0xff)),
+    ("maroon", Rgb8::new(0x80, 0x00, 0x00)),
+    ("mediumaquamarine", Rgb8::new(0x66, 0xcd, 0xaa)),
+    ("mediumblue", Rgb8::new(0x00, 0x00, 0xcd)),
+    ("mediumorchid", Rgb8::new(0xba, 0x55, 0xd3)),
+    ("mediumpurple", Rgb8::new(0x93, 0x70, 0xdb)),
+    ("mediumseagreen", Rgb8::new(0x3c, 0xb3, 0x71)),
+    ("mediumslateblue", Rgb8::new(0x7b, 0x68, 0xee)),
+    ("mediumspringgreen", Rgb8::new(0x00, 0xfa, 0x9a)),
+    ("mediumturquoise", Rgb8::new(0x48, 0xd1, 0xcc)),
+    ("mediumvioletred", Rgb8::new(0xc7, 0x15, 0x85)),
+    ("midnightblue", Rgb8::new(0x19, 0x19, 0x70)),
+    ("mintcream", Rgb8::new(0xf5, 0xff, 0xfa)),
+    ("mistyrose", Rgb8::new(0xff, 0xe4, 0xe1)),
+    ("moccasin", Rgb8::new(0xff, 0xe4, 0xb5)),
+    ("navajowhite", Rgb8::new(0xff, 0xde, 0xad)),
+    ("navy", Rgb8::new(0x00, 0x00, 0x80)),
+    ("oldlace", Rgb8::new(0xfd, 0xf5, 0xe6)),
+    ("olive", Rgb8::new(0x80, 0x80, 0x00)),
+    ("olivedrab", Rgb8::new(0x6b, 0x8e, 0x23)),
+    ("orange", Rgb8::new(0xff, 0xa5, 0x00)),
+    ("orangered", Rgb8::new(0xff, 0x45, 0x00)),
+    ("orchid", Rgb8::new(0xda, 0x70, 0xd6)),
+    ("palegoldenrod", Rgb8::new(0xee, 0xe8, 0xaa)),
+    ("palegreen", Rgb8::new(0x98, 0xfb, 0x98)),
+    ("paleturquoise", Rgb8::new(0xaf, 0xee, 0xee)),
+    ("palevioletred", Rgb8::new(0xdb, 0x70, 0x93)),
+    ("papayawhip", Rgb8::new(0xff, 0xef, 0xd5)),
+    ("peachpuff", Rgb8::new(0xff, 0xda, 0xb9)),
+    ("peru", Rgb8::new(0xcd, 0x85, 0x3f)),
+    ("pink", Rgb8::new(0xff, 0xc0, 0xcb)),
+    ("plum", Rgb8::new(0xdd, 0xa0, 0xdd)),
+    ("powderblue", Rgb8::new(0xb0, 0xe0, 0xe6)),
+    ("purple", Rgb8::new(0x80, 0x00, 0x80)),
+    ("rebeccapurple", Rgb8::new(0x66, 0x33, 0x99)),
+    ("red", Rgb8::new(0xff, 0x00, 0x00)),
+    ("rosybrown", Rgb8::new(0xbc, 0x8f, 0x8f)),
+    ("royalblue", Rgb8::new(0x41, 0x69, 0xe1)),
+    ("saddlebrown", Rgb8::new(0x8b, 0x45, 0x13)),
+    ("salmon", Rgb8::new(0xfa, 0x80, 0x72)),
+    ("sandybrown", Rgb8::new(0xf4, 0xa4, 0x60)),
+    ("seagreen", Rgb8::new(0x2e, 0x8b, 0x57)),
+    ("seashell", Rgb8::new(0xff, 0xf5, 0xee)),
+    ("sienna", Rgb8::new(0xa0, 0x52, 0x2d)),
+    ("silver", Rgb8::new(0xc0, 0xc0, 0xc0)),
+    ("skyblue", Rgb8::new(0x87, 0xce, 0xeb)),
+    ("slateblue", Rgb8::new(0x6a, 0x5a, 0xcd)),
+    ("slategray", Rgb8::new(0x70, 0x80, 0x90)),
+    ("slategrey", Rgb8::new(0x70, 0x80, 0x90)),
+    ("snow", Rgb8::new(0xff, 0xfa, 0xfa)),
+    ("springgreen", Rgb8::new(0x00, 0xff, 0x7f)),
+    ("steelblue", Rgb8::new(0x46, 0x82, 0xb4)),
+    ("tan", Rgb8::new(0xd2, 0xb4, 0x8c)),
+    ("teal", Rgb8::new(0x00, 0x80, 0x80)),
+    ("thistle", Rgb8::new(0xd8, 0xbf, 0xd8)),
+    ("tomato", Rgb8::new(0xff, 0x63, 0x47)),
+    ("turquoise", Rgb8::new(0x40, 0xe0, 0xd0)),
+    ("violet", Rgb8::new(0xee, 0x82, 0xee)),
+    ("wheat", Rgb8::new(0xf5, 0xde, 0xb3)),
+    ("white", Rgb8::new(0xff, 0xff, 0xff)),
+    ("whitesmoke", Rgb8::new(0xf5, 0xf5, 0xf5)),
+    ("yellow", Rgb8::new(0xff, 0xff, 0x00)),
+    ("yellowgreen", Rgb8::new(0x9a, 0xcd, 0x32)),
+];
+
+/// Look up a CSS named color, ignoring case.
+pub(crate) fn css_color(name: &str) -> Option<Rgb8> {
+    let name = name.to_ascii_lowercase();
+    CSS_COLORS
+        .binary_search_by(|(color_name, _)| (*color_name).cmp(name.as_str()))
+        .ok()
+        .map(|index| CSS_COLORS[index].1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sorted() {
+        assert_eq!(CSS_COLORS.len(), 148);
+        assert!(CSS_COLORS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(
+            css_color("RebeccaPurple"),
+            Some(Rgb8::new(0x66, 0x33, 0x99))
+        );
+        assert_eq!(css_color("not a color"), None);
+    }
+}
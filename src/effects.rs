@@ -0,0 +1,128 @@
+//! Software lighting effects for an [`Animator`](crate::Animator).
+//!
+//! Every effect sets the background color of the frame as well as the color of each key,
+//! so devices without per-key lighting still follow the effect through [`Sdk::set_lighting`](crate::Sdk::set_lighting).
+//! Spatial effects use a [`KeyboardLayout`](crate::KeyboardLayout) to find where each key is.
+
+mod breathing;
+mod color_cycle;
+mod fire;
+mod gradient_sweep;
+mod ripple;
+mod twinkle;
+mod wave;
+
+pub use self::breathing::Breathing;
+pub use self::color_cycle::ColorCycle;
+pub use self::fire::Fire;
+pub use self::gradient_sweep::GradientSweep;
+pub use self::ripple::Ripple;
+pub use self::twinkle::Twinkle;
+pub use self::wave::Wave;
+
+use crate::KeyRect;
+use std::time::Duration;
+
+/// The direction that a spatial effect moves in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    /// Left to right.
+    Horizontal,
+
+    /// Top to bottom.
+    Vertical,
+
+    /// Outwards from a point, in key units.
+    Radial {
+        /// The x position of the center.
+        x: f32,
+
+        /// The y position of the center.
+        y: f32,
+    },
+}
+
+impl Direction {
+    /// Get the position of a key along this direction, in key units from the top left of `bounds`,
+    /// or from the center for radial directions.
+    fn position(&self, bounds: KeyRect, key: KeyRect) -> f32 {
+        let (x, y) = key.center();
+        match *self {
+            Self::Horizontal => x - bounds.x,
+            Self::Vertical => y - bounds.y,
+            Self::Radial {
+                x: center_x,
+                y: center_y,
+            } => (x - center_x).hypot(y - center_y),
+        }
+    }
+}
+
+/// Get how far through its period an effect is, from 0 to 1.
+///
+/// A zero period is always at the start.
+fn phase(time: Duration, period: Duration) -> f32 {
+    if period.is_zero() {
+        return 0.0;
+    }
+
+    (time.as_secs_f64() / period.as_secs_f64()).fract() as f32
+}
+
+/// A small, seedable random number generator, so that random effects are reproducible.
+#[derive(Debug, Clone)]
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // xorshift gets stuck at zero.
+        Self { state: seed.max(1) }
+    }
+
+    /// Get the next number, using xorshift64*.
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Get a number from 0 to 1, not including 1.
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Get a number from 0 to `len`, not including `len`.
+    fn next_index(&mut self, len: usize) -> usize {
+        (self.next_u64() % len as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn phases() {
+        assert_eq!(
+            phase(Duration::from_millis(2500), Duration::from_secs(1)),
+            0.5
+        );
+        assert_eq!(phase(Duration::from_secs(1), Duration::ZERO), 0.0);
+    }
+
+    #[test]
+    fn rng() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+        for _ in 0..100 {
+            let value = a.next_f32();
+            assert_eq!(value, b.next_f32());
+            assert!((0.0..1.0).contains(&value));
+            assert!(a.next_index(10) < 10);
+            b.next_index(10);
+        }
+    }
+}
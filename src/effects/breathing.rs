@@ -0,0 +1,55 @@
+use super::phase;
+use crate::ColorPercent;
+use crate::Effect;
+use crate::Frame;
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+/// Smoothly fades every key in and out of a color.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breathing {
+    /// The color at full brightness.
+    pub color: ColorPercent,
+
+    /// The time for one breath, from off to full brightness and back.
+    pub period: Duration,
+}
+
+impl Breathing {
+    /// Make a new breathing effect.
+    pub fn new(color: ColorPercent, period: Duration) -> Self {
+        Self { color, period }
+    }
+}
+
+impl Effect for Breathing {
+    fn render(&mut self, time: Duration, frame: &mut Frame) {
+        let brightness = (1.0 - (phase(time, self.period) * TAU).cos()) / 2.0;
+        frame.fill(self.color.scale(brightness));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::KeyName;
+
+    #[test]
+    fn breathe() {
+        let color = ColorPercent::new_percent(100, 50, 0).unwrap();
+        let mut effect = Breathing::new(color, Duration::from_secs(4));
+        let mut frame = Frame::new();
+
+        effect.render(Duration::ZERO, &mut frame);
+        assert_eq!(frame.get(KeyName::W), ColorPercent { r: 0, g: 0, b: 0 });
+
+        effect.render(Duration::from_secs(1), &mut frame);
+        assert_eq!(
+            frame.get(KeyName::W),
+            ColorPercent::new_percent(50, 25, 0).unwrap()
+        );
+
+        effect.render(Duration::from_secs(2), &mut frame);
+        assert_eq!(frame.get(KeyName::W), color);
+    }
+}
@@ -0,0 +1,71 @@
+use super::phase;
+use crate::ColorPercent;
+use crate::Effect;
+use crate::Frame;
+use crate::Hsv;
+use std::time::Duration;
+
+/// Fades every key through the colors of the rainbow.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorCycle {
+    /// The time to go through every color once.
+    pub period: Duration,
+
+    /// The saturation of the colors, from 0 to 1.
+    pub saturation: f32,
+
+    /// The brightness of the colors, from 0 to 1.
+    pub value: f32,
+}
+
+impl ColorCycle {
+    /// Make a new color cycle with fully saturated, full brightness colors.
+    pub fn new(period: Duration) -> Self {
+        Self {
+            period,
+            saturation: 1.0,
+            value: 1.0,
+        }
+    }
+}
+
+impl Effect for ColorCycle {
+    fn render(&mut self, time: Duration, frame: &mut Frame) {
+        let hue = phase(time, self.period) * 360.0;
+        frame.fill(ColorPercent::from(Hsv::new(
+            hue,
+            self.saturation,
+            self.value,
+        )));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::KeyName;
+
+    #[test]
+    fn cycle() {
+        let mut effect = ColorCycle::new(Duration::from_secs(3));
+        let mut frame = Frame::new();
+
+        effect.render(Duration::ZERO, &mut frame);
+        assert_eq!(
+            frame.get(KeyName::W),
+            ColorPercent::new_percent(100, 0, 0).unwrap()
+        );
+
+        effect.render(Duration::from_secs(1), &mut frame);
+        assert_eq!(
+            frame.background(),
+            ColorPercent::new_percent(0, 100, 0).unwrap()
+        );
+
+        effect.render(Duration::from_secs(5), &mut frame);
+        assert_eq!(
+            frame.background(),
+            ColorPercent::new_percent(0, 0, 100).unwrap()
+        );
+    }
+}
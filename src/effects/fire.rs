@@ -0,0 +1,122 @@
+use super::Rng;
+use crate::ColorPercent;
+use crate::Effect;
+use crate::Frame;
+use crate::KeyName;
+use crate::KeyboardLayout;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Flickering flames that burn hottest at the bottom of the keyboard.
+#[derive(Debug, Clone)]
+pub struct Fire {
+    /// The position of each key.
+    pub layout: KeyboardLayout,
+
+    /// How much the flames flicker, from 0 to 1.
+    pub flicker: f32,
+
+    /// How much of the previous frame's heat is kept, from 0 to 1.
+    ///
+    /// Higher values make the flames change more slowly.
+    pub smoothing: f32,
+
+    rng: Rng,
+    heat: HashMap<KeyName, f32>,
+}
+
+impl Fire {
+    /// Make a new fire effect on an ANSI layout.
+    ///
+    /// The flicker is random, but the same seed always gives the same flames.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            layout: KeyboardLayout::ansi(),
+            flicker: 0.5,
+            smoothing: 0.5,
+
+            rng: Rng::new(seed),
+            heat: HashMap::new(),
+        }
+    }
+
+    /// Get the color of a flame from its heat, from 0 to 1.
+    fn color(heat: f32) -> ColorPercent {
+        const STOPS: [ColorPercent; 4] = [
+            ColorPercent { r: 0, g: 0, b: 0 },
+            ColorPercent { r: 100, g: 0, b: 0 },
+            ColorPercent {
+                r: 100,
+                g: 50,
+                b: 0,
+            },
+            ColorPercent {
+                r: 100,
+                g: 90,
+                b: 30,
+            },
+        ];
+
+        let position = heat.clamp(0.0, 1.0) * (STOPS.len() - 1) as f32;
+        let index = (position as usize).min(STOPS.len() - 2);
+        STOPS[index].lerp(STOPS[index + 1], position - index as f32)
+    }
+}
+
+impl Effect for Fire {
+    fn render(&mut self, _time: Duration, frame: &mut Frame) {
+        frame.fill(Self::color(0.6));
+
+        let bounds = match self.layout.bounds() {
+            Some(bounds) => bounds,
+            None => return,
+        };
+        let flicker = self.flicker.clamp(0.0, 1.0);
+        let smoothing = self.smoothing.clamp(0.0, 1.0);
+
+        for (key, rect) in self.layout.iter() {
+            let (_, y) = rect.center();
+            let height = ((y - bounds.y) / bounds.height.max(f32::EPSILON)).clamp(0.0, 1.0);
+            let target = height.powf(1.5) * (1.0 - (flicker * self.rng.next_f32()));
+
+            let heat = self.heat.entry(key).or_insert(target);
+            *heat = (*heat * smoothing) + (target * (1.0 - smoothing));
+            frame.set(key, Self::color(*heat));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hotter_at_the_bottom() {
+        let mut effect = Fire::new(3);
+        let mut frame = Frame::new();
+        for i in 0..10 {
+            effect.render(Duration::from_millis(i * 33), &mut frame);
+        }
+
+        let top = frame.get(KeyName::ESC);
+        let bottom = frame.get(KeyName::SPACE);
+        assert!(u16::from(bottom.r) + u16::from(bottom.g) > u16::from(top.r) + u16::from(top.g));
+
+        let mut other = Fire::new(3);
+        let mut other_frame = Frame::new();
+        for i in 0..10 {
+            other.render(Duration::from_millis(i * 33), &mut other_frame);
+        }
+        assert_eq!(frame, other_frame);
+
+        assert_eq!(Fire::color(0.0), ColorPercent { r: 0, g: 0, b: 0 });
+        assert_eq!(
+            Fire::color(1.0),
+            ColorPercent {
+                r: 100,
+                g: 90,
+                b: 30
+            }
+        );
+    }
+}
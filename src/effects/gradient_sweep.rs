@@ -0,0 +1,110 @@
+use super::phase;
+use super::Direction;
+use crate::ColorPercent;
+use crate::Effect;
+use crate::Frame;
+use crate::KeyboardLayout;
+use std::time::Duration;
+
+/// A gradient between two colors that sweeps across the keyboard and back.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradientSweep {
+    /// The position of each key.
+    pub layout: KeyboardLayout,
+
+    /// The direction the gradient moves in.
+    pub direction: Direction,
+
+    /// The color at the start of the gradient.
+    pub from: ColorPercent,
+
+    /// The color at the end of the gradient.
+    pub to: ColorPercent,
+
+    /// The time for the gradient to sweep across the keyboard and back.
+    pub period: Duration,
+}
+
+impl GradientSweep {
+    /// Make a new horizontal gradient sweep on an ANSI layout.
+    pub fn new(from: ColorPercent, to: ColorPercent, period: Duration) -> Self {
+        Self {
+            layout: KeyboardLayout::ansi(),
+            direction: Direction::Horizontal,
+            from,
+            to,
+            period,
+        }
+    }
+
+    /// Get the color at a position, where 0 is the start of the gradient and 1 is the end.
+    fn color(&self, position: f32, phase: f32) -> ColorPercent {
+        // A triangle wave, so that the gradient is continuous when it wraps around.
+        let t = (position - phase).rem_euclid(1.0);
+        self.from.lerp(self.to, 1.0 - ((2.0 * t) - 1.0).abs())
+    }
+}
+
+impl Effect for GradientSweep {
+    fn render(&mut self, time: Duration, frame: &mut Frame) {
+        let phase = phase(time, self.period);
+        frame.fill(self.color(0.0, phase));
+
+        let bounds = match self.layout.bounds() {
+            Some(bounds) => bounds,
+            None => return,
+        };
+        let length = match self.direction {
+            Direction::Horizontal => bounds.width,
+            Direction::Vertical => bounds.height,
+            Direction::Radial { .. } => bounds.width.hypot(bounds.height),
+        };
+        // The gradient spans the keyboard twice per period: there and back.
+        let length = (length * 2.0).max(f32::EPSILON);
+
+        for (key, rect) in self.layout.iter() {
+            let position = self.direction.position(bounds, rect) / length;
+            frame.set(key, self.color(position, phase));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::KeyName;
+    use crate::KeyRect;
+
+    #[test]
+    fn sweep() {
+        let red = ColorPercent::new_percent(100, 0, 0).unwrap();
+        let blue = ColorPercent::new_percent(0, 0, 100).unwrap();
+        let mut effect = GradientSweep::new(red, blue, Duration::from_secs(2));
+        effect.layout = KeyboardLayout::new();
+        effect
+            .layout
+            .insert(KeyName::A, KeyRect::new(0.0, 0.0, 1.0, 1.0));
+        effect
+            .layout
+            .insert(KeyName::B, KeyRect::new(1.0, 0.0, 1.0, 1.0));
+        effect
+            .layout
+            .insert(KeyName::C, KeyRect::new(2.0, 0.0, 1.0, 1.0));
+        effect
+            .layout
+            .insert(KeyName::D, KeyRect::new(3.0, 0.0, 1.0, 1.0));
+        let mut frame = Frame::new();
+
+        // The layout is 4 keys wide, so the gradient is 8 keys long.
+        effect.render(Duration::ZERO, &mut frame);
+        assert_eq!(frame.background(), red);
+        assert_eq!(frame.get(KeyName::A), red.lerp(blue, 0.125));
+        assert_eq!(frame.get(KeyName::D), red.lerp(blue, 0.875));
+
+        // Half way through, the gradient is reversed.
+        effect.render(Duration::from_secs(1), &mut frame);
+        assert_eq!(frame.background(), blue);
+        assert_eq!(frame.get(KeyName::A), red.lerp(blue, 0.875));
+        assert_eq!(frame.get(KeyName::D), red.lerp(blue, 0.125));
+    }
+}
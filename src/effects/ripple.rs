@@ -0,0 +1,121 @@
+use crate::ColorPercent;
+use crate::Effect;
+use crate::Frame;
+use crate::KeyName;
+use crate::KeyboardLayout;
+use std::time::Duration;
+
+/// A ring of light that spreads out from each pressed key.
+///
+/// Key presses are passed in through [`Animator::key_press`](crate::Animator::key_press).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ripple {
+    /// The position of each key.
+    pub layout: KeyboardLayout,
+
+    /// The color of keys without a ripple.
+    pub base: ColorPercent,
+
+    /// The color of a ripple.
+    pub color: ColorPercent,
+
+    /// How fast a ripple spreads, in key units per second.
+    pub speed: f32,
+
+    /// The width of a ripple, in key units.
+    pub width: f32,
+
+    /// How long a ripple lasts, fading out as it goes.
+    pub lifetime: Duration,
+
+    /// The center of each ripple and when it started.
+    ripples: Vec<((f32, f32), Duration)>,
+}
+
+impl Ripple {
+    /// Make a new ripple effect on an ANSI layout.
+    pub fn new(base: ColorPercent, color: ColorPercent) -> Self {
+        Self {
+            layout: KeyboardLayout::ansi(),
+            base,
+            color,
+            speed: 20.0,
+            width: 1.5,
+            lifetime: Duration::from_secs(1),
+
+            ripples: Vec::new(),
+        }
+    }
+
+    /// Get how bright a ripple is at a distance from its center, from 0 to 1.
+    fn brightness(&self, distance: f32, age: Duration) -> f32 {
+        let radius = self.speed * age.as_secs_f32();
+        let ring = 1.0 - ((distance - radius).abs() / self.width.max(f32::EPSILON));
+        let fade = 1.0 - (age.as_secs_f32() / self.lifetime.as_secs_f32());
+        ring.max(0.0) * fade.max(0.0)
+    }
+}
+
+impl Effect for Ripple {
+    fn render(&mut self, time: Duration, frame: &mut Frame) {
+        let lifetime = self.lifetime;
+        self.ripples
+            .retain(|(_, start)| *start <= time && time - *start < lifetime);
+
+        frame.fill(self.base);
+        for (key, rect) in self.layout.iter() {
+            let (x, y) = rect.center();
+            let brightness = self
+                .ripples
+                .iter()
+                .map(|((center_x, center_y), start)| {
+                    let distance = (x - center_x).hypot(y - center_y);
+                    self.brightness(distance, time - *start)
+                })
+                .fold(0.0, f32::max);
+
+            if brightness > 0.0 {
+                frame.set(key, self.base.lerp(self.color, brightness));
+            }
+        }
+    }
+
+    fn on_key_press(&mut self, key: KeyName, time: Duration) {
+        if let Some(rect) = self.layout.get(key) {
+            self.ripples.push((rect.center(), time));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn spread() {
+        let black = ColorPercent { r: 0, g: 0, b: 0 };
+        let white = ColorPercent::new_percent(100, 100, 100).unwrap();
+        let mut effect = Ripple::new(black, white);
+        effect.speed = 10.0;
+        effect.width = 1.0;
+        let mut frame = Frame::new();
+
+        effect.render(Duration::ZERO, &mut frame);
+        assert_eq!(frame.iter().count(), 0);
+
+        effect.on_key_press(KeyName::G, Duration::ZERO);
+        effect.render(Duration::ZERO, &mut frame);
+        assert_eq!(frame.get(KeyName::G), white);
+        assert_eq!(frame.get(KeyName::F), black);
+        assert_eq!(frame.get(KeyName::NUM_ENTER), black);
+
+        // After 100ms, the ring is 1 key out and has faded by 10%.
+        effect.render(Duration::from_millis(100), &mut frame);
+        assert_eq!(frame.get(KeyName::F), black.lerp(white, 0.9));
+        assert_eq!(frame.get(KeyName::H), black.lerp(white, 0.9));
+        assert_eq!(frame.get(KeyName::G), black);
+
+        effect.render(Duration::from_secs(1), &mut frame);
+        assert_eq!(frame.iter().count(), 0);
+    }
+}
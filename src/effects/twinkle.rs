@@ -0,0 +1,139 @@
+use super::Rng;
+use crate::ColorPercent;
+use crate::Effect;
+use crate::Frame;
+use crate::KeyName;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Random keys light up like stars, then fade out.
+#[derive(Debug, Clone)]
+pub struct Twinkle {
+    /// The color of keys without a star.
+    pub base: ColorPercent,
+
+    /// The color of a star when it appears.
+    pub color: ColorPercent,
+
+    /// The average number of stars that appear each second.
+    ///
+    /// A negative or NaN rate is treated as 0,
+    /// and no more stars than there are keys appear in one frame, even with an infinite rate.
+    pub rate: f32,
+
+    /// The time for a star to fade out.
+    pub fade: Duration,
+
+    /// The keys that stars can appear on.
+    pub keys: Vec<KeyName>,
+
+    rng: Rng,
+    stars: HashMap<KeyName, Duration>,
+    last_time: Option<Duration>,
+}
+
+impl Twinkle {
+    /// Make a new twinkle effect over every key.
+    ///
+    /// The stars are random, but the same seed always gives the same stars.
+    pub fn new(base: ColorPercent, color: ColorPercent, seed: u64) -> Self {
+        Self {
+            base,
+            color,
+            rate: 10.0,
+            fade: Duration::from_secs(1),
+            keys: KeyName::iter().collect(),
+
+            rng: Rng::new(seed),
+            stars: HashMap::new(),
+            last_time: None,
+        }
+    }
+}
+
+impl Effect for Twinkle {
+    fn render(&mut self, time: Duration, frame: &mut Frame) {
+        // The animation restarted.
+        let last_time = match self.last_time {
+            Some(last_time) if last_time <= time => last_time,
+            _ => {
+                self.stars.clear();
+                time
+            }
+        };
+        self.last_time = Some(time);
+
+        if !self.keys.is_empty() {
+            // Spawn whole stars, then one more with a chance of the fractional part.
+            let expected = self.rate.max(0.0) * (time - last_time).as_secs_f32();
+            let mut count = expected.floor() as usize;
+            if self.rng.next_f32() < expected.fract() {
+                count += 1;
+            }
+            let count = count.min(self.keys.len());
+
+            for _ in 0..count {
+                let key = self.keys[self.rng.next_index(self.keys.len())];
+                self.stars.insert(key, time);
+            }
+        }
+
+        let fade = self.fade;
+        self.stars.retain(|_, start| time - *start < fade);
+
+        frame.fill(self.base);
+        for (key, start) in self.stars.iter() {
+            let brightness = 1.0 - ((time - *start).as_secs_f32() / fade.as_secs_f32());
+            frame.set(*key, self.base.lerp(self.color, brightness));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stars_appear_and_fade() {
+        let base = ColorPercent::new_percent(0, 0, 10).unwrap();
+        let white = ColorPercent::new_percent(100, 100, 100).unwrap();
+        let mut effect = Twinkle::new(base, white, 1);
+        effect.rate = 100.0;
+        let mut frame = Frame::new();
+
+        effect.render(Duration::ZERO, &mut frame);
+        assert_eq!(frame.iter().count(), 0);
+
+        effect.render(Duration::from_millis(100), &mut frame);
+        let stars = frame.iter().count();
+        assert!(stars > 0);
+        assert!(frame.iter().all(|(_, color)| color == white));
+        assert_eq!(frame.background(), base);
+
+        // The same seed gives the same stars.
+        let mut other = Twinkle::new(base, white, 1);
+        other.rate = 100.0;
+        let mut other_frame = Frame::new();
+        other.render(Duration::ZERO, &mut other_frame);
+        other.render(Duration::from_millis(100), &mut other_frame);
+        assert_eq!(frame, other_frame);
+
+        effect.rate = 0.0;
+        effect.render(Duration::from_millis(600), &mut frame);
+        assert_eq!(frame.iter().count(), stars);
+        assert!(frame
+            .iter()
+            .all(|(_, color)| color == base.lerp(white, 0.5)));
+
+        effect.render(Duration::from_millis(1100), &mut frame);
+        assert_eq!(frame.iter().count(), 0);
+
+        // Rates that are not finite do not spawn forever.
+        effect.rate = f32::INFINITY;
+        effect.render(Duration::from_millis(1200), &mut frame);
+        assert!(frame.iter().count() > 0);
+        effect.rate = f32::NAN;
+        effect.render(Duration::from_millis(2300), &mut frame);
+        assert_eq!(frame.iter().count(), 0);
+    }
+}
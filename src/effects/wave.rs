@@ -0,0 +1,120 @@
+use super::phase;
+use super::Direction;
+use crate::ColorPercent;
+use crate::Effect;
+use crate::Frame;
+use crate::Hsv;
+use crate::KeyboardLayout;
+use std::time::Duration;
+
+/// A rainbow that moves across the keyboard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Wave {
+    /// The position of each key.
+    pub layout: KeyboardLayout,
+
+    /// The direction the wave moves in.
+    pub direction: Direction,
+
+    /// The distance between repeats of the same color, in key units.
+    pub wavelength: f32,
+
+    /// The time for the wave to move one wavelength.
+    pub period: Duration,
+
+    /// The saturation of the colors, from 0 to 1.
+    pub saturation: f32,
+
+    /// The brightness of the colors, from 0 to 1.
+    pub value: f32,
+}
+
+impl Wave {
+    /// Make a new wave on an ANSI layout, with a full rainbow across the main block every 2 seconds.
+    pub fn new(direction: Direction) -> Self {
+        Self {
+            layout: KeyboardLayout::ansi(),
+            direction,
+            wavelength: 15.0,
+            period: Duration::from_secs(2),
+            saturation: 1.0,
+            value: 1.0,
+        }
+    }
+
+    /// Get the hue at a position, in degrees.
+    fn hue(&self, position: f32, phase: f32) -> f32 {
+        let wavelength = if self.wavelength > 0.0 {
+            self.wavelength
+        } else {
+            1.0
+        };
+        ((position / wavelength) - phase) * 360.0
+    }
+}
+
+impl Effect for Wave {
+    fn render(&mut self, time: Duration, frame: &mut Frame) {
+        let phase = phase(time, self.period);
+        frame.fill(ColorPercent::from(Hsv::new(
+            self.hue(0.0, phase),
+            self.saturation,
+            self.value,
+        )));
+
+        let bounds = match self.layout.bounds() {
+            Some(bounds) => bounds,
+            None => return,
+        };
+        for (key, rect) in self.layout.iter() {
+            let position = self.direction.position(bounds, rect);
+            let color = ColorPercent::from(Hsv::new(
+                self.hue(position, phase),
+                self.saturation,
+                self.value,
+            ));
+            frame.set(key, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::KeyName;
+
+    #[test]
+    fn horizontal() {
+        let mut effect = Wave::new(Direction::Horizontal);
+        let mut frame = Frame::new();
+
+        effect.render(Duration::ZERO, &mut frame);
+        // Keys in the same column have the same color.
+        assert_eq!(frame.get(KeyName::ESC), frame.get(KeyName::TILDE));
+        assert_ne!(frame.get(KeyName::ESC), frame.get(KeyName::NUM_LOCK));
+
+        // After one period, the wave is back where it started.
+        let start = frame.clone();
+        effect.render(Duration::from_secs(2), &mut frame);
+        assert_eq!(frame, start);
+
+        // After half a period, each key has the color of the key half a wavelength before it.
+        effect.wavelength = 2.0;
+        effect.render(Duration::ZERO, &mut frame);
+        let one = frame.get(KeyName::ONE);
+        effect.render(Duration::from_secs(1), &mut frame);
+        assert_eq!(frame.get(KeyName::TWO), one);
+    }
+
+    #[test]
+    fn radial() {
+        let layout = KeyboardLayout::ansi();
+        let (x, y) = layout.get(KeyName::G).unwrap().center();
+        let mut effect = Wave::new(Direction::Radial { x, y });
+        let mut frame = Frame::new();
+
+        effect.render(Duration::ZERO, &mut frame);
+        assert_eq!(frame.get(KeyName::F), frame.get(KeyName::H));
+        assert_eq!(frame.get(KeyName::G), frame.background());
+    }
+}
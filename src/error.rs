@@ -0,0 +1,74 @@
+/// An error that may occur while using the sdk.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Error {
+    /// The sdk is already in use by another [`Sdk`](crate::Sdk) in this process.
+    LockHeld,
+
+    /// The sdk failed to initialize.
+    ///
+    /// This usually means that neither LGS nor LG HUB is running.
+    InitFailed,
+
+    /// An sdk function reported a failure.
+    CallFailed {
+        /// The name of the sdk function that failed.
+        function: &'static str,
+    },
+
+    /// A duration was too large to pass to the sdk, or was zero where that would mean "infinite".
+    DurationOutOfRange,
+
+    /// A string contained an interior NUL.
+    InteriorNul,
+
+    /// A list or buffer was too large to pass to the sdk.
+    LengthOutOfRange,
+
+    /// A config option default was not valid for the given options or range.
+    InvalidConfigDefault,
+
+    /// A zone was out of range for its device type.
+    InvalidZone {
+        /// The device type.
+        device: crate::DeviceType,
+
+        /// The zone.
+        zone: u32,
+    },
+
+    /// The library could not be loaded.
+    #[cfg(feature = "dynamic")]
+    Load(crate::sys::dynamic::LoadError),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LockHeld => "the sdk is already in use in this process".fmt(f),
+            Self::InitFailed => "failed to initialize the sdk".fmt(f),
+            Self::CallFailed { function } => write!(f, "call to \"{function}\" failed"),
+            Self::DurationOutOfRange => "duration out of range".fmt(f),
+            Self::InteriorNul => "string contains an interior NUL".fmt(f),
+            Self::LengthOutOfRange => "length out of range".fmt(f),
+            Self::InvalidConfigDefault => "invalid config option default".fmt(f),
+            Self::InvalidZone { device, zone } => write!(
+                f,
+                "zone {zone} is out of range for the {} device type",
+                device.name()
+            ),
+            #[cfg(feature = "dynamic")]
+            Self::Load(e) => write!(f, "failed to load the library: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "dynamic")]
+            Self::Load(e) => Some(e),
+            _ => None,
+        }
+    }
+}
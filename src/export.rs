@@ -0,0 +1,62 @@
+//! Render keyboard lighting to images, for previews in documentation and reviews.
+//!
+//! A [`KeyboardRenderer`] draws every key of a [`KeyboardLayout`](crate::KeyboardLayout) at its physical position,
+//! filled with its color from a [`Frame`](crate::Frame) or a [`KeyboardBitmap`](crate::KeyboardBitmap).
+//! Single frames are saved as PNG files with [`save_png`],
+//! and [`KeyboardRenderer::save_gif`] draws an [`Effect`](crate::Effect) over a range of time into an animated GIF.
+
+mod export_error;
+mod keyboard_renderer;
+
+pub use self::export_error::ExportError;
+pub use self::keyboard_renderer::KeyboardRenderer;
+pub use image::RgbaImage;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+
+/// Encode an image as a PNG.
+///
+/// # Errors
+/// Returns an error if the image could not be encoded or written.
+pub fn write_png<W>(image: &RgbaImage, out: W) -> Result<(), ExportError>
+where
+    W: Write,
+{
+    use image::ImageEncoder;
+
+    let encoder = image::codecs::png::PngEncoder::new(out);
+    encoder
+        .write_image(
+            image.as_raw(),
+            image.width(),
+            image.height(),
+            image::ExtendedColorType::Rgba8,
+        )
+        .map_err(ExportError::Encode)
+}
+
+/// Save an image to a PNG file.
+///
+/// # Errors
+/// Returns an error if the file could not be created, or the image could not be encoded or written.
+pub fn save_png<P>(image: &RgbaImage, path: P) -> Result<(), ExportError>
+where
+    P: AsRef<Path>,
+{
+    let mut out = create(path.as_ref())?;
+    write_png(image, &mut out)?;
+    out.flush()
+        .map_err(|error| ExportError::Encode(error.into()))
+}
+
+/// Create a file to export to.
+fn create(path: &Path) -> Result<BufWriter<File>, ExportError> {
+    File::create(path)
+        .map(BufWriter::new)
+        .map_err(|error| ExportError::Create {
+            path: path.into(),
+            error,
+        })
+}
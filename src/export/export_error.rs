@@ -0,0 +1,36 @@
+use std::path::PathBuf;
+
+/// An error that may occur while exporting an image.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ExportError {
+    /// The file could not be created.
+    Create {
+        /// The path of the file.
+        path: PathBuf,
+
+        /// The error.
+        error: std::io::Error,
+    },
+
+    /// The image could not be encoded or written.
+    Encode(image::ImageError),
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Create { path, .. } => write!(f, "failed to create \"{}\"", path.display()),
+            Self::Encode(_) => "failed to encode the image".fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Create { error, .. } => Some(error),
+            Self::Encode(error) => Some(error),
+        }
+    }
+}
@@ -0,0 +1,331 @@
+use super::create;
+use super::ExportError;
+use crate::ColorPercent;
+use crate::Effect;
+use crate::Frame;
+use crate::KeyName;
+use crate::KeyboardBitmap;
+use crate::KeyboardLayout;
+use crate::Rgb8;
+use image::codecs::gif::GifEncoder;
+use image::codecs::gif::Repeat;
+use image::Delay;
+use image::Rgba;
+use image::RgbaImage;
+use std::io::Write;
+use std::ops::Range;
+use std::path::Path;
+use std::time::Duration;
+
+/// The number of pixels in one key unit of a new renderer.
+const DEFAULT_KEY_SIZE: u32 = 48;
+
+/// The number of pixels between keys of a new renderer.
+const DEFAULT_KEY_GAP: u32 = 4;
+
+/// The number of pixels around the keyboard of a new renderer.
+const DEFAULT_PADDING: u32 = 16;
+
+/// The color behind the keys of a new renderer.
+const DEFAULT_BACKGROUND: Rgb8 = Rgb8::new(32, 32, 32);
+
+/// The color of the outline of every key, so keys that are off can still be seen.
+const KEY_OUTLINE: Rgb8 = Rgb8::new(72, 72, 72);
+
+/// Draws keyboard lighting onto images, using the physical position of every key.
+///
+/// Each key in the layout is a rectangle filled with its color and outlined so that unlit keys stay visible.
+/// Keys are drawn `key_size` pixels per key unit, with a gap between them and padding around the keyboard.
+#[derive(Debug, Clone)]
+pub struct KeyboardRenderer {
+    layout: KeyboardLayout,
+    key_size: u32,
+    key_gap: u32,
+    padding: u32,
+    background: Rgb8,
+}
+
+impl KeyboardRenderer {
+    /// Create a new renderer for a full-size ANSI keyboard.
+    pub fn new() -> Self {
+        Self {
+            layout: KeyboardLayout::ansi(),
+            key_size: DEFAULT_KEY_SIZE,
+            key_gap: DEFAULT_KEY_GAP,
+            padding: DEFAULT_PADDING,
+            background: DEFAULT_BACKGROUND,
+        }
+    }
+
+    /// Set the layout of the keyboard.
+    pub fn set_layout(&mut self, layout: KeyboardLayout) {
+        self.layout = layout;
+    }
+
+    /// Set the number of pixels in one key unit.
+    ///
+    /// A size of 0 is treated as 1.
+    pub fn set_key_size(&mut self, key_size: u32) {
+        self.key_size = key_size.max(1);
+    }
+
+    /// Set the number of pixels between keys.
+    ///
+    /// The gap is limited to one pixel less than a key unit.
+    /// Keys that are narrower than the gap are drawn one pixel wide where they fit in the image.
+    pub fn set_key_gap(&mut self, key_gap: u32) {
+        self.key_gap = key_gap;
+    }
+
+    /// Set the number of pixels around the keyboard.
+    pub fn set_padding(&mut self, padding: u32) {
+        self.padding = padding;
+    }
+
+    /// Set the color behind the keys.
+    pub fn set_background(&mut self, background: Rgb8) {
+        self.background = background;
+    }
+
+    /// Get the width and height of the images this renderer draws, in pixels.
+    pub fn image_size(&self) -> (u32, u32) {
+        let Some(bounds) = self.layout.bounds() else {
+            return (self.padding * 2, self.padding * 2);
+        };
+
+        let gap = self.gap();
+        let width = self.pixels(bounds.width).saturating_sub(gap);
+        let height = self.pixels(bounds.height).saturating_sub(gap);
+        (width + (self.padding * 2), height + (self.padding * 2))
+    }
+
+    /// Draw the colors of a frame.
+    pub fn render_frame(&self, frame: &Frame) -> RgbaImage {
+        self.render_with(|key| frame.get(key))
+    }
+
+    /// Draw the colors of a bitmap.
+    ///
+    /// Keys that are not part of a bitmap, like the G-keys, are drawn black.
+    pub fn render_bitmap(&self, bitmap: &KeyboardBitmap) -> RgbaImage {
+        self.render_with(|key| bitmap.get_key(key).unwrap_or(ColorPercent::BLACK))
+    }
+
+    /// Draw every key with the color from a function.
+    fn render_with<F>(&self, mut color: F) -> RgbaImage
+    where
+        F: FnMut(KeyName) -> ColorPercent,
+    {
+        let (width, height) = self.image_size();
+        let mut image = RgbaImage::from_pixel(width, height, rgba(self.background));
+        let Some(bounds) = self.layout.bounds() else {
+            return image;
+        };
+
+        let gap = self.gap();
+        for (key, rect) in self.layout.iter() {
+            let left = self.padding + self.pixels(rect.x - bounds.x);
+            let top = self.padding + self.pixels(rect.y - bounds.y);
+
+            // Keys narrower than the gap can start past the edge of the image.
+            if left >= width || top >= height {
+                continue;
+            }
+
+            let right = (self.padding + self.pixels(rect.x + rect.width - bounds.x))
+                .saturating_sub(gap)
+                .max(left + 1)
+                .min(width);
+            let bottom = (self.padding + self.pixels(rect.y + rect.height - bounds.y))
+                .saturating_sub(gap)
+                .max(top + 1)
+                .min(height);
+
+            let fill = rgba(color(key).to_rgb());
+            let outline = rgba(KEY_OUTLINE);
+            for y in top..bottom {
+                for x in left..right {
+                    let is_edge = x == left || x == right - 1 || y == top || y == bottom - 1;
+                    image.put_pixel(x, y, if is_edge { outline } else { fill });
+                }
+            }
+        }
+
+        image
+    }
+
+    /// Draw an effect over a range of time into an animated GIF that loops forever.
+    ///
+    /// A frame is drawn every `1 / frame_rate` seconds from the start of the range, stopping before its end,
+    /// and each frame starts with every key turned off.
+    /// At least one frame is drawn, even if the range is empty.
+    /// A frame rate of 0 is treated as 1.
+    /// GIF frame delays are stored in hundredths of a second, and many viewers slow down frame rates above 50.
+    ///
+    /// # Errors
+    /// Returns an error if the animation could not be encoded or written.
+    pub fn write_gif<E, W>(
+        &self,
+        effect: &mut E,
+        range: Range<Duration>,
+        frame_rate: u32,
+        out: W,
+    ) -> Result<(), ExportError>
+    where
+        E: Effect + ?Sized,
+        W: Write,
+    {
+        let frame_interval = Duration::from_secs(1) / frame_rate.max(1);
+        let delay = Delay::from_saturating_duration(frame_interval);
+
+        let mut encoder = GifEncoder::new(out);
+        encoder
+            .set_repeat(Repeat::Infinite)
+            .map_err(ExportError::Encode)?;
+
+        let mut time = range.start;
+        loop {
+            let mut frame = Frame::new();
+            effect.render(time, &mut frame);
+            let image = self.render_frame(&frame);
+            encoder
+                .encode_frame(image::Frame::from_parts(image, 0, 0, delay))
+                .map_err(ExportError::Encode)?;
+
+            time += frame_interval;
+            if time >= range.end {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Draw an effect over a range of time into an animated GIF file.
+    ///
+    /// See [`KeyboardRenderer::write_gif`] for how frames are drawn.
+    ///
+    /// # Errors
+    /// Returns an error if the file could not be created, or the animation could not be encoded or written.
+    pub fn save_gif<E, P>(
+        &self,
+        effect: &mut E,
+        range: Range<Duration>,
+        frame_rate: u32,
+        path: P,
+    ) -> Result<(), ExportError>
+    where
+        E: Effect + ?Sized,
+        P: AsRef<Path>,
+    {
+        let mut out = create(path.as_ref())?;
+        self.write_gif(effect, range, frame_rate, &mut out)?;
+        out.flush()
+            .map_err(|error| ExportError::Encode(error.into()))
+    }
+
+    /// Get the gap between keys, limited to one pixel less than a key unit.
+    fn gap(&self) -> u32 {
+        self.key_gap.min(self.key_size - 1)
+    }
+
+    /// Convert key units to pixels.
+    fn pixels(&self, units: f32) -> u32 {
+        (units * self.key_size as f32).round() as u32
+    }
+}
+
+impl Default for KeyboardRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Convert a color to an opaque pixel.
+fn rgba(color: Rgb8) -> Rgba<u8> {
+    Rgba([color.r, color.g, color.b, 255])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::KeyRect;
+    use image::AnimationDecoder;
+
+    #[test]
+    fn render() {
+        let renderer = KeyboardRenderer::new();
+        let mut frame = Frame::new_filled(ColorPercent::BLUE);
+        frame.set(KeyName::W, ColorPercent::RED);
+        let image = renderer.render_frame(&frame);
+        assert_eq!(image.dimensions(), renderer.image_size());
+
+        let layout = KeyboardLayout::ansi();
+        let bounds = layout.bounds().expect("layout is empty");
+        let pixel_at = |key| {
+            let (x, y) = layout.get(key).expect("key is missing").center();
+            let x = DEFAULT_PADDING as f32 + ((x - bounds.x) * DEFAULT_KEY_SIZE as f32);
+            let y = DEFAULT_PADDING as f32 + ((y - bounds.y) * DEFAULT_KEY_SIZE as f32);
+            *image.get_pixel(x as u32, y as u32)
+        };
+        assert_eq!(pixel_at(KeyName::W), Rgba([255, 0, 0, 255]));
+        assert_eq!(pixel_at(KeyName::SPACE), Rgba([0, 0, 255, 255]));
+        assert_eq!(*image.get_pixel(0, 0), rgba(DEFAULT_BACKGROUND));
+
+        let mut png = Vec::new();
+        crate::export::write_png(&image, &mut png).expect("failed to write png");
+        let decoded = image::load_from_memory_with_format(&png, image::ImageFormat::Png)
+            .expect("failed to decode png")
+            .to_rgba8();
+        assert_eq!(decoded, image);
+    }
+
+    #[test]
+    fn narrow_keys() {
+        let mut layout = KeyboardLayout::new();
+        layout.insert(KeyName::ESC, KeyRect::new(0.0, 0.0, 1.0, 1.0));
+        layout.insert(KeyName::F1, KeyRect::new(1.0, 0.0, 0.05, 1.0));
+
+        let mut renderer = KeyboardRenderer::new();
+        renderer.set_layout(layout);
+        renderer.set_padding(0);
+        let image = renderer.render_frame(&Frame::new_filled(ColorPercent::RED));
+        assert_eq!(image.dimensions(), renderer.image_size());
+        assert_eq!(*image.get_pixel(20, 20), Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn gif() {
+        let mut renderer = KeyboardRenderer::new();
+        renderer.set_key_size(8);
+        renderer.set_key_gap(1);
+
+        let mut effect = |time: Duration, frame: &mut Frame| {
+            if time >= Duration::from_millis(500) {
+                frame.fill(ColorPercent::WHITE);
+            }
+        };
+        let mut gif = Vec::new();
+        renderer
+            .write_gif(
+                &mut effect,
+                Duration::ZERO..Duration::from_secs(1),
+                10,
+                &mut gif,
+            )
+            .expect("failed to write gif");
+
+        let decoder = image::codecs::gif::GifDecoder::new(std::io::Cursor::new(gif))
+            .expect("failed to decode gif");
+        let frames = decoder
+            .into_frames()
+            .collect_frames()
+            .expect("failed to decode frames");
+        assert_eq!(frames.len(), 10);
+        assert_eq!(
+            Duration::from(frames[0].delay()),
+            Duration::from_millis(100)
+        );
+        assert_eq!(frames[0].buffer().dimensions(), renderer.image_size());
+    }
+}
@@ -0,0 +1,115 @@
+use crate::ColorPercent;
+use crate::KeyName;
+use crate::KeyboardBitmap;
+use std::collections::HashMap;
+
+/// The colors of every key at one point in time.
+///
+/// Keys that were not set explicitly use the background color.
+/// The background color is also what devices without per-key lighting show.
+/// Send a frame to the devices with [`Sdk::set_lighting_from_frame`](crate::Sdk::set_lighting_from_frame).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    background: ColorPercent,
+    keys: HashMap<KeyName, ColorPercent>,
+}
+
+impl Frame {
+    /// Create a new frame with every key turned off.
+    pub fn new() -> Self {
+        Self::new_filled(ColorPercent { r: 0, g: 0, b: 0 })
+    }
+
+    /// Create a new frame with every key set to the given color.
+    pub fn new_filled(color: ColorPercent) -> Self {
+        Self {
+            background: color,
+            keys: HashMap::new(),
+        }
+    }
+
+    /// Get the background color.
+    pub fn background(&self) -> ColorPercent {
+        self.background
+    }
+
+    /// Get the color of a key.
+    pub fn get(&self, key: KeyName) -> ColorPercent {
+        self.keys.get(&key).copied().unwrap_or(self.background)
+    }
+
+    /// Set the color of a key.
+    pub fn set(&mut self, key: KeyName, color: ColorPercent) {
+        self.keys.insert(key, color);
+    }
+
+    /// Reset a key to the background color.
+    pub fn reset(&mut self, key: KeyName) {
+        self.keys.remove(&key);
+    }
+
+    /// Set every key, and the background, to the given color.
+    pub fn fill(&mut self, color: ColorPercent) {
+        self.background = color;
+        self.keys.clear();
+    }
+
+    /// Turn off every key.
+    pub fn clear(&mut self) {
+        self.fill(ColorPercent { r: 0, g: 0, b: 0 });
+    }
+
+    /// Iterate over the keys that were set explicitly, in [`KeyName::iter`] order.
+    pub fn iter(&self) -> impl Iterator<Item = (KeyName, ColorPercent)> + '_ {
+        KeyName::iter().filter_map(|key| Some((key, *self.keys.get(&key)?)))
+    }
+
+    /// Convert this frame into a bitmap.
+    ///
+    /// Keys that are not part of the bitmap are left out.
+    pub fn to_bitmap(&self) -> KeyboardBitmap {
+        let mut bitmap = KeyboardBitmap::new_filled(self.background);
+        for (key, color) in self.keys.iter() {
+            bitmap.set_key(*key, *color);
+        }
+        bitmap
+    }
+}
+
+impl Default for Frame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn set_get() {
+        let red = ColorPercent::new_percent(100, 0, 0).unwrap();
+        let blue = ColorPercent::new_percent(0, 0, 100).unwrap();
+
+        let mut frame = Frame::new_filled(red);
+        frame.set(KeyName::W, blue);
+        frame.set(KeyName::G_1, blue);
+        assert_eq!(frame.get(KeyName::W), blue);
+        assert_eq!(frame.get(KeyName::A), red);
+        assert_eq!(
+            frame.iter().collect::<Vec<_>>(),
+            [(KeyName::W, blue), (KeyName::G_1, blue)]
+        );
+
+        let bitmap = frame.to_bitmap();
+        assert_eq!(bitmap.get_key(KeyName::W), Some(blue));
+        assert_eq!(bitmap.get_key(KeyName::A), Some(red));
+        assert_eq!(bitmap.get(1, 0), Some(red));
+
+        frame.reset(KeyName::W);
+        assert_eq!(frame.get(KeyName::W), red);
+
+        frame.clear();
+        assert_eq!(frame, Frame::default());
+    }
+}
@@ -0,0 +1,118 @@
+use crate::hsv::hue;
+use crate::ColorPercent;
+use crate::Hsv;
+use crate::Rgb8;
+
+/// A color as a hue, saturation and lightness.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Hsl {
+    /// The hue in degrees, from 0 up to 360.
+    pub hue: f32,
+
+    /// The saturation, from 0 to 1.
+    pub saturation: f32,
+
+    /// The lightness, from 0 to 1.
+    pub lightness: f32,
+}
+
+impl Hsl {
+    /// Create a new color.
+    ///
+    /// The hue is wrapped into the range 0 up to 360, and the saturation and lightness are clamped from 0 to 1.
+    pub fn new(hue: f32, saturation: f32, lightness: f32) -> Self {
+        Self {
+            hue: hue.rem_euclid(360.0),
+            saturation: saturation.clamp(0.0, 1.0),
+            lightness: lightness.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Get the channels as values from 0 to 1.
+    pub(crate) fn to_unit(self) -> [f32; 3] {
+        let saturation = self.saturation.clamp(0.0, 1.0);
+        let lightness = self.lightness.clamp(0.0, 1.0);
+
+        // HSL is HSV with the value and saturation measured differently.
+        let value = lightness + (saturation * lightness.min(1.0 - lightness));
+        let saturation = if value > 0.0 {
+            2.0 * (1.0 - (lightness / value))
+        } else {
+            0.0
+        };
+
+        Hsv::new(self.hue, saturation, value).to_unit()
+    }
+
+    /// Make a color from channels with values from 0 to 1.
+    pub(crate) fn from_unit([r, g, b]: [f32; 3]) -> Self {
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+
+        let lightness = (max + min) / 2.0;
+        let saturation = if lightness > 0.0 && lightness < 1.0 {
+            chroma / (1.0 - ((2.0 * lightness) - 1.0).abs())
+        } else {
+            0.0
+        };
+
+        Self::new(hue(r, g, b, max, chroma), saturation, lightness)
+    }
+}
+
+impl From<Rgb8> for Hsl {
+    fn from(color: Rgb8) -> Self {
+        Self::from_unit(color.to_unit())
+    }
+}
+
+impl From<Hsl> for Rgb8 {
+    fn from(color: Hsl) -> Self {
+        Self::from_unit(color.to_unit())
+    }
+}
+
+impl From<ColorPercent> for Hsl {
+    fn from(color: ColorPercent) -> Self {
+        Self::from_unit(color.to_unit())
+    }
+}
+
+impl From<Hsl> for ColorPercent {
+    fn from(color: Hsl) -> Self {
+        Self::from_unit(color.to_unit())
+    }
+}
+
+impl From<Hsv> for Hsl {
+    fn from(color: Hsv) -> Self {
+        Self::from_unit(color.to_unit())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Rgb8::from(Hsl::new(0.0, 1.0, 0.5)), Rgb8::new(255, 0, 0));
+        assert_eq!(Rgb8::from(Hsl::new(120.0, 1.0, 0.25)), Rgb8::new(0, 128, 0));
+        assert_eq!(
+            Rgb8::from(Hsl::new(0.0, 0.0, 1.0)),
+            Rgb8::new(255, 255, 255)
+        );
+        assert_eq!(Hsl::from(Rgb8::new(255, 0, 0)), Hsl::new(0.0, 1.0, 0.5));
+
+        for color in [
+            Rgb8::new(0x66, 0x33, 0x99),
+            Rgb8::new(0xFF, 0xA5, 0x00),
+            Rgb8::new(0x12, 0x34, 0x56),
+            Rgb8::new(0x80, 0x80, 0x80),
+        ] {
+            assert_eq!(Rgb8::from(Hsl::from(color)), color);
+            assert_eq!(Rgb8::from(Hsv::from(Hsl::from(color))), color);
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use crate::ColorPercent;
+use crate::Hsl;
+use crate::Rgb8;
+
+/// A color as a hue, saturation and value.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Hsv {
+    /// The hue in degrees, from 0 up to 360.
+    pub hue: f32,
+
+    /// The saturation, from 0 to 1.
+    pub saturation: f32,
+
+    /// The value, from 0 to 1.
+    pub value: f32,
+}
+
+impl Hsv {
+    /// Create a new color.
+    ///
+    /// The hue is wrapped into the range 0 up to 360, and the saturation and value are clamped from 0 to 1.
+    pub fn new(hue: f32, saturation: f32, value: f32) -> Self {
+        Self {
+            hue: hue.rem_euclid(360.0),
+            saturation: saturation.clamp(0.0, 1.0),
+            value: value.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Get the channels as values from 0 to 1.
+    pub(crate) fn to_unit(self) -> [f32; 3] {
+        let hue = self.hue.rem_euclid(360.0) / 60.0;
+        let saturation = self.saturation.clamp(0.0, 1.0);
+        let value = self.value.clamp(0.0, 1.0);
+
+        let chroma = value * saturation;
+        let x = chroma * (1.0 - ((hue % 2.0) - 1.0).abs());
+        let (r, g, b) = match hue as u8 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = value - chroma;
+
+        [r + m, g + m, b + m]
+    }
+
+    /// Make a color from channels with values from 0 to 1.
+    pub(crate) fn from_unit([r, g, b]: [f32; 3]) -> Self {
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+
+        let saturation = if max > 0.0 { chroma / max } else { 0.0 };
+
+        Self::new(hue(r, g, b, max, chroma), saturation, max)
+    }
+}
+
+/// Get the hue in degrees of channels with values from 0 to 1.
+///
+/// Grays have a hue of 0.
+pub(crate) fn hue(r: f32, g: f32, b: f32, max: f32, chroma: f32) -> f32 {
+    if chroma <= 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / chroma)
+    } else if max == g {
+        60.0 * (((b - r) / chroma) + 2.0)
+    } else {
+        60.0 * (((r - g) / chroma) + 4.0)
+    }
+}
+
+impl From<Rgb8> for Hsv {
+    fn from(color: Rgb8) -> Self {
+        Self::from_unit(color.to_unit())
+    }
+}
+
+impl From<Hsv> for Rgb8 {
+    fn from(color: Hsv) -> Self {
+        Self::from_unit(color.to_unit())
+    }
+}
+
+impl From<ColorPercent> for Hsv {
+    fn from(color: ColorPercent) -> Self {
+        Self::from_unit(color.to_unit())
+    }
+}
+
+impl From<Hsv> for ColorPercent {
+    fn from(color: Hsv) -> Self {
+        Self::from_unit(color.to_unit())
+    }
+}
+
+impl From<Hsl> for Hsv {
+    fn from(color: Hsl) -> Self {
+        Self::from_unit(color.to_unit())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        let red = ColorPercent::new_percent(100, 0, 0).unwrap();
+        let blue = ColorPercent::new_percent(0, 0, 100).unwrap();
+
+        assert_eq!(ColorPercent::from(Hsv::new(0.0, 1.0, 1.0)), red);
+        assert_eq!(ColorPercent::from(Hsv::new(240.0, 1.0, 1.0)), blue);
+        assert_eq!(ColorPercent::from(Hsv::new(-120.0, 1.0, 1.0)), blue);
+        assert_eq!(
+            ColorPercent::from(Hsv::new(60.0, 1.0, 0.5)),
+            ColorPercent::new_percent(50, 50, 0).unwrap()
+        );
+
+        assert_eq!(Hsv::from(blue), Hsv::new(240.0, 1.0, 1.0));
+        assert_eq!(Hsv::from(Rgb8::new(0, 0, 0)), Hsv::new(0.0, 0.0, 0.0));
+        for color in [
+            Rgb8::new(0x66, 0x33, 0x99),
+            Rgb8::new(0xFF, 0xA5, 0x00),
+            Rgb8::new(0x12, 0x34, 0x56),
+            Rgb8::new(0x80, 0x80, 0x80),
+        ] {
+            assert_eq!(Rgb8::from(Hsv::from(color)), color);
+        }
+    }
+}
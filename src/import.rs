@@ -0,0 +1,17 @@
+//! Turn images into keyboard lighting.
+//!
+//! An [`ImageImporter`] loads PNG, JPEG and GIF files and resamples them to the
+//! [`BITMAP_WIDTH`](crate::BITMAP_WIDTH) by [`BITMAP_HEIGHT`](crate::BITMAP_HEIGHT) grid of a [`KeyboardBitmap`](crate::KeyboardBitmap),
+//! where each cell is the color of one key.
+//! Single images can be sent with [`Sdk::set_lighting_from_bitmap`](crate::Sdk::set_lighting_from_bitmap).
+//! Animated GIFs become a [`BitmapAnimation`], an [`Effect`](crate::Effect) that an [`Animator`](crate::Animator) plays through an [`Sdk`](crate::Sdk).
+
+mod bitmap_animation;
+mod image_importer;
+mod import_error;
+
+pub use self::bitmap_animation::BitmapAnimation;
+pub use self::image_importer::AspectMode;
+pub use self::image_importer::ImageImporter;
+pub use self::image_importer::ResizeFilter;
+pub use self::import_error::ImportError;
@@ -0,0 +1,112 @@
+use crate::Effect;
+use crate::Frame;
+use crate::KeyboardBitmap;
+use crate::BITMAP_KEY_POSITIONS;
+use std::time::Duration;
+
+/// A sequence of bitmaps, each shown for a time.
+///
+/// As an [`Effect`], the animation loops forever and draws the bitmap of the current frame onto the keys in it.
+/// Keys that are not part of a bitmap, like the G-keys, are left alone.
+#[derive(Debug, Clone, Default)]
+pub struct BitmapAnimation {
+    frames: Vec<(KeyboardBitmap, Duration)>,
+    duration: Duration,
+}
+
+impl BitmapAnimation {
+    /// Make an empty animation.
+    pub fn new() -> Self {
+        Self {
+            frames: Vec::new(),
+            duration: Duration::ZERO,
+        }
+    }
+
+    /// Add a frame to the end of the animation, shown for `delay`.
+    pub fn push(&mut self, bitmap: KeyboardBitmap, delay: Duration) {
+        self.frames.push((bitmap, delay));
+        self.duration += delay;
+    }
+
+    /// Get the frames of the animation and how long each is shown.
+    pub fn frames(&self) -> &[(KeyboardBitmap, Duration)] {
+        &self.frames
+    }
+
+    /// Get the time it takes to play every frame once.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Get the frame shown at a time since the animation started, looping forever.
+    ///
+    /// # Returns
+    /// Returns `None` if the animation has no frames.
+    /// If every frame has no delay, the first frame is always shown.
+    pub fn frame_at(&self, time: Duration) -> Option<&KeyboardBitmap> {
+        let (first, _) = self.frames.first()?;
+        if self.duration.is_zero() {
+            return Some(first);
+        }
+
+        let mut time = Duration::from_nanos(
+            (time.as_nanos() % self.duration.as_nanos())
+                .try_into()
+                .expect("time within the animation fits"),
+        );
+        for (bitmap, delay) in self.frames.iter() {
+            if time < *delay {
+                return Some(bitmap);
+            }
+            time -= *delay;
+        }
+
+        Some(first)
+    }
+}
+
+impl Effect for BitmapAnimation {
+    fn render(&mut self, time: Duration, frame: &mut Frame) {
+        let Some(bitmap) = self.frame_at(time) else {
+            return;
+        };
+
+        for (key, x, y) in BITMAP_KEY_POSITIONS.iter().copied() {
+            let color = bitmap.get(x, y).expect("key position in bounds");
+            frame.set(key, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ColorPercent;
+    use crate::KeyName;
+
+    #[test]
+    fn frame_at() {
+        let mut animation = BitmapAnimation::new();
+        assert!(animation.frame_at(Duration::ZERO).is_none());
+
+        animation.push(
+            KeyboardBitmap::new_filled(ColorPercent::RED),
+            Duration::from_millis(100),
+        );
+        animation.push(
+            KeyboardBitmap::new_filled(ColorPercent::BLUE),
+            Duration::from_millis(50),
+        );
+        assert_eq!(animation.duration(), Duration::from_millis(150));
+
+        let mut frame = Frame::new();
+        animation.render(Duration::from_millis(120), &mut frame);
+        assert_eq!(frame.get(KeyName::Q), ColorPercent::BLUE);
+        assert_eq!(frame.get(KeyName::G_1), ColorPercent::BLACK);
+
+        // The animation loops.
+        animation.render(Duration::from_millis(170), &mut frame);
+        assert_eq!(frame.get(KeyName::Q), ColorPercent::RED);
+    }
+}
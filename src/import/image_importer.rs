@@ -0,0 +1,386 @@
+use super::BitmapAnimation;
+use super::ImportError;
+use crate::ColorPercent;
+use crate::KeyboardBitmap;
+use crate::BITMAP_HEIGHT;
+use crate::BITMAP_WIDTH;
+use image::codecs::gif::GifDecoder;
+use image::imageops::FilterType;
+use image::AnimationDecoder;
+use image::ImageFormat;
+use image::Rgb;
+use image::RgbImage;
+use image::Rgba;
+use image::RgbaImage;
+use std::io::Cursor;
+use std::path::Path;
+use std::time::Duration;
+
+/// How long GIF frames without a delay are shown, like most viewers do.
+const DEFAULT_GIF_DELAY: Duration = Duration::from_millis(100);
+
+/// The filter used to resample images to the bitmap grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ResizeFilter {
+    /// Use the nearest pixel, keeping hard edges.
+    Nearest,
+
+    /// Blend nearby pixels linearly.
+    #[default]
+    Triangle,
+
+    /// Blend nearby pixels with a Catmull-Rom spline, which is sharper than [`ResizeFilter::Triangle`].
+    CatmullRom,
+
+    /// Blend nearby pixels with a Gaussian, which is softer than [`ResizeFilter::Triangle`].
+    Gaussian,
+
+    /// Blend nearby pixels with a Lanczos window, which is the sharpest of the smooth filters.
+    Lanczos3,
+}
+
+impl ResizeFilter {
+    fn filter_type(self) -> FilterType {
+        match self {
+            Self::Nearest => FilterType::Nearest,
+            Self::Triangle => FilterType::Triangle,
+            Self::CatmullRom => FilterType::CatmullRom,
+            Self::Gaussian => FilterType::Gaussian,
+            Self::Lanczos3 => FilterType::Lanczos3,
+        }
+    }
+}
+
+/// How images that do not have the shape of the bitmap grid are fitted to it.
+///
+/// Every cell of the grid is treated as square.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AspectMode {
+    /// Stretch the image to cover the grid, ignoring its aspect ratio.
+    Stretch,
+
+    /// Scale the image to fit inside the grid, centered, and fill the rest with the background color.
+    Fit,
+
+    /// Scale the image to cover the grid, centered, and crop the parts that do not fit.
+    #[default]
+    Fill,
+}
+
+/// Loads images and resamples them to the grid of a [`KeyboardBitmap`].
+///
+/// PNG, JPEG and GIF files are supported, and the format is detected from the file contents.
+/// Transparent pixels are blended with the background color before resampling.
+#[derive(Debug, Clone)]
+pub struct ImageImporter {
+    filter: ResizeFilter,
+    aspect_mode: AspectMode,
+    background: ColorPercent,
+}
+
+impl ImageImporter {
+    /// Create a new importer that uses [`ResizeFilter::Triangle`], [`AspectMode::Fill`] and a black background.
+    pub fn new() -> Self {
+        Self {
+            filter: ResizeFilter::default(),
+            aspect_mode: AspectMode::default(),
+            background: ColorPercent::BLACK,
+        }
+    }
+
+    /// Set the filter used to resample images.
+    pub fn set_filter(&mut self, filter: ResizeFilter) {
+        self.filter = filter;
+    }
+
+    /// Set how images are fitted to the bitmap grid.
+    pub fn set_aspect_mode(&mut self, aspect_mode: AspectMode) {
+        self.aspect_mode = aspect_mode;
+    }
+
+    /// Set the color behind transparent pixels and around images fitted with [`AspectMode::Fit`].
+    pub fn set_background(&mut self, background: ColorPercent) {
+        self.background = background;
+    }
+
+    /// Load an image file as a bitmap.
+    ///
+    /// Only the first frame of an animated GIF is used.
+    ///
+    /// # Errors
+    /// Returns an error if the file could not be read or decoded.
+    pub fn load_bitmap<P>(&self, path: P) -> Result<KeyboardBitmap, ImportError>
+    where
+        P: AsRef<Path>,
+    {
+        self.decode_bitmap(&read(path.as_ref())?)
+    }
+
+    /// Decode the contents of an image file as a bitmap.
+    ///
+    /// Only the first frame of an animated GIF is used.
+    ///
+    /// # Errors
+    /// Returns an error if the image could not be decoded.
+    pub fn decode_bitmap(&self, data: &[u8]) -> Result<KeyboardBitmap, ImportError> {
+        let image = image::load_from_memory(data).map_err(ImportError::Decode)?;
+        Ok(self.resample(&image.to_rgba8()))
+    }
+
+    /// Load an image file as an animation.
+    ///
+    /// Every frame of an animated GIF becomes a frame of the animation, shown for the frame's delay.
+    /// Frames without a delay are shown for 100 milliseconds.
+    /// Other images become an animation with one frame and no delay.
+    ///
+    /// # Errors
+    /// Returns an error if the file could not be read or decoded.
+    pub fn load_animation<P>(&self, path: P) -> Result<BitmapAnimation, ImportError>
+    where
+        P: AsRef<Path>,
+    {
+        self.decode_animation(&read(path.as_ref())?)
+    }
+
+    /// Decode the contents of an image file as an animation.
+    ///
+    /// See [`ImageImporter::load_animation`] for how frames are timed.
+    ///
+    /// # Errors
+    /// Returns an error if the image could not be decoded.
+    pub fn decode_animation(&self, data: &[u8]) -> Result<BitmapAnimation, ImportError> {
+        let mut animation = BitmapAnimation::new();
+        if image::guess_format(data).map_err(ImportError::Decode)? != ImageFormat::Gif {
+            animation.push(self.decode_bitmap(data)?, Duration::ZERO);
+            return Ok(animation);
+        }
+
+        let frames = GifDecoder::new(Cursor::new(data))
+            .and_then(|decoder| decoder.into_frames().collect_frames())
+            .map_err(ImportError::Decode)?;
+        for frame in frames {
+            let delay = match Duration::from(frame.delay()) {
+                Duration::ZERO => DEFAULT_GIF_DELAY,
+                delay => delay,
+            };
+            animation.push(self.resample(frame.buffer()), delay);
+        }
+
+        Ok(animation)
+    }
+
+    /// Resample an image to the bitmap grid.
+    fn resample(&self, image: &RgbaImage) -> KeyboardBitmap {
+        let mut bitmap = KeyboardBitmap::new_filled(self.background);
+        let (width, height) = image.dimensions();
+        if width == 0 || height == 0 {
+            return bitmap;
+        }
+
+        // Blend transparent pixels first, so their hidden colors do not bleed into their neighbors.
+        let background = self.background.to_rgb();
+        let image = RgbImage::from_fn(width, height, |x, y| {
+            let Rgba([r, g, b, a]) = *image.get_pixel(x, y);
+            let blend = |value: u8, background: u8| {
+                let alpha = u16::from(a);
+                let value = (u16::from(value) * alpha) + (u16::from(background) * (255 - alpha));
+                ((value + 127) / 255) as u8
+            };
+            Rgb([
+                blend(r, background.r),
+                blend(g, background.g),
+                blend(b, background.b),
+            ])
+        });
+
+        let grid_width = BITMAP_WIDTH as u32;
+        let grid_height = BITMAP_HEIGHT as u32;
+        let filter = self.filter.filter_type();
+        let (resized, left, top) = match self.aspect_mode {
+            AspectMode::Stretch => (
+                image::imageops::resize(&image, grid_width, grid_height, filter),
+                0,
+                0,
+            ),
+            AspectMode::Fit => {
+                let scale = f64::min(
+                    f64::from(grid_width) / f64::from(width),
+                    f64::from(grid_height) / f64::from(height),
+                );
+                let fit_width = ((f64::from(width) * scale).round() as u32).clamp(1, grid_width);
+                let fit_height = ((f64::from(height) * scale).round() as u32).clamp(1, grid_height);
+                (
+                    image::imageops::resize(&image, fit_width, fit_height, filter),
+                    (grid_width - fit_width) / 2,
+                    (grid_height - fit_height) / 2,
+                )
+            }
+            AspectMode::Fill => {
+                // Crop the image to the shape of the grid, keeping its center.
+                let (crop_width, crop_height) = if u64::from(width) * u64::from(grid_height)
+                    > u64::from(height) * u64::from(grid_width)
+                {
+                    let crop_width = (u64::from(height) * u64::from(grid_width)
+                        / u64::from(grid_height))
+                    .max(1) as u32;
+                    (crop_width, height)
+                } else {
+                    let crop_height = (u64::from(width) * u64::from(grid_height)
+                        / u64::from(grid_width))
+                    .max(1) as u32;
+                    (width, crop_height)
+                };
+                let cropped = image::imageops::crop_imm(
+                    &image,
+                    (width - crop_width) / 2,
+                    (height - crop_height) / 2,
+                    crop_width,
+                    crop_height,
+                )
+                .to_image();
+                (
+                    image::imageops::resize(&cropped, grid_width, grid_height, filter),
+                    0,
+                    0,
+                )
+            }
+        };
+
+        for (x, y, Rgb([r, g, b])) in resized.enumerate_pixels() {
+            bitmap.set(
+                (left + x) as usize,
+                (top + y) as usize,
+                ColorPercent::new_rgb(*r, *g, *b),
+            );
+        }
+
+        bitmap
+    }
+}
+
+impl Default for ImageImporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Read an image file.
+fn read(path: &Path) -> Result<Vec<u8>, ImportError> {
+    std::fs::read(path).map_err(|error| ImportError::Io {
+        path: path.into(),
+        error,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use image::codecs::gif::GifEncoder;
+    use image::Delay;
+    use image::ImageEncoder;
+
+    /// Make an image with a red left half and a blue right half.
+    fn halves(width: u32, height: u32) -> RgbaImage {
+        RgbaImage::from_fn(width, height, |x, _| {
+            if x < width / 2 {
+                Rgba([255, 0, 0, 255])
+            } else {
+                Rgba([0, 0, 255, 255])
+            }
+        })
+    }
+
+    fn png(image: &RgbaImage) -> Vec<u8> {
+        let mut data = Vec::new();
+        image::codecs::png::PngEncoder::new(&mut data)
+            .write_image(
+                image.as_raw(),
+                image.width(),
+                image.height(),
+                image::ExtendedColorType::Rgba8,
+            )
+            .expect("failed to encode png");
+        data
+    }
+
+    #[test]
+    fn aspect_modes() {
+        let mut importer = ImageImporter::new();
+        importer.set_filter(ResizeFilter::Nearest);
+        importer.set_background(ColorPercent::GREEN);
+
+        let bitmap = importer
+            .decode_bitmap(&png(&halves(42, 12)))
+            .expect("failed to import");
+        assert_eq!(bitmap.get(0, 0), Some(ColorPercent::RED));
+        assert_eq!(bitmap.get(20, 5), Some(ColorPercent::BLUE));
+
+        // A square image fits in the middle of the grid.
+        importer.set_aspect_mode(AspectMode::Fit);
+        let bitmap = importer
+            .decode_bitmap(&png(&halves(12, 12)))
+            .expect("failed to import");
+        assert_eq!(bitmap.get(0, 0), Some(ColorPercent::GREEN));
+        assert_eq!(bitmap.get(8, 3), Some(ColorPercent::RED));
+        assert_eq!(bitmap.get(12, 3), Some(ColorPercent::BLUE));
+        assert_eq!(bitmap.get(20, 5), Some(ColorPercent::GREEN));
+
+        // A square image loses its top and bottom.
+        importer.set_aspect_mode(AspectMode::Fill);
+        let bitmap = importer
+            .decode_bitmap(&png(&halves(12, 12)))
+            .expect("failed to import");
+        assert_eq!(bitmap.get(0, 0), Some(ColorPercent::RED));
+        assert_eq!(bitmap.get(20, 5), Some(ColorPercent::BLUE));
+
+        // A square image is stretched over the whole grid.
+        importer.set_aspect_mode(AspectMode::Stretch);
+        let mut image = halves(12, 12);
+        for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            image.put_pixel(x, y, Rgba([0, 0, 0, 0]));
+        }
+        let bitmap = importer
+            .decode_bitmap(&png(&image))
+            .expect("failed to import");
+        assert_eq!(bitmap.get(0, 0), Some(ColorPercent::GREEN));
+        assert_eq!(bitmap.get(5, 3), Some(ColorPercent::RED));
+        assert_eq!(bitmap.get(15, 3), Some(ColorPercent::BLUE));
+    }
+
+    #[test]
+    fn animation() {
+        let mut data = Vec::new();
+        {
+            let mut encoder = GifEncoder::new(&mut data);
+            for (color, millis) in [([255, 0, 0, 255], 100), ([0, 0, 255, 255], 0)] {
+                let image = RgbaImage::from_pixel(21, 6, Rgba(color));
+                let delay = Delay::from_numer_denom_ms(millis, 1);
+                encoder
+                    .encode_frame(image::Frame::from_parts(image, 0, 0, delay))
+                    .expect("failed to encode gif");
+            }
+        }
+
+        let importer = ImageImporter::new();
+        let animation = importer
+            .decode_animation(&data)
+            .expect("failed to import animation");
+        assert_eq!(animation.frames().len(), 2);
+        assert_eq!(animation.duration(), Duration::from_millis(200));
+        let frame = animation
+            .frame_at(Duration::from_millis(150))
+            .expect("animation is empty");
+        assert_eq!(frame.get(10, 3), Some(ColorPercent::BLUE));
+
+        let animation = importer
+            .decode_animation(&png(&halves(21, 6)))
+            .expect("failed to import image");
+        assert_eq!(animation.frames().len(), 1);
+        assert_eq!(animation.duration(), Duration::ZERO);
+
+        assert!(matches!(
+            importer.decode_bitmap(b"not an image"),
+            Err(ImportError::Decode(_))
+        ));
+    }
+}
@@ -0,0 +1,36 @@
+use std::path::PathBuf;
+
+/// An error that may occur while importing an image.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ImportError {
+    /// The file could not be read.
+    Io {
+        /// The path of the file.
+        path: PathBuf,
+
+        /// The error.
+        error: std::io::Error,
+    },
+
+    /// The image could not be decoded.
+    Decode(image::ImageError),
+}
+
+impl std::fmt::Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { path, .. } => write!(f, "failed to read \"{}\"", path.display()),
+            Self::Decode(_) => "failed to decode the image".fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { error, .. } => Some(error),
+            Self::Decode(error) => Some(error),
+        }
+    }
+}
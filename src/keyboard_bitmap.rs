@@ -0,0 +1,386 @@
+use crate::color_percent::byte_to_percent;
+use crate::color_percent::percent_to_byte;
+use crate::sys;
+use crate::ColorPercent;
+use crate::KeyName;
+
+/// The width of a keyboard bitmap, in keys.
+pub const BITMAP_WIDTH: usize = sys::LOGI_LED_BITMAP_WIDTH as usize;
+
+/// The height of a keyboard bitmap, in keys.
+pub const BITMAP_HEIGHT: usize = sys::LOGI_LED_BITMAP_HEIGHT as usize;
+
+const BYTES_PER_KEY: usize = sys::LOGI_LED_BITMAP_BYTES_PER_KEY as usize;
+const BITMAP_SIZE: usize = sys::LOGI_LED_BITMAP_SIZE as usize;
+
+/// The bitmap cell of every key that can be addressed through a bitmap, as `(key, x, y)`.
+///
+/// `x` is the column and `y` is the row.
+/// G-keys and the logo are not part of the bitmap.
+/// Cells that are not listed here do not correspond to any key.
+pub const BITMAP_KEY_POSITIONS: &[(KeyName, usize, usize)] = &[
+    // Row 0
+    (KeyName::ESC, 0, 0),
+    (KeyName::F1, 1, 0),
+    (KeyName::F2, 2, 0),
+    (KeyName::F3, 3, 0),
+    (KeyName::F4, 4, 0),
+    (KeyName::F5, 5, 0),
+    (KeyName::F6, 6, 0),
+    (KeyName::F7, 7, 0),
+    (KeyName::F8, 8, 0),
+    (KeyName::F9, 9, 0),
+    (KeyName::F10, 10, 0),
+    (KeyName::F11, 11, 0),
+    (KeyName::F12, 12, 0),
+    (KeyName::PRINT_SCREEN, 13, 0),
+    (KeyName::SCROLL_LOCK, 14, 0),
+    (KeyName::PAUSE_BREAK, 15, 0),
+    // Row 1
+    (KeyName::TILDE, 0, 1),
+    (KeyName::ONE, 1, 1),
+    (KeyName::TWO, 2, 1),
+    (KeyName::THREE, 3, 1),
+    (KeyName::FOUR, 4, 1),
+    (KeyName::FIVE, 5, 1),
+    (KeyName::SIX, 6, 1),
+    (KeyName::SEVEN, 7, 1),
+    (KeyName::EIGHT, 8, 1),
+    (KeyName::NINE, 9, 1),
+    (KeyName::ZERO, 10, 1),
+    (KeyName::MINUS, 11, 1),
+    (KeyName::EQUALS, 12, 1),
+    (KeyName::BACKSPACE, 13, 1),
+    (KeyName::INSERT, 14, 1),
+    (KeyName::HOME, 15, 1),
+    (KeyName::PAGE_UP, 16, 1),
+    (KeyName::NUM_LOCK, 17, 1),
+    (KeyName::NUM_SLASH, 18, 1),
+    (KeyName::NUM_ASTERISK, 19, 1),
+    (KeyName::NUM_MINUS, 20, 1),
+    // Row 2
+    (KeyName::TAB, 0, 2),
+    (KeyName::Q, 1, 2),
+    (KeyName::W, 2, 2),
+    (KeyName::E, 3, 2),
+    (KeyName::R, 4, 2),
+    (KeyName::T, 5, 2),
+    (KeyName::Y, 6, 2),
+    (KeyName::U, 7, 2),
+    (KeyName::I, 8, 2),
+    (KeyName::O, 9, 2),
+    (KeyName::P, 10, 2),
+    (KeyName::OPEN_BRACKET, 11, 2),
+    (KeyName::CLOSE_BRACKET, 12, 2),
+    (KeyName::BACKSLASH, 13, 2),
+    (KeyName::KEYBOARD_DELETE, 14, 2),
+    (KeyName::END, 15, 2),
+    (KeyName::PAGE_DOWN, 16, 2),
+    (KeyName::NUM_SEVEN, 17, 2),
+    (KeyName::NUM_EIGHT, 18, 2),
+    (KeyName::NUM_NINE, 19, 2),
+    (KeyName::NUM_PLUS, 20, 2),
+    // Row 3
+    (KeyName::CAPS_LOCK, 0, 3),
+    (KeyName::A, 1, 3),
+    (KeyName::S, 2, 3),
+    (KeyName::D, 3, 3),
+    (KeyName::F, 4, 3),
+    (KeyName::G, 5, 3),
+    (KeyName::H, 6, 3),
+    (KeyName::J, 7, 3),
+    (KeyName::K, 8, 3),
+    (KeyName::L, 9, 3),
+    (KeyName::SEMICOLON, 10, 3),
+    (KeyName::APOSTROPHE, 11, 3),
+    (KeyName::ENTER, 13, 3),
+    (KeyName::NUM_FOUR, 17, 3),
+    (KeyName::NUM_FIVE, 18, 3),
+    (KeyName::NUM_SIX, 19, 3),
+    // Row 4
+    (KeyName::LEFT_SHIFT, 0, 4),
+    (KeyName::Z, 2, 4),
+    (KeyName::X, 3, 4),
+    (KeyName::C, 4, 4),
+    (KeyName::V, 5, 4),
+    (KeyName::B, 6, 4),
+    (KeyName::N, 7, 4),
+    (KeyName::M, 8, 4),
+    (KeyName::COMMA, 9, 4),
+    (KeyName::PERIOD, 10, 4),
+    (KeyName::FORWARD_SLASH, 11, 4),
+    (KeyName::RIGHT_SHIFT, 13, 4),
+    (KeyName::ARROW_UP, 15, 4),
+    (KeyName::NUM_ONE, 17, 4),
+    (KeyName::NUM_TWO, 18, 4),
+    (KeyName::NUM_THREE, 19, 4),
+    (KeyName::NUM_ENTER, 20, 4),
+    // Row 5
+    (KeyName::LEFT_CONTROL, 0, 5),
+    (KeyName::LEFT_WINDOWS, 1, 5),
+    (KeyName::LEFT_ALT, 2, 5),
+    (KeyName::SPACE, 5, 5),
+    (KeyName::RIGHT_ALT, 11, 5),
+    (KeyName::RIGHT_WINDOWS, 12, 5),
+    (KeyName::APPLICATION_SELECT, 13, 5),
+    (KeyName::RIGHT_CONTROL, 14, 5),
+    (KeyName::ARROW_LEFT, 15, 5),
+    (KeyName::ARROW_DOWN, 16, 5),
+    (KeyName::ARROW_RIGHT, 17, 5),
+    (KeyName::NUM_ZERO, 18, 5),
+    (KeyName::NUM_PERIOD, 19, 5),
+];
+
+/// A full-keyboard lighting frame.
+///
+/// This is a grid of `BITMAP_WIDTH` by `BITMAP_HEIGHT` cells, where each cell corresponds to a key.
+/// It can be sent to the keyboard all at once with [`Sdk::set_lighting_from_bitmap`](crate::Sdk::set_lighting_from_bitmap).
+/// The SDK's BGRA byte order is handled internally.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct KeyboardBitmap {
+    data: [u8; BITMAP_SIZE],
+}
+
+impl KeyboardBitmap {
+    /// Create a new bitmap with every key turned off.
+    pub fn new() -> Self {
+        let mut bitmap = Self {
+            data: [0; BITMAP_SIZE],
+        };
+        bitmap.clear();
+        bitmap
+    }
+
+    /// Create a new bitmap with every key set to the given color.
+    pub fn new_filled(color: ColorPercent) -> Self {
+        let mut bitmap = Self::new();
+        bitmap.fill(color);
+        bitmap
+    }
+
+    /// Create a bitmap from raw BGRA bytes, in the layout the SDK expects.
+    pub fn from_bgra_bytes(data: [u8; BITMAP_SIZE]) -> Self {
+        Self { data }
+    }
+
+    /// Get the raw BGRA bytes of this bitmap, in the layout the SDK expects.
+    pub fn as_bgra_bytes(&self) -> &[u8; BITMAP_SIZE] {
+        &self.data
+    }
+
+    /// Get the cell of a key, as `(x, y)`.
+    ///
+    /// # Returns
+    /// Returns None if the key is not part of the bitmap.
+    pub fn key_position(key: KeyName) -> Option<(usize, usize)> {
+        BITMAP_KEY_POSITIONS
+            .iter()
+            .find(|(k, _, _)| *k == key)
+            .map(|(_, x, y)| (*x, *y))
+    }
+
+    /// Get the key at a cell.
+    ///
+    /// # Returns
+    /// Returns None if the position is out of bounds or the cell is unused.
+    pub fn key_at(x: usize, y: usize) -> Option<KeyName> {
+        BITMAP_KEY_POSITIONS
+            .iter()
+            .find(|(_, key_x, key_y)| (*key_x, *key_y) == (x, y))
+            .map(|(key, _, _)| *key)
+    }
+
+    /// Iterate over the cells that do not correspond to any key, in row-major order, as `(x, y)`.
+    pub fn unused_cells() -> impl Iterator<Item = (usize, usize)> {
+        (0..BITMAP_HEIGHT)
+            .flat_map(|y| (0..BITMAP_WIDTH).map(move |x| (x, y)))
+            .filter(|(x, y)| Self::key_at(*x, *y).is_none())
+    }
+
+    /// Get the byte offset of a cell.
+    fn offset(x: usize, y: usize) -> Option<usize> {
+        if x >= BITMAP_WIDTH || y >= BITMAP_HEIGHT {
+            return None;
+        }
+
+        Some(((y * BITMAP_WIDTH) + x) * BYTES_PER_KEY)
+    }
+
+    /// Get the color of the cell at the given position.
+    ///
+    /// # Returns
+    /// Returns None if the position is out of bounds.
+    pub fn get(&self, x: usize, y: usize) -> Option<ColorPercent> {
+        let offset = Self::offset(x, y)?;
+        let cell = &self.data[offset..offset + BYTES_PER_KEY];
+
+        Some(ColorPercent {
+            r: byte_to_percent(cell[2]),
+            g: byte_to_percent(cell[1]),
+            b: byte_to_percent(cell[0]),
+        })
+    }
+
+    /// Set the color of the cell at the given position.
+    ///
+    /// # Returns
+    /// Returns false if the position is out of bounds.
+    pub fn set(&mut self, x: usize, y: usize, color: ColorPercent) -> bool {
+        let offset = match Self::offset(x, y) {
+            Some(offset) => offset,
+            None => return false,
+        };
+
+        self.data[offset..offset + BYTES_PER_KEY].copy_from_slice(&[
+            percent_to_byte(color.b),
+            percent_to_byte(color.g),
+            percent_to_byte(color.r),
+            u8::MAX,
+        ]);
+
+        true
+    }
+
+    /// Get the color of the given key.
+    ///
+    /// # Returns
+    /// Returns None if the key is not part of the bitmap.
+    pub fn get_key(&self, key: KeyName) -> Option<ColorPercent> {
+        let (x, y) = Self::key_position(key)?;
+        self.get(x, y)
+    }
+
+    /// Set the color of the given key.
+    ///
+    /// # Returns
+    /// Returns false if the key is not part of the bitmap.
+    pub fn set_key(&mut self, key: KeyName, color: ColorPercent) -> bool {
+        match Self::key_position(key) {
+            Some((x, y)) => self.set(x, y, color),
+            None => false,
+        }
+    }
+
+    /// Set every cell to the given color.
+    pub fn fill(&mut self, color: ColorPercent) {
+        for y in 0..BITMAP_HEIGHT {
+            for x in 0..BITMAP_WIDTH {
+                self.set(x, y, color);
+            }
+        }
+    }
+
+    /// Turn off every cell.
+    pub fn clear(&mut self) {
+        self.fill(ColorPercent { r: 0, g: 0, b: 0 });
+    }
+
+    /// Iterate over every cell in row-major order, as `((x, y), color)`.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), ColorPercent)> + '_ {
+        (0..BITMAP_HEIGHT).flat_map(move |y| {
+            (0..BITMAP_WIDTH).map(move |x| {
+                let color = self.get(x, y).expect("cell in bounds");
+                ((x, y), color)
+            })
+        })
+    }
+}
+
+impl Default for KeyboardBitmap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for KeyboardBitmap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyboardBitmap").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn set_get() {
+        let mut bitmap = KeyboardBitmap::new();
+        let color = ColorPercent::new_percent(100, 50, 0).unwrap();
+
+        assert!(bitmap.set(20, 5, color));
+        assert_eq!(bitmap.get(20, 5), Some(color));
+        assert!(!bitmap.set(21, 0, color));
+        assert_eq!(bitmap.get(0, 6), None);
+
+        // BGRA
+        let offset = ((5 * BITMAP_WIDTH) + 20) * BYTES_PER_KEY;
+        assert_eq!(
+            bitmap.as_bgra_bytes()[offset..offset + BYTES_PER_KEY],
+            [0, 128, 255, 255]
+        );
+    }
+
+    #[test]
+    fn keys() {
+        let mut bitmap = KeyboardBitmap::new();
+        let color = ColorPercent::new_percent(0, 0, 100).unwrap();
+
+        assert!(bitmap.set_key(KeyName::W, color));
+        assert_eq!(bitmap.get(2, 2), Some(color));
+        assert_eq!(bitmap.get_key(KeyName::W), Some(color));
+        assert!(!bitmap.set_key(KeyName::G_LOGO, color));
+        assert_eq!(bitmap.get_key(KeyName::G_1), None);
+    }
+
+    #[test]
+    fn key_positions_unique() {
+        for (i, (key, x, y)) in BITMAP_KEY_POSITIONS.iter().enumerate() {
+            assert!(*x < BITMAP_WIDTH && *y < BITMAP_HEIGHT, "{key:?}");
+            for (other_key, other_x, other_y) in BITMAP_KEY_POSITIONS[i + 1..].iter() {
+                assert_ne!(key, other_key);
+                assert_ne!((x, y), (other_x, other_y), "{key:?} {other_key:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn key_lookups() {
+        assert_eq!(KeyboardBitmap::key_position(KeyName::W), Some((2, 2)));
+        assert_eq!(
+            KeyboardBitmap::key_position(KeyName::NUM_ENTER),
+            Some((20, 4))
+        );
+        assert_eq!(KeyboardBitmap::key_position(KeyName::G_LOGO), None);
+
+        for (key, x, y) in BITMAP_KEY_POSITIONS.iter().copied() {
+            assert_eq!(KeyboardBitmap::key_at(x, y), Some(key));
+        }
+        assert_eq!(KeyboardBitmap::key_at(1, 0), Some(KeyName::F1));
+        assert_eq!(KeyboardBitmap::key_at(15, 0), Some(KeyName::PAUSE_BREAK));
+        assert_eq!(KeyboardBitmap::key_at(16, 0), None);
+        assert_eq!(KeyboardBitmap::key_at(BITMAP_WIDTH, 0), None);
+
+        let unused: Vec<_> = KeyboardBitmap::unused_cells().collect();
+        assert_eq!(
+            unused.len() + BITMAP_KEY_POSITIONS.len(),
+            BITMAP_WIDTH * BITMAP_HEIGHT
+        );
+        assert_eq!(unused[0], (16, 0));
+        assert!(unused.contains(&(12, 3)));
+        assert!(!unused.contains(&(13, 3)));
+    }
+
+    #[test]
+    fn fill_clear_iter() {
+        let color = ColorPercent::new_percent(10, 20, 30).unwrap();
+        let mut bitmap = KeyboardBitmap::new_filled(color);
+        assert_eq!(bitmap.iter().count(), BITMAP_WIDTH * BITMAP_HEIGHT);
+        assert!(bitmap.iter().all(|(_, c)| c == color));
+
+        bitmap.clear();
+        assert!(bitmap
+            .iter()
+            .all(|(_, c)| c == ColorPercent { r: 0, g: 0, b: 0 }));
+        assert_eq!(bitmap, KeyboardBitmap::default());
+    }
+}
@@ -0,0 +1,362 @@
+use crate::KeyName;
+
+/// A rectangle on a keyboard, in key units.
+///
+/// One key unit is the width of a letter key.
+/// `x` grows to the right and `y` grows downwards.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct KeyRect {
+    /// The x position of the left edge.
+    pub x: f32,
+
+    /// The y position of the top edge.
+    pub y: f32,
+
+    /// The width.
+    pub width: f32,
+
+    /// The height.
+    pub height: f32,
+}
+
+impl KeyRect {
+    /// Make a new rectangle.
+    pub const fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Get the center of this rectangle, as `(x, y)`.
+    pub fn center(&self) -> (f32, f32) {
+        (self.x + (self.width / 2.0), self.y + (self.height / 2.0))
+    }
+
+    /// Returns true if this rectangle contains the point.
+    ///
+    /// The left and top edges are inside the rectangle, the right and bottom edges are not.
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+/// The full-size ANSI layout.
+///
+/// The main block starts at the origin.
+/// There is a half key gap below the function row.
+const ANSI_KEYS: &[(KeyName, KeyRect)] = &[
+    (KeyName::ESC, KeyRect::new(0.0, 0.0, 1.0, 1.0)),
+    (KeyName::F1, KeyRect::new(2.0, 0.0, 1.0, 1.0)),
+    (KeyName::F2, KeyRect::new(3.0, 0.0, 1.0, 1.0)),
+    (KeyName::F3, KeyRect::new(4.0, 0.0, 1.0, 1.0)),
+    (KeyName::F4, KeyRect::new(5.0, 0.0, 1.0, 1.0)),
+    (KeyName::F5, KeyRect::new(6.5, 0.0, 1.0, 1.0)),
+    (KeyName::F6, KeyRect::new(7.5, 0.0, 1.0, 1.0)),
+    (KeyName::F7, KeyRect::new(8.5, 0.0, 1.0, 1.0)),
+    (KeyName::F8, KeyRect::new(9.5, 0.0, 1.0, 1.0)),
+    (KeyName::F9, KeyRect::new(11.0, 0.0, 1.0, 1.0)),
+    (KeyName::F10, KeyRect::new(12.0, 0.0, 1.0, 1.0)),
+    (KeyName::F11, KeyRect::new(13.0, 0.0, 1.0, 1.0)),
+    (KeyName::F12, KeyRect::new(14.0, 0.0, 1.0, 1.0)),
+    (KeyName::PRINT_SCREEN, KeyRect::new(15.25, 0.0, 1.0, 1.0)),
+    (KeyName::SCROLL_LOCK, KeyRect::new(16.25, 0.0, 1.0, 1.0)),
+    (KeyName::PAUSE_BREAK, KeyRect::new(17.25, 0.0, 1.0, 1.0)),
+    (KeyName::TILDE, KeyRect::new(0.0, 1.5, 1.0, 1.0)),
+    (KeyName::ONE, KeyRect::new(1.0, 1.5, 1.0, 1.0)),
+    (KeyName::TWO, KeyRect::new(2.0, 1.5, 1.0, 1.0)),
+    (KeyName::THREE, KeyRect::new(3.0, 1.5, 1.0, 1.0)),
+    (KeyName::FOUR, KeyRect::new(4.0, 1.5, 1.0, 1.0)),
+    (KeyName::FIVE, KeyRect::new(5.0, 1.5, 1.0, 1.0)),
+    (KeyName::SIX, KeyRect::new(6.0, 1.5, 1.0, 1.0)),
+    (KeyName::SEVEN, KeyRect::new(7.0, 1.5, 1.0, 1.0)),
+    (KeyName::EIGHT, KeyRect::new(8.0, 1.5, 1.0, 1.0)),
+    (KeyName::NINE, KeyRect::new(9.0, 1.5, 1.0, 1.0)),
+    (KeyName::ZERO, KeyRect::new(10.0, 1.5, 1.0, 1.0)),
+    (KeyName::MINUS, KeyRect::new(11.0, 1.5, 1.0, 1.0)),
+    (KeyName::EQUALS, KeyRect::new(12.0, 1.5, 1.0, 1.0)),
+    (KeyName::BACKSPACE, KeyRect::new(13.0, 1.5, 2.0, 1.0)),
+    (KeyName::INSERT, KeyRect::new(15.25, 1.5, 1.0, 1.0)),
+    (KeyName::HOME, KeyRect::new(16.25, 1.5, 1.0, 1.0)),
+    (KeyName::PAGE_UP, KeyRect::new(17.25, 1.5, 1.0, 1.0)),
+    (KeyName::NUM_LOCK, KeyRect::new(18.5, 1.5, 1.0, 1.0)),
+    (KeyName::NUM_SLASH, KeyRect::new(19.5, 1.5, 1.0, 1.0)),
+    (KeyName::NUM_ASTERISK, KeyRect::new(20.5, 1.5, 1.0, 1.0)),
+    (KeyName::NUM_MINUS, KeyRect::new(21.5, 1.5, 1.0, 1.0)),
+    (KeyName::TAB, KeyRect::new(0.0, 2.5, 1.5, 1.0)),
+    (KeyName::Q, KeyRect::new(1.5, 2.5, 1.0, 1.0)),
+    (KeyName::W, KeyRect::new(2.5, 2.5, 1.0, 1.0)),
+    (KeyName::E, KeyRect::new(3.5, 2.5, 1.0, 1.0)),
+    (KeyName::R, KeyRect::new(4.5, 2.5, 1.0, 1.0)),
+    (KeyName::T, KeyRect::new(5.5, 2.5, 1.0, 1.0)),
+    (KeyName::Y, KeyRect::new(6.5, 2.5, 1.0, 1.0)),
+    (KeyName::U, KeyRect::new(7.5, 2.5, 1.0, 1.0)),
+    (KeyName::I, KeyRect::new(8.5, 2.5, 1.0, 1.0)),
+    (KeyName::O, KeyRect::new(9.5, 2.5, 1.0, 1.0)),
+    (KeyName::P, KeyRect::new(10.5, 2.5, 1.0, 1.0)),
+    (KeyName::OPEN_BRACKET, KeyRect::new(11.5, 2.5, 1.0, 1.0)),
+    (KeyName::CLOSE_BRACKET, KeyRect::new(12.5, 2.5, 1.0, 1.0)),
+    (KeyName::BACKSLASH, KeyRect::new(13.5, 2.5, 1.5, 1.0)),
+    (KeyName::KEYBOARD_DELETE, KeyRect::new(15.25, 2.5, 1.0, 1.0)),
+    (KeyName::END, KeyRect::new(16.25, 2.5, 1.0, 1.0)),
+    (KeyName::PAGE_DOWN, KeyRect::new(17.25, 2.5, 1.0, 1.0)),
+    (KeyName::NUM_SEVEN, KeyRect::new(18.5, 2.5, 1.0, 1.0)),
+    (KeyName::NUM_EIGHT, KeyRect::new(19.5, 2.5, 1.0, 1.0)),
+    (KeyName::NUM_NINE, KeyRect::new(20.5, 2.5, 1.0, 1.0)),
+    (KeyName::NUM_PLUS, KeyRect::new(21.5, 2.5, 1.0, 2.0)),
+    (KeyName::CAPS_LOCK, KeyRect::new(0.0, 3.5, 1.75, 1.0)),
+    (KeyName::A, KeyRect::new(1.75, 3.5, 1.0, 1.0)),
+    (KeyName::S, KeyRect::new(2.75, 3.5, 1.0, 1.0)),
+    (KeyName::D, KeyRect::new(3.75, 3.5, 1.0, 1.0)),
+    (KeyName::F, KeyRect::new(4.75, 3.5, 1.0, 1.0)),
+    (KeyName::G, KeyRect::new(5.75, 3.5, 1.0, 1.0)),
+    (KeyName::H, KeyRect::new(6.75, 3.5, 1.0, 1.0)),
+    (KeyName::J, KeyRect::new(7.75, 3.5, 1.0, 1.0)),
+    (KeyName::K, KeyRect::new(8.75, 3.5, 1.0, 1.0)),
+    (KeyName::L, KeyRect::new(9.75, 3.5, 1.0, 1.0)),
+    (KeyName::SEMICOLON, KeyRect::new(10.75, 3.5, 1.0, 1.0)),
+    (KeyName::APOSTROPHE, KeyRect::new(11.75, 3.5, 1.0, 1.0)),
+    (KeyName::ENTER, KeyRect::new(12.75, 3.5, 2.25, 1.0)),
+    (KeyName::NUM_FOUR, KeyRect::new(18.5, 3.5, 1.0, 1.0)),
+    (KeyName::NUM_FIVE, KeyRect::new(19.5, 3.5, 1.0, 1.0)),
+    (KeyName::NUM_SIX, KeyRect::new(20.5, 3.5, 1.0, 1.0)),
+    (KeyName::LEFT_SHIFT, KeyRect::new(0.0, 4.5, 2.25, 1.0)),
+    (KeyName::Z, KeyRect::new(2.25, 4.5, 1.0, 1.0)),
+    (KeyName::X, KeyRect::new(3.25, 4.5, 1.0, 1.0)),
+    (KeyName::C, KeyRect::new(4.25, 4.5, 1.0, 1.0)),
+    (KeyName::V, KeyRect::new(5.25, 4.5, 1.0, 1.0)),
+    (KeyName::B, KeyRect::new(6.25, 4.5, 1.0, 1.0)),
+    (KeyName::N, KeyRect::new(7.25, 4.5, 1.0, 1.0)),
+    (KeyName::M, KeyRect::new(8.25, 4.5, 1.0, 1.0)),
+    (KeyName::COMMA, KeyRect::new(9.25, 4.5, 1.0, 1.0)),
+    (KeyName::PERIOD, KeyRect::new(10.25, 4.5, 1.0, 1.0)),
+    (KeyName::FORWARD_SLASH, KeyRect::new(11.25, 4.5, 1.0, 1.0)),
+    (KeyName::RIGHT_SHIFT, KeyRect::new(12.25, 4.5, 2.75, 1.0)),
+    (KeyName::ARROW_UP, KeyRect::new(16.25, 4.5, 1.0, 1.0)),
+    (KeyName::NUM_ONE, KeyRect::new(18.5, 4.5, 1.0, 1.0)),
+    (KeyName::NUM_TWO, KeyRect::new(19.5, 4.5, 1.0, 1.0)),
+    (KeyName::NUM_THREE, KeyRect::new(20.5, 4.5, 1.0, 1.0)),
+    (KeyName::NUM_ENTER, KeyRect::new(21.5, 4.5, 1.0, 2.0)),
+    (KeyName::LEFT_CONTROL, KeyRect::new(0.0, 5.5, 1.25, 1.0)),
+    (KeyName::LEFT_WINDOWS, KeyRect::new(1.25, 5.5, 1.25, 1.0)),
+    (KeyName::LEFT_ALT, KeyRect::new(2.5, 5.5, 1.25, 1.0)),
+    (KeyName::SPACE, KeyRect::new(3.75, 5.5, 6.25, 1.0)),
+    (KeyName::RIGHT_ALT, KeyRect::new(10.0, 5.5, 1.25, 1.0)),
+    (KeyName::RIGHT_WINDOWS, KeyRect::new(11.25, 5.5, 1.25, 1.0)),
+    (
+        KeyName::APPLICATION_SELECT,
+        KeyRect::new(12.5, 5.5, 1.25, 1.0),
+    ),
+    (KeyName::RIGHT_CONTROL, KeyRect::new(13.75, 5.5, 1.25, 1.0)),
+    (KeyName::ARROW_LEFT, KeyRect::new(15.25, 5.5, 1.0, 1.0)),
+    (KeyName::ARROW_DOWN, KeyRect::new(16.25, 5.5, 1.0, 1.0)),
+    (KeyName::ARROW_RIGHT, KeyRect::new(17.25, 5.5, 1.0, 1.0)),
+    (KeyName::NUM_ZERO, KeyRect::new(18.5, 5.5, 2.0, 1.0)),
+    (KeyName::NUM_PERIOD, KeyRect::new(20.5, 5.5, 1.0, 1.0)),
+];
+
+/// The keys that are not part of a standard layout, loosely following the G910.
+///
+/// These sit above and to the left of the main block, so they have negative positions.
+const LOGITECH_KEYS: &[(KeyName, KeyRect)] = &[
+    (KeyName::G_1, KeyRect::new(-1.25, 1.5, 1.0, 1.0)),
+    (KeyName::G_2, KeyRect::new(-1.25, 2.5, 1.0, 1.0)),
+    (KeyName::G_3, KeyRect::new(-1.25, 3.5, 1.0, 1.0)),
+    (KeyName::G_4, KeyRect::new(-1.25, 4.5, 1.0, 1.0)),
+    (KeyName::G_5, KeyRect::new(-1.25, 5.5, 1.0, 1.0)),
+    (KeyName::G_6, KeyRect::new(2.0, -1.25, 1.0, 1.0)),
+    (KeyName::G_7, KeyRect::new(3.0, -1.25, 1.0, 1.0)),
+    (KeyName::G_8, KeyRect::new(4.0, -1.25, 1.0, 1.0)),
+    (KeyName::G_9, KeyRect::new(5.0, -1.25, 1.0, 1.0)),
+    (KeyName::G_LOGO, KeyRect::new(-1.25, 0.0, 1.0, 1.0)),
+    (KeyName::G_BADGE, KeyRect::new(-1.25, -1.25, 1.0, 1.0)),
+];
+
+/// The keys that differ between the full-size ANSI and ISO layouts.
+///
+/// ISO moves the backslash key next to enter and shortens left shift to make room for an extra key.
+/// The extra key has no [`KeyName`], so it is left out.
+/// The ISO enter key is L-shaped; it is one rectangle as wide as its top part,
+/// so the backslash key to the left of its lower part is narrowed to 0.75 units to leave no gaps.
+const ISO_KEYS: &[(KeyName, KeyRect)] = &[
+    (KeyName::ENTER, KeyRect::new(13.5, 2.5, 1.5, 2.0)),
+    (KeyName::BACKSLASH, KeyRect::new(12.75, 3.5, 0.75, 1.0)),
+    (KeyName::LEFT_SHIFT, KeyRect::new(0.0, 4.5, 1.25, 1.0)),
+];
+
+/// The physical position of keys on a keyboard.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct KeyboardLayout {
+    keys: Vec<(KeyName, KeyRect)>,
+}
+
+impl KeyboardLayout {
+    /// Make an empty layout.
+    pub fn new() -> Self {
+        Self { keys: Vec::new() }
+    }
+
+    /// Make a full-size ANSI layout, with every [`KeyName`].
+    pub fn ansi() -> Self {
+        let mut layout = Self::new();
+        for (key, rect) in ANSI_KEYS.iter().chain(LOGITECH_KEYS) {
+            layout.insert(*key, *rect);
+        }
+        layout
+    }
+
+    /// Make a full-size ISO layout, with every [`KeyName`].
+    pub fn iso() -> Self {
+        let mut layout = Self::ansi();
+        for (key, rect) in ISO_KEYS {
+            layout.insert(*key, *rect);
+        }
+        layout
+    }
+
+    /// Set the rectangle of a key, returning the old one if it was in the layout.
+    pub fn insert(&mut self, key: KeyName, rect: KeyRect) -> Option<KeyRect> {
+        match self.keys.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, old)) => Some(std::mem::replace(old, rect)),
+            None => {
+                self.keys.push((key, rect));
+                None
+            }
+        }
+    }
+
+    /// Remove a key from the layout, returning its rectangle if it was in the layout.
+    pub fn remove(&mut self, key: KeyName) -> Option<KeyRect> {
+        let index = self.keys.iter().position(|(k, _)| *k == key)?;
+        Some(self.keys.remove(index).1)
+    }
+
+    /// Get the rectangle of a key.
+    pub fn get(&self, key: KeyName) -> Option<KeyRect> {
+        self.keys
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, rect)| *rect)
+    }
+
+    /// Iterate over the keys in this layout and their rectangles.
+    pub fn iter(&self) -> impl Iterator<Item = (KeyName, KeyRect)> + '_ {
+        self.keys.iter().copied()
+    }
+
+    /// Get the smallest rectangle that contains every key, or `None` if the layout is empty.
+    pub fn bounds(&self) -> Option<KeyRect> {
+        let mut iter = self.keys.iter().map(|(_, rect)| rect);
+        let first = iter.next()?;
+        let (mut left, mut top) = (first.x, first.y);
+        let (mut right, mut bottom) = (first.x + first.width, first.y + first.height);
+        for rect in iter {
+            left = left.min(rect.x);
+            top = top.min(rect.y);
+            right = right.max(rect.x + rect.width);
+            bottom = bottom.max(rect.y + rect.height);
+        }
+
+        Some(KeyRect::new(left, top, right - left, bottom - top))
+    }
+
+    /// Get the key at a point, if there is one.
+    pub fn key_at(&self, x: f32, y: f32) -> Option<KeyName> {
+        self.keys
+            .iter()
+            .find(|(_, rect)| rect.contains(x, y))
+            .map(|(key, _)| *key)
+    }
+
+    /// Get the distance between the centers of two keys, or `None` if either is not in the layout.
+    pub fn distance(&self, a: KeyName, b: KeyName) -> Option<f32> {
+        let (ax, ay) = self.get(a)?.center();
+        let (bx, by) = self.get(b)?.center();
+        Some((bx - ax).hypot(by - ay))
+    }
+
+    /// Iterate over the keys whose centers are within `radius` of the center of `of`, including `of` itself.
+    ///
+    /// This is empty if `of` is not in the layout.
+    pub fn keys_within(&self, radius: f32, of: KeyName) -> impl Iterator<Item = KeyName> + '_ {
+        let center = self.get(of).map(|rect| rect.center());
+        self.keys
+            .iter()
+            .filter(move |(_, rect)| {
+                let Some((x, y)) = center else {
+                    return false;
+                };
+                let (key_x, key_y) = rect.center();
+                (key_x - x).hypot(key_y - y) <= radius
+            })
+            .map(|(key, _)| *key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn assert_valid(layout: &KeyboardLayout) {
+        assert_eq!(layout.iter().count(), KeyName::iter().count());
+        for key in KeyName::iter() {
+            let rect = layout.get(key).expect("missing key");
+            let (x, y) = rect.center();
+            assert_eq!(layout.key_at(x, y), Some(key));
+        }
+
+        let keys: Vec<_> = layout.iter().collect();
+        for (i, (a_key, a)) in keys.iter().enumerate() {
+            for (b_key, b) in keys[i + 1..].iter() {
+                let overlaps = a.x < b.x + b.width
+                    && b.x < a.x + a.width
+                    && a.y < b.y + b.height
+                    && b.y < a.y + a.height;
+                assert!(!overlaps, "{a_key:?} overlaps {b_key:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn presets() {
+        let ansi = KeyboardLayout::ansi();
+        let iso = KeyboardLayout::iso();
+        assert_valid(&ansi);
+        assert_valid(&iso);
+
+        assert_eq!(
+            ansi.get(KeyName::LEFT_SHIFT),
+            Some(KeyRect::new(0.0, 4.5, 2.25, 1.0))
+        );
+        assert_eq!(iso.key_at(13.0, 4.0), Some(KeyName::BACKSLASH));
+        assert_eq!(iso.key_at(13.6, 3.0), Some(KeyName::ENTER));
+        assert_eq!(iso.key_at(14.0, 4.0), Some(KeyName::ENTER));
+        assert_eq!(ansi.key_at(13.0, 4.0), Some(KeyName::ENTER));
+        assert_eq!(ansi.key_at(1.5, 0.5), None);
+        assert_eq!(ansi.bounds(), Some(KeyRect::new(-1.25, -1.25, 23.75, 7.75)));
+        assert_eq!(KeyboardLayout::new().bounds(), None);
+    }
+
+    #[test]
+    fn keys_within() {
+        let layout = KeyboardLayout::ansi();
+
+        let keys: HashSet<_> = layout.keys_within(1.0, KeyName::W).collect();
+        assert_eq!(keys, HashSet::from([KeyName::Q, KeyName::W, KeyName::E]));
+
+        let keys: HashSet<_> = layout.keys_within(1.1, KeyName::W).collect();
+        assert_eq!(
+            keys,
+            HashSet::from([KeyName::Q, KeyName::W, KeyName::E, KeyName::S])
+        );
+
+        assert_eq!(layout.distance(KeyName::Q, KeyName::E), Some(2.0));
+
+        let mut layout = layout;
+        layout.remove(KeyName::W);
+        assert_eq!(layout.keys_within(10.0, KeyName::W).count(), 0);
+    }
+}
@@ -1,14 +1,100 @@
+mod animator;
+mod backend;
+mod call;
+mod clock;
 mod color_percent;
+mod compositor;
+mod css_colors;
+pub mod effects;
+mod error;
+#[cfg(feature = "export")]
+pub mod export;
+mod frame;
+mod hsl;
+mod hsv;
+#[cfg(feature = "import")]
+pub mod import;
+mod keyboard_bitmap;
+mod keyboard_layout;
+mod lighting_snapshot_guard;
+#[cfg(feature = "openrgb")]
+pub mod openrgb;
+mod parse_color_error;
+#[cfg(feature = "profile")]
+mod profile;
+#[cfg(feature = "profile")]
+mod profile_error;
+mod recording_sdk;
+mod replayer;
+mod rgb8;
 mod sdk;
+#[cfg(feature = "simulator")]
+mod simulator_display;
 mod target_device;
+mod trace;
+mod trace_error;
 
+pub use self::animator::Animator;
+pub use self::animator::Effect;
+pub use self::animator::StopHandle;
+pub use self::animator::DEFAULT_FRAME_RATE;
+pub use self::backend::Backend;
+#[cfg(any(test, feature = "mock"))]
+pub use self::backend::MockBackend;
+#[cfg(any(test, feature = "mock"))]
+pub use self::backend::MockCall;
+#[cfg(any(test, feature = "mock"))]
+pub use self::backend::MockConfigValue;
+#[cfg(any(target_os = "windows", feature = "rustdoc", feature = "dynamic"))]
+pub use self::backend::NativeBackend;
+#[cfg(feature = "simulator")]
+pub use self::backend::SimulatorBackend;
+pub use self::call::Call;
+pub use self::call::CallResult;
+pub use self::clock::Clock;
+pub use self::clock::ManualClock;
+pub use self::clock::SystemClock;
 pub use self::color_percent::ColorPercent;
+pub use self::compositor::BlendMode;
+pub use self::compositor::Compositor;
+pub use self::compositor::Layer;
+pub use self::compositor::LayerId;
+pub use self::error::Error;
+pub use self::frame::Frame;
+pub use self::hsl::Hsl;
+pub use self::hsv::Hsv;
+pub use self::keyboard_bitmap::KeyboardBitmap;
+pub use self::keyboard_bitmap::BITMAP_HEIGHT;
+pub use self::keyboard_bitmap::BITMAP_KEY_POSITIONS;
+pub use self::keyboard_bitmap::BITMAP_WIDTH;
+pub use self::keyboard_layout::KeyRect;
+pub use self::keyboard_layout::KeyboardLayout;
+pub use self::lighting_snapshot_guard::LightingSnapshotGuard;
+pub use self::parse_color_error::ParseColorError;
+#[cfg(feature = "profile")]
+pub use self::profile::Profile;
+#[cfg(feature = "profile")]
+pub use self::profile::ProfileEffect;
+#[cfg(feature = "profile")]
+pub use self::profile_error::ProfileError;
+pub use self::recording_sdk::RecordingSdk;
+pub use self::replayer::Replayer;
+pub use self::rgb8::Rgb8;
 pub use self::sdk::Sdk;
+pub use self::sdk::CONFIG_STRING_CAPACITY;
+#[cfg(feature = "simulator")]
+pub use self::simulator_display::SimulatorDisplay;
 pub use self::target_device::TargetDevice;
+pub use self::trace::Trace;
+pub use self::trace::TraceEvent;
+pub use self::trace::TRACE_HEADER;
+pub use self::trace_error::TraceError;
 pub use logitech_led_sdk_sys as sys;
 use std::sync::Mutex;
+pub use sys::KeyCodes;
 pub use sys::LogiLed_DeviceType as DeviceType;
 pub use sys::LogiLed_KeyName as KeyName;
+pub use sys::KEY_CODES;
 
 /// The lock that syncs accesses to the SDK.
 ///
@@ -16,7 +102,7 @@ pub use sys::LogiLed_KeyName as KeyName;
 /// This library does all this for you, this is exposed only for users who want to use raw sdk functions safely.
 pub static SDK_LOCK: Mutex<()> = Mutex::new(());
 
-#[cfg(test)]
+#[cfg(all(test, target_os = "windows"))]
 mod test {
     use super::*;
     use std::time::Duration;
@@ -38,48 +124,95 @@ mod test {
         let sdk = Sdk::new_with_name("Test").expect("failed to init LG SDK");
 
         // 3rd init fails, we already opened the 2nd.
-        assert!(Sdk::new().is_none());
+        assert!(matches!(Sdk::new(), Err(Error::LockHeld)));
 
         std::thread::sleep(Duration::from_secs(5));
         let _version = sdk.get_version().expect("failed to get LG SDK version");
-        assert!(sdk.set_target(TargetDevice::All));
-        assert!(sdk.set_lighting(ColorPercent::new_rgb(255, 255, 255)));
-        assert!(sdk.set_lighting_for_key_with_name(KeyName::L, ColorPercent::new_rgb(0, 255, 255)));
-        assert!(sdk.set_lighting_for_target_zone(
-            DeviceType::Mouse,
-            1,
-            ColorPercent::new_rgb(255, 0, 0)
-        ));
-        assert!(sdk.flash_lighting(
+        sdk.set_config_label("lighting", "Lighting")
+            .expect("failed to set config label");
+        let _speed = sdk
+            .config_number("lighting/speed", 1.0)
+            .expect("failed to get config number");
+        let _enabled = sdk
+            .config_bool("lighting/enabled", true)
+            .expect("failed to get config bool");
+        let _color = sdk
+            .config_color("lighting/color", ColorPercent::new_rgb(255, 0, 0))
+            .expect("failed to get config color");
+        let _rect = sdk
+            .config_rect("lighting/rect", (0, 0, 10, 10))
+            .expect("failed to get config rect");
+        let _name = sdk
+            .config_string("lighting/name", "Test")
+            .expect("failed to get config string");
+        let _key = sdk
+            .config_key_input("lighting/key", "CTRL+A")
+            .expect("failed to get config key input");
+        let mode = sdk
+            .config_select("lighting/mode", "Solid", &["Solid", "Flash", "Pulse"])
+            .expect("failed to get config select");
+        assert!(["Solid", "Flash", "Pulse"].contains(&mode.as_str()));
+        let brightness = sdk
+            .config_range("lighting/brightness", 50, 0, 100)
+            .expect("failed to get config range");
+        assert!((0..=100).contains(&brightness));
+        sdk.set_target(TargetDevice::All)
+            .expect("failed to set target");
+        sdk.set_lighting(ColorPercent::new_rgb(255, 255, 255))
+            .expect("failed to set lighting");
+        sdk.set_lighting_for_key_with_name(KeyName::L, ColorPercent::new_rgb(0, 255, 255))
+            .expect("failed to set lighting for key with name");
+        let mut bitmap = KeyboardBitmap::new_filled(ColorPercent::new_rgb(0, 0, 255));
+        assert!(bitmap.set_key(KeyName::W, ColorPercent::new_rgb(255, 0, 0)));
+        sdk.set_lighting_from_bitmap(&bitmap)
+            .expect("failed to set lighting from bitmap");
+        sdk.exclude_keys_from_bitmap([KeyName::W, KeyName::A])
+            .expect("failed to exclude keys from bitmap");
+        sdk.set_lighting_from_bitmap(&KeyboardBitmap::new())
+            .expect("failed to set lighting from bitmap");
+        sdk.exclude_keys_from_bitmap([])
+            .expect("failed to exclude keys from bitmap");
+        sdk.set_lighting_for_target_zone(DeviceType::Mouse, 1, ColorPercent::new_rgb(255, 0, 0))
+            .expect("failed to set lighting for target zone");
+        sdk.flash_lighting(
             ColorPercent::new_rgb(255, 0, 0),
             Some(Duration::from_millis(10_000)),
-            Duration::from_millis(100)
-        ));
-        assert!(sdk.stop_effects());
-        assert!(sdk.pulse_lighting(
+            Duration::from_millis(100),
+        )
+        .expect("failed to flash lighting");
+        sdk.stop_effects().expect("failed to stop effects");
+        sdk.pulse_lighting(
             ColorPercent::new_rgb(255, 0, 0),
             Some(Duration::from_millis(10_000)),
-            Duration::from_millis(100)
-        ));
-        assert!(sdk.stop_effects());
-        assert!(sdk.set_lighting_for_key_with_scan_code(16, ColorPercent::new_rgb(255, 255, 255)));
-        assert!(sdk.set_lighting_for_key_with_hid_code(26, ColorPercent::new_rgb(255, 255, 255)));
-        assert!(sdk.save_lighting_for_key(KeyName::L));
-        assert!(sdk.restore_lighting_for_key(KeyName::L));
-        assert!(sdk.flash_single_key(
+            Duration::from_millis(100),
+        )
+        .expect("failed to pulse lighting");
+        sdk.stop_effects().expect("failed to stop effects");
+        sdk.set_lighting_for_key_with_scan_code(16, ColorPercent::new_rgb(255, 255, 255))
+            .expect("failed to set lighting for key with scan code");
+        sdk.set_lighting_for_key_with_hid_code(26, ColorPercent::new_rgb(255, 255, 255))
+            .expect("failed to set lighting for key with hid code");
+        sdk.save_lighting_for_key(KeyName::L)
+            .expect("failed to save lighting for key");
+        sdk.restore_lighting_for_key(KeyName::L)
+            .expect("failed to restore lighting for key");
+        sdk.flash_single_key(
             KeyName::L,
             ColorPercent::new_rgb(255, 0, 0),
             Some(Duration::from_millis(10_000)),
-            Duration::from_millis(100)
-        ));
-        assert!(sdk.pulse_single_key(
+            Duration::from_millis(100),
+        )
+        .expect("failed to flash single key");
+        sdk.pulse_single_key(
             KeyName::L,
             ColorPercent::new_rgb(255, 0, 0),
             ColorPercent::new_rgb(255, 255, 0),
             Duration::from_millis(10_000),
-            true
-        ));
-        assert!(sdk.stop_effects_on_key(KeyName::L));
+            true,
+        )
+        .expect("failed to pulse single key");
+        sdk.stop_effects_on_key(KeyName::L)
+            .expect("failed to stop effects on key");
         drop(sdk);
         std::thread::sleep(Duration::from_secs(5));
     }
@@ -90,54 +223,51 @@ mod test {
 
         let sdk = Sdk::new_with_name("Test").expect("failed to init LG SDK");
         std::thread::sleep(Duration::from_secs(5));
-        assert!(sdk.set_target(TargetDevice::All));
-        assert!(sdk.set_lighting_for_key_with_name(KeyName::L, ColorPercent::new_rgb(0, 255, 255)));
-        assert!(sdk.set_lighting_for_key_with_name(KeyName::O, ColorPercent::new_rgb(0, 255, 255)));
-        assert!(sdk.set_lighting_for_key_with_name(KeyName::G, ColorPercent::new_rgb(0, 255, 255)));
-        assert!(sdk.set_lighting_for_key_with_name(KeyName::I, ColorPercent::new_rgb(0, 255, 255)));
+        sdk.set_target(TargetDevice::All)
+            .expect("failed to set target");
+        sdk.set_lighting_for_key_with_name(KeyName::L, ColorPercent::new_rgb(0, 255, 255))
+            .expect("failed to set lighting for key with name");
+        sdk.set_lighting_for_key_with_name(KeyName::O, ColorPercent::new_rgb(0, 255, 255))
+            .expect("failed to set lighting for key with name");
+        sdk.set_lighting_for_key_with_name(KeyName::G, ColorPercent::new_rgb(0, 255, 255))
+            .expect("failed to set lighting for key with name");
+        sdk.set_lighting_for_key_with_name(KeyName::I, ColorPercent::new_rgb(0, 255, 255))
+            .expect("failed to set lighting for key with name");
 
-        assert!(sdk.set_lighting_for_target_zone(
-            DeviceType::Mouse,
-            1,
-            ColorPercent::new_rgb(255, 0, 0)
-        ));
+        sdk.set_lighting_for_target_zone(DeviceType::Mouse, 1, ColorPercent::new_rgb(255, 0, 0))
+            .expect("failed to set lighting for target zone");
 
-        assert!(sdk.set_lighting_for_target_zone(
-            DeviceType::Keyboard,
-            1,
-            ColorPercent::new_rgb(255, 0, 0)
-        ));
-        assert!(sdk.set_lighting_for_target_zone(
+        sdk.set_lighting_for_target_zone(DeviceType::Keyboard, 1, ColorPercent::new_rgb(255, 0, 0))
+            .expect("failed to set lighting for target zone");
+        sdk.set_lighting_for_target_zone(
             DeviceType::Keyboard,
             2,
-            ColorPercent::new_rgb(255, 255, 0)
-        ));
-        assert!(sdk.set_lighting_for_target_zone(
-            DeviceType::Keyboard,
-            3,
-            ColorPercent::new_rgb(0, 255, 0)
-        ));
-        assert!(sdk.set_lighting_for_target_zone(
+            ColorPercent::new_rgb(255, 255, 0),
+        )
+        .expect("failed to set lighting for target zone");
+        sdk.set_lighting_for_target_zone(DeviceType::Keyboard, 3, ColorPercent::new_rgb(0, 255, 0))
+            .expect("failed to set lighting for target zone");
+        sdk.set_lighting_for_target_zone(
             DeviceType::Keyboard,
             4,
-            ColorPercent::new_rgb(0, 255, 255)
-        ));
-        assert!(sdk.set_lighting_for_target_zone(
-            DeviceType::Keyboard,
-            5,
-            ColorPercent::new_rgb(0, 0, 255)
-        ));
+            ColorPercent::new_rgb(0, 255, 255),
+        )
+        .expect("failed to set lighting for target zone");
+        sdk.set_lighting_for_target_zone(DeviceType::Keyboard, 5, ColorPercent::new_rgb(0, 0, 255))
+            .expect("failed to set lighting for target zone");
 
-        assert!(sdk.set_lighting_for_target_zone(
+        sdk.set_lighting_for_target_zone(
             DeviceType::Headset,
             0,
-            ColorPercent::new_rgb(255, 255, 255)
-        ));
-        assert!(sdk.set_lighting_for_target_zone(
+            ColorPercent::new_rgb(255, 255, 255),
+        )
+        .expect("failed to set lighting for target zone");
+        sdk.set_lighting_for_target_zone(
             DeviceType::Headset,
             1,
-            ColorPercent::new_rgb(255, 0, 255)
-        ));
+            ColorPercent::new_rgb(255, 0, 255),
+        )
+        .expect("failed to set lighting for target zone");
         drop(sdk);
         std::thread::sleep(Duration::from_secs(5));
     }
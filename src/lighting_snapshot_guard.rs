@@ -0,0 +1,94 @@
+use crate::Error;
+use crate::Sdk;
+
+/// A guard that restores the lighting saved by [`Sdk::save_lighting`].
+///
+/// The lighting is restored when the guard is dropped, including during an early return or a panic.
+/// Use [`LightingSnapshotGuard::restore`] to restore it early and observe any error.
+#[must_use = "the lighting is restored as soon as the guard is dropped"]
+pub struct LightingSnapshotGuard<'a> {
+    sdk: &'a Sdk,
+}
+
+impl<'a> LightingSnapshotGuard<'a> {
+    pub(crate) fn new(sdk: &'a Sdk) -> Self {
+        Self { sdk }
+    }
+
+    /// Restore the saved lighting now.
+    ///
+    /// # Errors
+    /// Returns an error if the lighting could not be restored.
+    pub fn restore(self) -> Result<(), Error> {
+        let sdk = self.sdk;
+        std::mem::forget(self);
+        sdk.restore_lighting()
+    }
+}
+
+impl Drop for LightingSnapshotGuard<'_> {
+    fn drop(&mut self) {
+        // There is no way to report an error from a drop.
+        let _ = self.sdk.restore_lighting();
+    }
+}
+
+impl std::fmt::Debug for LightingSnapshotGuard<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LightingSnapshotGuard")
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ColorPercent;
+    use crate::KeyName;
+    use crate::MockBackend;
+    use crate::MockCall;
+
+    #[test]
+    fn restore_on_drop() {
+        let backend = MockBackend::new();
+        let sdk = Sdk::new_with_backend(backend.clone()).expect("failed to init mock sdk");
+        let red = ColorPercent::new_rgb(255, 0, 0);
+        let blue = ColorPercent::new_rgb(0, 0, 255);
+
+        sdk.set_lighting(red).expect("failed to set lighting");
+        {
+            let _guard = sdk.save_lighting().expect("failed to save lighting");
+            sdk.set_lighting(blue).expect("failed to set lighting");
+            assert_eq!(backend.key_color(KeyName::W), Some(blue));
+        }
+        assert_eq!(backend.key_color(KeyName::W), Some(red));
+        assert_eq!(backend.calls().last(), Some(&MockCall::RestoreLighting));
+    }
+
+    #[test]
+    fn restore_explicitly() {
+        let backend = MockBackend::new();
+        let sdk = Sdk::new_with_backend(backend.clone()).expect("failed to init mock sdk");
+        let red = ColorPercent::new_rgb(255, 0, 0);
+
+        let guard = sdk.save_lighting().expect("failed to save lighting");
+        sdk.set_lighting_for_key_with_name(KeyName::W, red)
+            .expect("failed to set lighting");
+
+        backend.set_failing("LogiLedRestoreLighting", true);
+        backend.take_calls();
+        assert_eq!(
+            guard.restore(),
+            Err(Error::CallFailed {
+                function: "LogiLedRestoreLighting"
+            })
+        );
+
+        // The guard was consumed, so it must not restore again.
+        assert_eq!(backend.take_calls(), vec![MockCall::RestoreLighting]);
+        assert_eq!(backend.key_color(KeyName::W), Some(red));
+
+        backend.set_failing("LogiLedSaveCurrentLighting", true);
+        assert!(sdk.save_lighting().is_err());
+    }
+}
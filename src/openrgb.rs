@@ -0,0 +1,23 @@
+//! A server for the [OpenRGB](https://openrgb.org) SDK network protocol.
+//!
+//! This lets tools that speak the OpenRGB client protocol control Logitech devices through an [`Sdk`](crate::Sdk).
+//! Clients see these controllers, in order:
+//! * `Logitech Keyboard`, with one LED per [`KeyName`](crate::KeyName) in [`KeyName::iter`](crate::KeyName::iter) order,
+//!   in a matrix zone laid out like a [`KeyboardBitmap`](crate::KeyboardBitmap).
+//! * `Logitech <device> Zones` for each [`DeviceType`](crate::DeviceType), with one single-LED zone per sdk zone.
+//!
+//! Each controller has one `Direct` mode.
+//! `UpdateLEDs`, `UpdateZoneLEDs` and `UpdateSingleLED` are sent to the sdk, and mode and profile requests are ignored.
+//! Call [`Server::listen`] with a listener, usually on [`DEFAULT_PORT`], then [`Server::run`] on the thread that owns the sdk.
+
+mod controller;
+mod packet;
+mod server;
+
+pub use self::server::Server;
+
+/// The port that OpenRGB servers listen on by default.
+pub const DEFAULT_PORT: u16 = 6742;
+
+/// The newest OpenRGB SDK protocol version that the server speaks.
+pub const PROTOCOL_VERSION: u32 = 3;
@@ -0,0 +1,361 @@
+use super::packet::DataWriter;
+use crate::ColorPercent;
+use crate::DeviceType;
+use crate::Error;
+use crate::KeyName;
+use crate::KeyboardBitmap;
+use crate::Rgb8;
+use crate::Sdk;
+use crate::BITMAP_HEIGHT;
+use crate::BITMAP_WIDTH;
+use std::ops::Range;
+
+/// OpenRGB device types.
+const DEVICE_TYPE_KEYBOARD: i32 = 5;
+const DEVICE_TYPE_MOUSE: i32 = 6;
+const DEVICE_TYPE_MOUSEMAT: i32 = 7;
+const DEVICE_TYPE_HEADSET: i32 = 8;
+const DEVICE_TYPE_SPEAKER: i32 = 12;
+
+/// OpenRGB zone types.
+const ZONE_TYPE_SINGLE: i32 = 0;
+const ZONE_TYPE_MATRIX: i32 = 2;
+
+/// The mode flag for modes that set the color of each LED.
+const MODE_FLAG_HAS_PER_LED_COLOR: u32 = 1 << 5;
+
+/// The mode color mode for modes that set the color of each LED.
+const MODE_COLORS_PER_LED: u32 = 1;
+
+/// The value of matrix map cells without an LED.
+const NO_LED: u32 = u32::MAX;
+
+/// What a controller sends its colors to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Target {
+    /// Every key of per-key keyboards, in [`KeyName::iter`] order.
+    Keys,
+
+    /// The zones of a device type.
+    Zones(DeviceType),
+}
+
+/// A zone of a controller.
+#[derive(Debug, Clone)]
+struct Zone {
+    name: String,
+    zone_type: i32,
+    leds: Range<usize>,
+    /// The LED index of each cell, in row-major order, with the width and height.
+    matrix: Option<(u32, u32, Vec<u32>)>,
+}
+
+/// A device as OpenRGB clients see it.
+#[derive(Debug, Clone)]
+pub(crate) struct Controller {
+    name: String,
+    device_type: i32,
+    target: Target,
+    zones: Vec<Zone>,
+    leds: Vec<String>,
+    colors: Vec<Rgb8>,
+}
+
+impl Controller {
+    /// Make the controllers for every device, starting with the per-key keyboard.
+    pub(crate) fn all() -> Vec<Self> {
+        std::iter::once(Self::keyboard())
+            .chain(DeviceType::iter().map(Self::zones))
+            .collect()
+    }
+
+    /// Make a controller with one LED per key, in a matrix zone laid out like a [`KeyboardBitmap`].
+    fn keyboard() -> Self {
+        let keys: Vec<KeyName> = KeyName::iter().collect();
+
+        let mut map = vec![NO_LED; BITMAP_WIDTH * BITMAP_HEIGHT];
+        for (led, key) in keys.iter().enumerate() {
+            if let Some((x, y)) = KeyboardBitmap::key_position(*key) {
+                map[y * BITMAP_WIDTH + x] = led as u32;
+            }
+        }
+
+        Self {
+            name: "Logitech Keyboard".into(),
+            device_type: DEVICE_TYPE_KEYBOARD,
+            target: Target::Keys,
+            zones: vec![Zone {
+                name: "Keyboard".into(),
+                zone_type: ZONE_TYPE_MATRIX,
+                leds: 0..keys.len(),
+                matrix: Some((BITMAP_WIDTH as u32, BITMAP_HEIGHT as u32, map)),
+            }],
+            leds: keys
+                .iter()
+                .map(|key| format!("Key: {}", key.name()))
+                .collect(),
+            colors: vec![Rgb8::default(); keys.len()],
+        }
+    }
+
+    /// Make a controller with one single-LED zone per zone of a device type.
+    fn zones(device_type: DeviceType) -> Self {
+        let zone_count = device_type.zone_count() as usize;
+        let zone_names: Vec<String> = (0..zone_count).map(|zone| format!("Zone {zone}")).collect();
+
+        Self {
+            name: format!("Logitech {} Zones", device_type.name()),
+            device_type: match device_type {
+                DeviceType::Keyboard => DEVICE_TYPE_KEYBOARD,
+                DeviceType::Mouse => DEVICE_TYPE_MOUSE,
+                DeviceType::Mousemat => DEVICE_TYPE_MOUSEMAT,
+                DeviceType::Headset => DEVICE_TYPE_HEADSET,
+                DeviceType::Speaker => DEVICE_TYPE_SPEAKER,
+            },
+            target: Target::Zones(device_type),
+            zones: zone_names
+                .iter()
+                .enumerate()
+                .map(|(zone, name)| Zone {
+                    name: name.clone(),
+                    zone_type: ZONE_TYPE_SINGLE,
+                    leds: zone..zone + 1,
+                    matrix: None,
+                })
+                .collect(),
+            leds: zone_names,
+            colors: vec![Rgb8::default(); zone_count],
+        }
+    }
+
+    /// Get the name of this controller.
+    #[cfg(test)]
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the current colors of the LEDs.
+    #[cfg(test)]
+    pub(crate) fn colors(&self) -> &[Rgb8] {
+        &self.colors
+    }
+
+    /// Encode the controller data for a protocol version.
+    pub(crate) fn encode(&self, protocol_version: u32) -> Vec<u8> {
+        let mut writer = DataWriter::new();
+        writer.i32(self.device_type);
+        writer.string(&self.name);
+        if protocol_version >= 1 {
+            writer.string("Logitech");
+        }
+        writer.string("Logitech LED SDK device");
+        writer.string(env!("CARGO_PKG_VERSION"));
+        writer.string("");
+        writer.string("Logitech LED SDK");
+
+        // Only a direct mode is offered, as hardware effects go through other sdk calls.
+        writer.count(1);
+        writer.i32(0);
+        writer.string("Direct");
+        writer.i32(0);
+        writer.u32(MODE_FLAG_HAS_PER_LED_COLOR);
+        writer.u32(0);
+        writer.u32(0);
+        if protocol_version >= 3 {
+            writer.u32(0);
+            writer.u32(0);
+        }
+        writer.u32(0);
+        writer.u32(0);
+        writer.u32(0);
+        if protocol_version >= 3 {
+            writer.u32(0);
+        }
+        writer.u32(0);
+        writer.u32(MODE_COLORS_PER_LED);
+        writer.count(0);
+
+        writer.count(self.zones.len());
+        for zone in self.zones.iter() {
+            let led_count = zone.leds.len() as u32;
+            writer.string(&zone.name);
+            writer.i32(zone.zone_type);
+            writer.u32(led_count);
+            writer.u32(led_count);
+            writer.u32(led_count);
+            match zone.matrix.as_ref() {
+                Some((width, height, map)) => {
+                    writer.count(8 + 4 * map.len());
+                    writer.u32(*height);
+                    writer.u32(*width);
+                    for led in map.iter() {
+                        writer.u32(*led);
+                    }
+                }
+                None => writer.count(0),
+            }
+        }
+
+        writer.count(self.leds.len());
+        for (value, led) in self.leds.iter().enumerate() {
+            writer.string(led);
+            writer.u32(value as u32);
+        }
+
+        writer.count(self.colors.len());
+        for color in self.colors.iter() {
+            writer.color(*color);
+        }
+
+        // The data starts with its own size, including the size field.
+        let data = writer.into_bytes();
+        let mut bytes = Vec::with_capacity(data.len() + 4);
+        bytes.extend_from_slice(&(data.len() as u32 + 4).to_le_bytes());
+        bytes.extend_from_slice(&data);
+        bytes
+    }
+
+    /// Set the colors of all LEDs, starting from the first.
+    ///
+    /// Extra colors are ignored.
+    ///
+    /// # Errors
+    /// Returns an error if an sdk call fails.
+    pub(crate) fn update_leds(&mut self, sdk: &Sdk, colors: &[Rgb8]) -> Result<(), Error> {
+        self.update(sdk, 0..self.colors.len(), colors)
+    }
+
+    /// Set the colors of the LEDs in a zone.
+    ///
+    /// Extra colors are ignored, and out of range zones are ignored.
+    ///
+    /// # Errors
+    /// Returns an error if an sdk call fails.
+    pub(crate) fn update_zone_leds(
+        &mut self,
+        sdk: &Sdk,
+        zone: usize,
+        colors: &[Rgb8],
+    ) -> Result<(), Error> {
+        match self.zones.get(zone) {
+            Some(zone) => self.update(sdk, zone.leds.clone(), colors),
+            None => Ok(()),
+        }
+    }
+
+    /// Set the color of one LED.
+    ///
+    /// Out of range LEDs are ignored.
+    ///
+    /// # Errors
+    /// Returns an error if an sdk call fails.
+    pub(crate) fn update_single_led(
+        &mut self,
+        sdk: &Sdk,
+        led: usize,
+        color: Rgb8,
+    ) -> Result<(), Error> {
+        if led >= self.colors.len() {
+            return Ok(());
+        }
+
+        self.update(sdk, led..led + 1, &[color])
+    }
+
+    /// Set the colors of a range of LEDs and send them to the sdk.
+    fn update(&mut self, sdk: &Sdk, leds: Range<usize>, colors: &[Rgb8]) -> Result<(), Error> {
+        let leds = leds.start..leds.end.min(leds.start + colors.len());
+        if leds.is_empty() {
+            return Ok(());
+        }
+        self.colors[leds.clone()].copy_from_slice(&colors[..leds.len()]);
+
+        match self.target {
+            Target::Keys => {
+                let keys = KeyName::iter().zip(self.colors.iter().copied());
+                if leds.len() == 1 {
+                    let (key, color) = keys.clone().nth(leds.start).expect("every led is a key");
+                    return sdk.set_lighting_for_key_with_name(key, color.into());
+                }
+
+                // A bitmap sets every key it has in one call, so only keys outside of it are sent one by one.
+                let mut bitmap = KeyboardBitmap::new();
+                for (key, color) in keys.clone() {
+                    bitmap.set_key(key, color.into());
+                }
+                sdk.set_lighting_from_bitmap(&bitmap)?;
+                for (key, color) in keys.skip(leds.start).take(leds.len()) {
+                    if KeyboardBitmap::key_position(key).is_none() {
+                        sdk.set_lighting_for_key_with_name(key, color.into())?;
+                    }
+                }
+            }
+            Target::Zones(device_type) => {
+                for led in leds {
+                    let color = ColorPercent::from(self.colors[led]);
+                    sdk.set_lighting_for_target_zone(device_type, led as u32, color)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::openrgb::packet::DataReader;
+
+    #[test]
+    fn encode() {
+        let controllers = Controller::all();
+        let names: Vec<&str> = controllers.iter().map(|c| c.name()).collect();
+        assert_eq!(
+            names,
+            [
+                "Logitech Keyboard",
+                "Logitech Keyboard Zones",
+                "Logitech Mouse Zones",
+                "Logitech Mousemat Zones",
+                "Logitech Headset Zones",
+                "Logitech Speaker Zones",
+            ]
+        );
+
+        let keyboard = &controllers[0];
+        let data = keyboard.encode(3);
+        let mut reader = DataReader::new(&data);
+        assert_eq!(reader.u32(), Some(data.len() as u32));
+        assert_eq!(reader.u32(), Some(DEVICE_TYPE_KEYBOARD as u32));
+        assert_eq!(
+            reader.string().expect("missing string"),
+            "Logitech Keyboard"
+        );
+        assert_eq!(reader.string().expect("missing string"), "Logitech");
+
+        // Older protocols do not have a vendor.
+        let data = keyboard.encode(0);
+        let mut reader = DataReader::new(&data);
+        reader.u32();
+        reader.u32();
+        assert_eq!(
+            reader.string().expect("missing string"),
+            "Logitech Keyboard"
+        );
+        assert_eq!(
+            reader.string().expect("missing string"),
+            "Logitech LED SDK device"
+        );
+
+        // Every controller ends with one color per LED.
+        for controller in controllers.iter() {
+            let data = controller.encode(3);
+            let colors = controller.colors().len();
+            assert_eq!(
+                data[data.len() - colors * 4 - 2..data.len() - colors * 4],
+                (colors as u16).to_le_bytes()
+            );
+        }
+    }
+}
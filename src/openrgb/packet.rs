@@ -0,0 +1,210 @@
+use crate::Rgb8;
+use std::io::Read;
+use std::io::Write;
+
+/// The bytes that start every packet.
+const MAGIC: [u8; 4] = *b"ORGB";
+
+/// The size of a packet header.
+const HEADER_SIZE: usize = 16;
+
+/// The largest packet that is accepted, to avoid allocating huge buffers for garbage input.
+const MAX_PACKET_SIZE: u32 = 1 << 20;
+
+pub(crate) const REQUEST_CONTROLLER_COUNT: u32 = 0;
+pub(crate) const REQUEST_CONTROLLER_DATA: u32 = 1;
+pub(crate) const REQUEST_PROTOCOL_VERSION: u32 = 40;
+pub(crate) const SET_CLIENT_NAME: u32 = 50;
+pub(crate) const RGBCONTROLLER_UPDATELEDS: u32 = 1050;
+pub(crate) const RGBCONTROLLER_UPDATEZONELEDS: u32 = 1051;
+pub(crate) const RGBCONTROLLER_UPDATESINGLELED: u32 = 1052;
+
+/// A packet of the OpenRGB SDK protocol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Packet {
+    /// The index of the controller that the packet is about.
+    pub(crate) device: u32,
+
+    /// The kind of packet.
+    pub(crate) id: u32,
+
+    /// The data after the header.
+    pub(crate) data: Vec<u8>,
+}
+
+impl Packet {
+    /// Make a new packet.
+    pub(crate) fn new(device: u32, id: u32, data: Vec<u8>) -> Self {
+        Self { device, id, data }
+    }
+
+    /// Read a packet.
+    ///
+    /// # Returns
+    /// Returns None if the stream ended before the packet started.
+    ///
+    /// # Errors
+    /// Returns an error if reading failed, or the header is invalid.
+    pub(crate) fn read_from<R>(reader: &mut R) -> std::io::Result<Option<Self>>
+    where
+        R: Read,
+    {
+        let mut header = [0; HEADER_SIZE];
+        let mut filled = 0;
+        while filled < HEADER_SIZE {
+            match reader.read(&mut header[filled..])? {
+                0 if filled == 0 => return Ok(None),
+                0 => return Err(std::io::ErrorKind::UnexpectedEof.into()),
+                n => filled += n,
+            }
+        }
+
+        if header[..4] != MAGIC {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "invalid packet magic",
+            ));
+        }
+        let field = |offset: usize| {
+            u32::from_le_bytes(
+                header[offset..offset + 4]
+                    .try_into()
+                    .expect("header fields are 4 bytes"),
+            )
+        };
+        let device = field(4);
+        let id = field(8);
+        let size = field(12);
+        if size > MAX_PACKET_SIZE {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "packet too large",
+            ));
+        }
+
+        let mut data = vec![0; size as usize];
+        reader.read_exact(&mut data)?;
+
+        Ok(Some(Self { device, id, data }))
+    }
+
+    /// Write this packet.
+    ///
+    /// # Errors
+    /// Returns an error if writing failed.
+    pub(crate) fn write_to<W>(&self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: Write,
+    {
+        let size = u32::try_from(self.data.len()).map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "packet too large")
+        })?;
+
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + self.data.len());
+        bytes.extend_from_slice(&MAGIC);
+        bytes.extend_from_slice(&self.device.to_le_bytes());
+        bytes.extend_from_slice(&self.id.to_le_bytes());
+        bytes.extend_from_slice(&size.to_le_bytes());
+        bytes.extend_from_slice(&self.data);
+        writer.write_all(&bytes)?;
+        writer.flush()
+    }
+}
+
+/// Builds the little-endian data of a packet.
+#[derive(Debug, Default)]
+pub(crate) struct DataWriter {
+    bytes: Vec<u8>,
+}
+
+impl DataWriter {
+    /// Make an empty writer.
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn u16(&mut self, value: u16) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn i32(&mut self, value: i32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Write a count, which the protocol stores in 16 bits.
+    pub(crate) fn count(&mut self, count: usize) {
+        self.u16(u16::try_from(count).expect("count does not fit in 16 bits"));
+    }
+
+    /// Write a length-prefixed, nul-terminated string.
+    pub(crate) fn string(&mut self, value: &str) {
+        self.count(value.len() + 1);
+        self.bytes.extend_from_slice(value.as_bytes());
+        self.bytes.push(0);
+    }
+
+    /// Write a color, stored as red, green, blue and a padding byte.
+    pub(crate) fn color(&mut self, color: Rgb8) {
+        self.bytes
+            .extend_from_slice(&[color.r, color.g, color.b, 0]);
+    }
+
+    /// Get the data.
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Reads the little-endian data of a packet.
+#[derive(Debug)]
+pub(crate) struct DataReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> DataReader<'a> {
+    /// Make a reader over packet data.
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let (value, rest) = self.bytes.split_first_chunk::<N>()?;
+        self.bytes = rest;
+        Some(*value)
+    }
+
+    pub(crate) fn u16(&mut self) -> Option<u16> {
+        self.take().map(u16::from_le_bytes)
+    }
+
+    pub(crate) fn u32(&mut self) -> Option<u32> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    pub(crate) fn color(&mut self) -> Option<Rgb8> {
+        self.take::<4>().map(|[r, g, b, _]| Rgb8::new(r, g, b))
+    }
+
+    /// Read a length-prefixed, nul-terminated string.
+    #[cfg(test)]
+    pub(crate) fn string(&mut self) -> Option<String> {
+        let len = usize::from(self.u16()?);
+        if len == 0 || self.bytes.len() < len {
+            return None;
+        }
+        let (value, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+
+        String::from_utf8(value.strip_suffix(&[0])?.to_vec()).ok()
+    }
+
+    /// Read a 16 bit count, followed by that many colors.
+    pub(crate) fn colors(&mut self) -> Option<Vec<Rgb8>> {
+        let count = self.u16()?;
+        (0..count).map(|_| self.color()).collect()
+    }
+}
@@ -0,0 +1,388 @@
+use super::controller::Controller;
+use super::packet;
+use super::packet::DataReader;
+use super::packet::DataWriter;
+use super::packet::Packet;
+use super::PROTOCOL_VERSION;
+use crate::Error;
+use crate::Sdk;
+use crate::StopHandle;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often the server checks whether it was stopped.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A packet from a connection, with a channel for the response.
+struct Request {
+    packet: Packet,
+    reply: mpsc::Sender<Option<Packet>>,
+}
+
+/// A server for the OpenRGB SDK protocol, which sends the LED updates of its clients to an [`Sdk`].
+///
+/// Connections are read on background threads, but every sdk call is made by the thread that calls [`Server::run`].
+pub struct Server {
+    controllers: Vec<Controller>,
+    sender: mpsc::Sender<Request>,
+    receiver: mpsc::Receiver<Request>,
+    stop_requested: Arc<AtomicBool>,
+}
+
+impl Server {
+    /// Create a new server with a controller for each device.
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+
+        Self {
+            controllers: Controller::all(),
+            sender,
+            receiver,
+            stop_requested: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Get a handle that can stop this server from another thread.
+    pub fn stop_handle(&self) -> StopHandle {
+        StopHandle::new(self.stop_requested.clone())
+    }
+
+    /// Accept clients from a listener on a background thread.
+    ///
+    /// The thread exits when the server is stopped.
+    ///
+    /// # Errors
+    /// Returns an error if the listener could not be made non-blocking.
+    pub fn listen(&self, listener: TcpListener) -> std::io::Result<()> {
+        listener.set_nonblocking(true)?;
+
+        let sender = self.sender.clone();
+        let stop_requested = self.stop_requested.clone();
+        std::thread::spawn(move || {
+            while !stop_requested.load(Ordering::SeqCst) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        let sender = sender.clone();
+                        std::thread::spawn(move || {
+                            let _ = serve_connection(stream, sender);
+                        });
+                    }
+                    Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => {
+                        std::thread::sleep(POLL_INTERVAL);
+                    }
+                    Err(_) => {}
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Handle client requests until stopped with a [`StopHandle`].
+    ///
+    /// If an sdk call fails, the connection of the client that sent the packet is closed,
+    /// and other clients are still served.
+    pub fn run(&mut self, sdk: &Sdk) {
+        while !self.stop_requested.load(Ordering::SeqCst) {
+            if let Ok(request) = self.receiver.recv_timeout(POLL_INTERVAL) {
+                // Dropping the reply without a response closes the connection.
+                if let Ok(response) = self.handle_packet(sdk, &request.packet) {
+                    // The connection may have closed while waiting.
+                    let _ = request.reply.send(response);
+                }
+            }
+        }
+    }
+
+    /// Handle a packet, returning the response if it has one.
+    ///
+    /// Malformed packets and packets for missing controllers are ignored.
+    fn handle_packet(&mut self, sdk: &Sdk, packet: &Packet) -> Result<Option<Packet>, Error> {
+        let mut reader = DataReader::new(&packet.data);
+        let controller = self.controllers.get_mut(packet.device as usize);
+
+        match (packet.id, controller) {
+            (packet::REQUEST_CONTROLLER_COUNT, _) => {
+                let mut writer = DataWriter::new();
+                writer.u32(self.controllers.len() as u32);
+                return Ok(Some(Packet::new(0, packet.id, writer.into_bytes())));
+            }
+            (packet::REQUEST_CONTROLLER_DATA, Some(controller)) => {
+                // Clients older than version 1 send no version.
+                let protocol_version = reader.u32().unwrap_or(0).min(PROTOCOL_VERSION);
+                let data = controller.encode(protocol_version);
+                return Ok(Some(Packet::new(packet.device, packet.id, data)));
+            }
+            (packet::REQUEST_PROTOCOL_VERSION, _) => {
+                let mut writer = DataWriter::new();
+                writer.u32(PROTOCOL_VERSION);
+                return Ok(Some(Packet::new(0, packet.id, writer.into_bytes())));
+            }
+            (packet::RGBCONTROLLER_UPDATELEDS, Some(controller)) => {
+                let _size = reader.u32();
+                if let Some(colors) = reader.colors() {
+                    controller.update_leds(sdk, &colors)?;
+                }
+            }
+            (packet::RGBCONTROLLER_UPDATEZONELEDS, Some(controller)) => {
+                let _size = reader.u32();
+                if let (Some(zone), Some(colors)) = (reader.u32(), reader.colors()) {
+                    controller.update_zone_leds(sdk, zone as usize, &colors)?;
+                }
+            }
+            (packet::RGBCONTROLLER_UPDATESINGLELED, Some(controller)) => {
+                if let (Some(led), Some(color)) = (reader.u32(), reader.color()) {
+                    controller.update_single_led(sdk, led as usize, color)?;
+                }
+            }
+            // Client names are only shown by the OpenRGB server UI.
+            (packet::SET_CLIENT_NAME, _) => {}
+            // There is only one mode, and profiles are not supported.
+            _ => {}
+        }
+
+        Ok(None)
+    }
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Forward the packets of a connection to the server, and write back the responses.
+fn serve_connection(mut stream: TcpStream, sender: mpsc::Sender<Request>) -> std::io::Result<()> {
+    // Accepted streams may inherit non-blocking mode from the listener.
+    stream.set_nonblocking(false)?;
+    let mut reader = stream.try_clone()?;
+
+    while let Some(packet) = Packet::read_from(&mut reader)? {
+        let (reply, response) = mpsc::channel();
+        if sender.send(Request { packet, reply }).is_err() {
+            break;
+        }
+
+        match response.recv() {
+            Ok(Some(response)) => response.write_to(&mut stream)?,
+            Ok(None) => {}
+            Err(_) => break,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ColorPercent;
+    use crate::DeviceType;
+    use crate::KeyName;
+    use crate::MockBackend;
+    use crate::Rgb8;
+    use std::time::Instant;
+
+    /// Run a server on a new thread, with its own sdk.
+    fn spawn(
+        backend: MockBackend,
+    ) -> (
+        std::net::SocketAddr,
+        StopHandle,
+        std::thread::JoinHandle<Result<(), Error>>,
+    ) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind");
+        let address = listener.local_addr().expect("failed to get address");
+        let mut server = Server::new();
+        server.listen(listener).expect("failed to listen");
+        let stop_handle = server.stop_handle();
+
+        let thread = std::thread::spawn(move || {
+            let sdk = Sdk::new_with_backend(backend)?;
+            server.run(&sdk);
+            Ok(())
+        });
+
+        (address, stop_handle, thread)
+    }
+
+    /// Send a packet and read the response.
+    fn request(stream: &mut TcpStream, device: u32, id: u32, data: Vec<u8>) -> Packet {
+        Packet::new(device, id, data)
+            .write_to(stream)
+            .expect("failed to write packet");
+        Packet::read_from(stream)
+            .expect("failed to read packet")
+            .expect("connection closed")
+    }
+
+    /// Wait for a condition that the server makes true after handling a packet.
+    fn wait_for<F>(mut condition: F)
+    where
+        F: FnMut() -> bool,
+    {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !condition() {
+            assert!(
+                Instant::now() < deadline,
+                "timed out waiting for the server"
+            );
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn client() {
+        let backend = MockBackend::new();
+        let (address, stop_handle, thread) = spawn(backend.clone());
+        let mut stream = TcpStream::connect(address).expect("failed to connect");
+
+        let mut data = DataWriter::new();
+        data.u32(4);
+        let response = request(
+            &mut stream,
+            0,
+            packet::REQUEST_PROTOCOL_VERSION,
+            data.into_bytes(),
+        );
+        assert_eq!(response.data, PROTOCOL_VERSION.to_le_bytes());
+
+        let response = request(&mut stream, 0, packet::REQUEST_CONTROLLER_COUNT, Vec::new());
+        assert_eq!(response.data, 6_u32.to_le_bytes());
+
+        let mut data = DataWriter::new();
+        data.u32(PROTOCOL_VERSION);
+        let response = request(
+            &mut stream,
+            2,
+            packet::REQUEST_CONTROLLER_DATA,
+            data.into_bytes(),
+        );
+        assert_eq!(response.device, 2);
+        let mut reader = DataReader::new(&response.data);
+        assert_eq!(reader.u32(), Some(response.data.len() as u32));
+        reader.u32();
+        assert_eq!(reader.string().as_deref(), Some("Logitech Mouse Zones"));
+
+        // Every key of the keyboard.
+        let key_count = KeyName::iter().count();
+        let mut data = DataWriter::new();
+        data.u32(0);
+        data.count(key_count);
+        for _ in 0..key_count {
+            data.color(Rgb8::new(0, 0, 255));
+        }
+        Packet::new(0, packet::RGBCONTROLLER_UPDATELEDS, data.into_bytes())
+            .write_to(&mut stream)
+            .expect("failed to write packet");
+        wait_for(|| KeyName::iter().all(|key| backend.key_color(key) == Some(ColorPercent::BLUE)));
+
+        // One key.
+        let w = KeyName::iter()
+            .position(|key| key == KeyName::W)
+            .expect("missing key") as u32;
+        let mut data = DataWriter::new();
+        data.u32(w);
+        data.color(Rgb8::new(255, 0, 0));
+        Packet::new(0, packet::RGBCONTROLLER_UPDATESINGLELED, data.into_bytes())
+            .write_to(&mut stream)
+            .expect("failed to write packet");
+        wait_for(|| backend.key_color(KeyName::W) == Some(ColorPercent::RED));
+        assert_eq!(backend.key_color(KeyName::A), Some(ColorPercent::BLUE));
+
+        // One zone of the mouse.
+        let mut data = DataWriter::new();
+        data.u32(0);
+        data.u32(1);
+        data.count(1);
+        data.color(Rgb8::new(255, 255, 255));
+        Packet::new(2, packet::RGBCONTROLLER_UPDATEZONELEDS, data.into_bytes())
+            .write_to(&mut stream)
+            .expect("failed to write packet");
+        wait_for(|| backend.zone_color(DeviceType::Mouse, 1) == Some(ColorPercent::WHITE));
+
+        // The colors are reported back.
+        let mut data = DataWriter::new();
+        data.u32(PROTOCOL_VERSION);
+        let response = request(
+            &mut stream,
+            2,
+            packet::REQUEST_CONTROLLER_DATA,
+            data.into_bytes(),
+        );
+        let colors = &response.data[response.data.len() - 3 * 4..];
+        assert_eq!(colors, [0, 0, 0, 0, 255, 255, 255, 0, 0, 0, 0, 0]);
+
+        stop_handle.stop();
+        thread
+            .join()
+            .expect("server thread panicked")
+            .expect("server failed");
+    }
+
+    #[test]
+    fn invalid_packets() {
+        let backend = MockBackend::new();
+        let (address, stop_handle, thread) = spawn(backend.clone());
+
+        // Garbage closes the connection.
+        let mut stream = TcpStream::connect(address).expect("failed to connect");
+        std::io::Write::write_all(&mut stream, b"GET / HTTP/1.1\r\n\r\n").expect("failed to write");
+        assert!(matches!(Packet::read_from(&mut stream), Ok(None) | Err(_)));
+
+        // Missing controllers and truncated data are ignored.
+        let mut stream = TcpStream::connect(address).expect("failed to connect");
+        Packet::new(100, packet::RGBCONTROLLER_UPDATESINGLELED, vec![0; 8])
+            .write_to(&mut stream)
+            .expect("failed to write packet");
+        Packet::new(0, packet::RGBCONTROLLER_UPDATELEDS, vec![0; 3])
+            .write_to(&mut stream)
+            .expect("failed to write packet");
+        let response = request(&mut stream, 0, packet::REQUEST_CONTROLLER_COUNT, Vec::new());
+        assert_eq!(response.data, 6_u32.to_le_bytes());
+        assert!(backend
+            .take_calls()
+            .iter()
+            .all(|call| !call.function().starts_with("LogiLedSetLighting")));
+
+        stop_handle.stop();
+        thread
+            .join()
+            .expect("server thread panicked")
+            .expect("server failed");
+    }
+
+    #[test]
+    fn failing_sdk() {
+        let backend = MockBackend::new();
+        let (address, stop_handle, thread) = spawn(backend.clone());
+
+        // A failed sdk call closes the connection of the client that sent it.
+        backend.set_failing("LogiLedSetLightingForTargetZone", true);
+        let mut stream = TcpStream::connect(address).expect("failed to connect");
+        let mut data = DataWriter::new();
+        data.u32(0);
+        data.color(Rgb8::new(255, 0, 0));
+        Packet::new(2, packet::RGBCONTROLLER_UPDATESINGLELED, data.into_bytes())
+            .write_to(&mut stream)
+            .expect("failed to write packet");
+        assert!(matches!(Packet::read_from(&mut stream), Ok(None) | Err(_)));
+
+        // Other clients are still served.
+        backend.set_failing("LogiLedSetLightingForTargetZone", false);
+        let mut stream = TcpStream::connect(address).expect("failed to connect");
+        let response = request(&mut stream, 0, packet::REQUEST_CONTROLLER_COUNT, Vec::new());
+        assert_eq!(response.data, 6_u32.to_le_bytes());
+
+        stop_handle.stop();
+        thread
+            .join()
+            .expect("server thread panicked")
+            .expect("server failed");
+    }
+}
@@ -0,0 +1,31 @@
+/// An error that may occur while parsing a color.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParseColorError {
+    input: String,
+}
+
+impl ParseColorError {
+    /// Create a new error for an input that could not be parsed.
+    pub(crate) fn new(input: &str) -> Self {
+        Self {
+            input: input.into(),
+        }
+    }
+
+    /// Get the input that could not be parsed.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+}
+
+impl std::fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "\"{}\" is not a \"#rrggbb\" or \"#rgb\" hex color or a CSS color name",
+            self.input
+        )
+    }
+}
+
+impl std::error::Error for ParseColorError {}
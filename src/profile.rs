@@ -0,0 +1,717 @@
+use crate::ColorPercent;
+use crate::DeviceType;
+use crate::Error;
+use crate::KeyName;
+use crate::ProfileError;
+use crate::Sdk;
+use crate::TargetDevice;
+use serde::de::DeserializeSeed;
+use serde::de::MapAccess;
+use serde::de::Visitor;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+/// A lighting profile, loaded from a TOML or JSON file.
+///
+/// Every field is optional.
+/// Colors are written as `"#rrggbb"` or `"#rgb"` hex colors, CSS color names, or `{ r, g, b }` percentages.
+/// Keys use the names of [`KeyName`], like `W` or `NUM_ENTER`, and zones are numbered from 0.
+///
+/// ```toml
+/// # The devices to set, from Monochrome, PerKeyRgb, Rgb and All. Defaults to all of them.
+/// target = ["PerKeyRgb", "Rgb"]
+///
+/// # The color of every device.
+/// base = "#102030"
+///
+/// # The colors of single keys.
+/// [keys]
+/// W = "red"
+/// A = "red"
+/// S = "red"
+/// D = "red"
+/// NUM_ENTER = { r = 0, g = 50, b = 100 }
+///
+/// # The colors of zones, by device type.
+/// [zones.Mouse]
+/// 0 = "#ff8800"
+/// 1 = "white"
+///
+/// # Effects started by the sdk, in order.
+/// [[effects]]
+/// type = "pulse_key"
+/// key = "ESC"
+/// from = "black"
+/// to = "red"
+/// duration_ms = 1000
+/// infinite = true
+/// ```
+///
+/// The same profile as JSON looks like `{ "target": ["PerKeyRgb", "Rgb"], "base": "#102030", "keys": { "W": "red" }, ... }`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// The devices to set.
+    #[serde(default = "default_target")]
+    pub target: TargetDevice,
+
+    /// The color of every device.
+    #[serde(default)]
+    pub base: Option<ColorPercent>,
+
+    /// The colors of single keys.
+    #[serde(default, deserialize_with = "deserialize_keys")]
+    pub keys: HashMap<KeyName, ColorPercent>,
+
+    /// The colors of zones, by device type.
+    #[serde(default, deserialize_with = "deserialize_zones")]
+    pub zones: HashMap<DeviceType, BTreeMap<u32, ColorPercent>>,
+
+    /// Effects started by the sdk, in order.
+    #[serde(default)]
+    pub effects: Vec<ProfileEffect>,
+}
+
+impl Profile {
+    /// Create a new, empty profile that targets every device.
+    pub fn new() -> Self {
+        Self {
+            target: TargetDevice::All,
+            base: None,
+            keys: HashMap::new(),
+            zones: HashMap::new(),
+            effects: Vec::new(),
+        }
+    }
+
+    /// Load a profile from a `.toml` or `.json` file.
+    ///
+    /// # Errors
+    /// Returns an error if the file could not be read, has another extension, or is not a valid profile.
+    pub fn load<P>(path: P) -> Result<Self, ProfileError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+        let parse = match extension.as_deref() {
+            Some("toml") => Self::from_toml_str,
+            Some("json") => Self::from_json_str,
+            _ => {
+                return Err(ProfileError::UnknownFormat { path: path.into() });
+            }
+        };
+
+        let input = std::fs::read_to_string(path).map_err(|error| ProfileError::Io {
+            path: path.into(),
+            error,
+        })?;
+
+        parse(&input).map_err(|error| error.with_path(path.into()))
+    }
+
+    /// Parse a profile from TOML.
+    ///
+    /// # Errors
+    /// Returns an error if the input is not a valid profile.
+    pub fn from_toml_str(input: &str) -> Result<Self, ProfileError> {
+        let table = toml::de::DeTable::parse(input)
+            .map_err(|error| ProfileError::from_toml(error, input))?;
+
+        // Serde errors inside a table point at the table, so check zones here to point at the bad zone.
+        check_toml_zones(table.get_ref())
+            .map_err(|(offset, message)| ProfileError::from_toml_offset(offset, message, input))?;
+
+        Self::deserialize(toml::de::Deserializer::from(table))
+            .map_err(|error| ProfileError::from_toml(error, input))
+    }
+
+    /// Parse a profile from JSON.
+    ///
+    /// # Errors
+    /// Returns an error if the input is not a valid profile.
+    pub fn from_json_str(input: &str) -> Result<Self, ProfileError> {
+        serde_json::from_str(input).map_err(ProfileError::from_json)
+    }
+
+    /// Apply this profile.
+    ///
+    /// This sets the target devices, the base color, the keys in [`KeyName::iter`] order,
+    /// the zones in [`DeviceType::iter`] order, and then starts the effects.
+    ///
+    /// # Errors
+    /// Returns an error if a zone is out of range for its device type, before anything is sent.
+    /// Returns an error if any of the calls fail.
+    pub fn apply(&self, sdk: &Sdk) -> Result<(), Error> {
+        for (device, zones) in self.zones.iter() {
+            if let Some(zone) = zones.keys().find(|zone| **zone >= device.zone_count()) {
+                return Err(Error::InvalidZone {
+                    device: *device,
+                    zone: *zone,
+                });
+            }
+        }
+
+        sdk.set_target(self.target)?;
+
+        if let Some(base) = self.base {
+            sdk.set_lighting(base)?;
+        }
+
+        for key in KeyName::iter() {
+            if let Some(color) = self.keys.get(&key) {
+                sdk.set_lighting_for_key_with_name(key, *color)?;
+            }
+        }
+
+        for device in DeviceType::iter() {
+            for (zone, color) in self.zones.get(&device).into_iter().flatten() {
+                sdk.set_lighting_for_target_zone(device, *zone, *color)?;
+            }
+        }
+
+        for effect in self.effects.iter() {
+            effect.apply(sdk)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An effect started by the sdk when a [`Profile`] is applied.
+///
+/// In a file, the `type` field selects the effect, like `type = "flash"`.
+/// Times are in milliseconds.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ProfileEffect {
+    /// Flash every device, like [`Sdk::flash_lighting`].
+    Flash {
+        /// The color.
+        color: ColorPercent,
+
+        /// How long to flash for, or forever if missing.
+        #[serde(default)]
+        duration_ms: Option<u64>,
+
+        /// The time between flashes.
+        interval_ms: u64,
+    },
+
+    /// Pulse every device, like [`Sdk::pulse_lighting`].
+    Pulse {
+        /// The color.
+        color: ColorPercent,
+
+        /// How long to pulse for, or forever if missing.
+        #[serde(default)]
+        duration_ms: Option<u64>,
+
+        /// The time between pulses.
+        interval_ms: u64,
+    },
+
+    /// Flash a key, like [`Sdk::flash_single_key`].
+    FlashKey {
+        /// The key.
+        key: KeyName,
+
+        /// The color.
+        color: ColorPercent,
+
+        /// How long to flash for, or forever if missing.
+        #[serde(default)]
+        duration_ms: Option<u64>,
+
+        /// The time between flashes.
+        interval_ms: u64,
+    },
+
+    /// Pulse a key between two colors, like [`Sdk::pulse_single_key`].
+    PulseKey {
+        /// The key.
+        key: KeyName,
+
+        /// The starting color.
+        from: ColorPercent,
+
+        /// The ending color.
+        to: ColorPercent,
+
+        /// How long one pulse takes.
+        duration_ms: u64,
+
+        /// Whether to keep pulsing forever.
+        #[serde(default)]
+        infinite: bool,
+    },
+}
+
+impl ProfileEffect {
+    /// Start this effect.
+    ///
+    /// # Errors
+    /// Returns an error if the call fails or any of the times are too large.
+    pub fn apply(&self, sdk: &Sdk) -> Result<(), Error> {
+        match *self {
+            Self::Flash {
+                color,
+                duration_ms,
+                interval_ms,
+            } => sdk.flash_lighting(
+                color,
+                duration_ms.map(Duration::from_millis),
+                Duration::from_millis(interval_ms),
+            ),
+            Self::Pulse {
+                color,
+                duration_ms,
+                interval_ms,
+            } => sdk.pulse_lighting(
+                color,
+                duration_ms.map(Duration::from_millis),
+                Duration::from_millis(interval_ms),
+            ),
+            Self::FlashKey {
+                key,
+                color,
+                duration_ms,
+                interval_ms,
+            } => sdk.flash_single_key(
+                key,
+                color,
+                duration_ms.map(Duration::from_millis),
+                Duration::from_millis(interval_ms),
+            ),
+            Self::PulseKey {
+                key,
+                from,
+                to,
+                duration_ms,
+                infinite,
+            } => sdk.pulse_single_key(key, from, to, Duration::from_millis(duration_ms), infinite),
+        }
+    }
+}
+
+fn default_target() -> TargetDevice {
+    TargetDevice::All
+}
+
+/// Parse a zone of a device type, checking it against the number of zones of the device type.
+///
+/// # Errors
+/// Returns a message describing the problem if the zone is not valid.
+fn parse_zone(device: DeviceType, zone: &str) -> Result<u32, String> {
+    let zone_count = device.zone_count();
+    zone.parse::<u32>()
+        .ok()
+        .filter(|zone| *zone < zone_count)
+        .ok_or_else(|| {
+            format!(
+                "invalid zone `{zone}` for the {} device type, expected a zone from 0 to {}",
+                device.name(),
+                zone_count - 1
+            )
+        })
+}
+
+/// Check the zones of a parsed TOML profile.
+///
+/// Anything other than a bad zone is left for deserialization to report.
+///
+/// # Errors
+/// Returns the byte offset of the first bad zone and a message describing the problem.
+fn check_toml_zones(table: &toml::de::DeTable<'_>) -> Result<(), (usize, String)> {
+    let Some((_, zones)) = table.iter().find(|(key, _)| key.get_ref() == "zones") else {
+        return Ok(());
+    };
+    let Some(zones) = zones.get_ref().as_table() else {
+        return Ok(());
+    };
+
+    for (device, device_zones) in zones.iter() {
+        let Some(device) = DeviceType::from_name(device.get_ref()) else {
+            continue;
+        };
+        let Some(device_zones) = device_zones.get_ref().as_table() else {
+            continue;
+        };
+
+        for (zone, _) in device_zones.iter() {
+            parse_zone(device, zone.get_ref()).map_err(|message| (zone.span().start, message))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Deserialize the colors of keys, rejecting keys that appear more than once like TOML does.
+fn deserialize_keys<'de, D>(deserializer: D) -> Result<HashMap<KeyName, ColorPercent>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct KeysVisitor;
+
+    impl<'de> Visitor<'de> for KeysVisitor {
+        type Value = HashMap<KeyName, ColorPercent>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("a map of key names to colors")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut keys = HashMap::new();
+            while let Some(key) = map.next_key::<KeyName>()? {
+                if keys.contains_key(&key) {
+                    return Err(serde::de::Error::custom(format_args!(
+                        "duplicate key `{}`",
+                        key.name()
+                    )));
+                }
+
+                keys.insert(key, map.next_value()?);
+            }
+
+            Ok(keys)
+        }
+    }
+
+    deserializer.deserialize_map(KeysVisitor)
+}
+
+/// Deserialize zones, checking each zone against the number of zones of its device type,
+/// so that errors point at the bad zone.
+fn deserialize_zones<'de, D>(
+    deserializer: D,
+) -> Result<HashMap<DeviceType, BTreeMap<u32, ColorPercent>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct ZonesVisitor;
+
+    impl<'de> Visitor<'de> for ZonesVisitor {
+        type Value = HashMap<DeviceType, BTreeMap<u32, ColorPercent>>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("a map of device type names to zones")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut devices = HashMap::new();
+            while let Some(device) = map.next_key::<DeviceType>()? {
+                if devices.contains_key(&device) {
+                    return Err(serde::de::Error::custom(format_args!(
+                        "duplicate device type `{}`",
+                        device.name()
+                    )));
+                }
+
+                let zones = map.next_value_seed(DeviceZones { device })?;
+                devices.insert(device, zones);
+            }
+
+            Ok(devices)
+        }
+    }
+
+    deserializer.deserialize_map(ZonesVisitor)
+}
+
+/// The zones of one device type.
+struct DeviceZones {
+    device: DeviceType,
+}
+
+impl<'de> DeserializeSeed<'de> for DeviceZones {
+    type Value = BTreeMap<u32, ColorPercent>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for DeviceZones {
+    type Value = BTreeMap<u32, ColorPercent>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("a map of zones to colors")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut zones = BTreeMap::new();
+        while let Some(key) = map.next_key::<Cow<'_, str>>()? {
+            let zone = parse_zone(self.device, &key).map_err(serde::de::Error::custom)?;
+            if zones.contains_key(&zone) {
+                return Err(serde::de::Error::custom(format_args!(
+                    "duplicate zone `{zone}`"
+                )));
+            }
+
+            zones.insert(zone, map.next_value()?);
+        }
+
+        Ok(zones)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MockBackend;
+    use crate::MockCall;
+
+    const TOML: &str = r##"
+target = ["PerKeyRgb", "Rgb"]
+base = "#102030"
+
+[keys]
+W = "red"
+NUM_ENTER = { r = 0, g = 50, b = 100 }
+
+[zones.Mouse]
+1 = "white"
+0 = "#ff8800"
+
+[[effects]]
+type = "pulse_key"
+key = "ESC"
+from = "black"
+to = "red"
+duration_ms = 1000
+infinite = true
+"##;
+
+    fn expected() -> Profile {
+        let mut profile = Profile::new();
+        profile.target = TargetDevice::PerKeyRgb | TargetDevice::Rgb;
+        profile.base = Some(ColorPercent::new_rgb(0x10, 0x20, 0x30));
+        profile.keys.insert(KeyName::W, ColorPercent::RED);
+        profile.keys.insert(
+            KeyName::NUM_ENTER,
+            ColorPercent::new_percent(0, 50, 100).unwrap(),
+        );
+        profile.zones.insert(
+            DeviceType::Mouse,
+            BTreeMap::from([
+                (0, ColorPercent::new_rgb(0xFF, 0x88, 0x00)),
+                (1, ColorPercent::WHITE),
+            ]),
+        );
+        profile.effects.push(ProfileEffect::PulseKey {
+            key: KeyName::ESC,
+            from: ColorPercent::BLACK,
+            to: ColorPercent::RED,
+            duration_ms: 1000,
+            infinite: true,
+        });
+        profile
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Profile::from_toml_str(TOML).expect("failed to parse toml"),
+            expected()
+        );
+
+        let json = r##"{
+            "target": ["PerKeyRgb", "Rgb"],
+            "base": "#102030",
+            "keys": { "W": "red", "NUM_ENTER": { "r": 0, "g": 50, "b": 100 } },
+            "zones": { "Mouse": { "1": "white", "0": "#ff8800" } },
+            "effects": [
+                { "type": "pulse_key", "key": "ESC", "from": "black", "to": "red", "duration_ms": 1000, "infinite": true }
+            ]
+        }"##;
+        assert_eq!(
+            Profile::from_json_str(json).expect("failed to parse json"),
+            expected()
+        );
+
+        assert_eq!(
+            Profile::from_toml_str("").expect("failed to parse empty toml"),
+            Profile::new()
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error_position = |error| match error {
+            ProfileError::Parse {
+                line,
+                column,
+                message,
+                ..
+            } => (line, column, message),
+            error => panic!("unexpected error {error:?}"),
+        };
+
+        let (line, column, message) = error_position(
+            Profile::from_toml_str("[keys]\nW = \"red\"\nNUMENTER = \"red\"\n")
+                .expect_err("parsed an unknown key"),
+        );
+        assert_eq!((line, column), (3, 1));
+        assert!(message.contains("expected a key name"), "{message}");
+
+        let (line, column, message) = error_position(
+            Profile::from_toml_str("[zones.Headset]\n0 = \"red\"\n2 = \"red\"\n")
+                .expect_err("parsed an out of range zone"),
+        );
+        assert_eq!((line, column), (3, 1));
+        assert!(
+            message.contains("invalid zone `2` for the Headset device type"),
+            "{message}"
+        );
+
+        let (line, column, message) = error_position(
+            Profile::from_toml_str("[keys]\nW = \"#ff00\"\n").expect_err("parsed a bad color"),
+        );
+        assert_eq!((line, column), (2, 5));
+        assert!(message.contains("expected a hex color"), "{message}");
+
+        let (line, column, message) = error_position(
+            Profile::from_json_str("{\n  \"zones\": { \"Mouse\": { \"3\": \"red\" } }\n}")
+                .expect_err("parsed an out of range zone"),
+        );
+        assert_eq!((line, column), (2, 27));
+        assert_eq!(
+            message,
+            "invalid zone `3` for the Mouse device type, expected a zone from 0 to 2"
+        );
+
+        let (line, column, message) = error_position(
+            Profile::from_json_str(
+                "{\n  \"keys\": {\n    \"W\": \"red\",\n    \"W\": \"blue\"\n  }\n}",
+            )
+            .expect_err("parsed a duplicate key"),
+        );
+        assert_eq!((line, column), (4, 7));
+        assert_eq!(message, "duplicate key `W`");
+
+        let (line, _, message) = error_position(
+            Profile::from_json_str("{\n  \"colour\": \"red\"\n}").expect_err("parsed a bad field"),
+        );
+        assert_eq!(line, 2);
+        assert!(message.starts_with("unknown field `colour`"), "{message}");
+    }
+
+    #[test]
+    fn load() {
+        let dir =
+            std::env::temp_dir().join(format!("logitech-led-sdk-profile-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("failed to create temp dir");
+
+        let path = dir.join("profile.toml");
+        std::fs::write(&path, TOML).expect("failed to write profile");
+        assert_eq!(
+            Profile::load(&path).expect("failed to load profile"),
+            expected()
+        );
+
+        let path = dir.join("bad.toml");
+        std::fs::write(&path, "base = \"nope\"\n").expect("failed to write profile");
+        let error = Profile::load(&path).expect_err("loaded a bad profile");
+        assert!(
+            error
+                .to_string()
+                .starts_with(&format!("{}:1:8: ", path.display())),
+            "{error}"
+        );
+
+        assert!(matches!(
+            Profile::load(dir.join("profile.yaml")),
+            Err(ProfileError::UnknownFormat { .. })
+        ));
+        assert!(matches!(
+            Profile::load(dir.join("missing.json")),
+            Err(ProfileError::Io { .. })
+        ));
+
+        std::fs::remove_dir_all(&dir).expect("failed to remove temp dir");
+    }
+
+    #[test]
+    fn apply() {
+        let backend = MockBackend::new();
+        let sdk = Sdk::new_with_backend(backend.clone()).expect("failed to init mock sdk");
+        backend.take_calls();
+
+        expected().apply(&sdk).expect("failed to apply profile");
+        assert_eq!(
+            backend.take_calls(),
+            [
+                MockCall::SetTargetDevice {
+                    target_device: TargetDevice::PerKeyRgb | TargetDevice::Rgb
+                },
+                MockCall::SetLighting {
+                    color: ColorPercent::new_rgb(0x10, 0x20, 0x30)
+                },
+                MockCall::SetLightingForKeyWithKeyName {
+                    key: KeyName::W,
+                    color: ColorPercent::RED
+                },
+                MockCall::SetLightingForKeyWithKeyName {
+                    key: KeyName::NUM_ENTER,
+                    color: ColorPercent::new_percent(0, 50, 100).unwrap()
+                },
+                MockCall::SetLightingForTargetZone {
+                    device: DeviceType::Mouse,
+                    zone: 0,
+                    color: ColorPercent::new_rgb(0xFF, 0x88, 0x00)
+                },
+                MockCall::SetLightingForTargetZone {
+                    device: DeviceType::Mouse,
+                    zone: 1,
+                    color: ColorPercent::WHITE
+                },
+                MockCall::PulseSingleKey {
+                    key: KeyName::ESC,
+                    start_color: ColorPercent::BLACK,
+                    end_color: ColorPercent::RED,
+                    duration: 1000,
+                    is_infinite: true
+                },
+            ]
+        );
+
+        let mut profile = expected();
+        profile.zones.insert(
+            DeviceType::Headset,
+            BTreeMap::from([(2, ColorPercent::RED)]),
+        );
+        assert_eq!(
+            profile.apply(&sdk),
+            Err(Error::InvalidZone {
+                device: DeviceType::Headset,
+                zone: 2
+            })
+        );
+        assert!(backend.take_calls().is_empty());
+    }
+}
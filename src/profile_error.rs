@@ -0,0 +1,116 @@
+use std::path::PathBuf;
+
+/// An error that may occur while loading a [`Profile`](crate::Profile).
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ProfileError {
+    /// The file could not be read.
+    Io {
+        /// The path of the file.
+        path: PathBuf,
+
+        /// The error.
+        error: std::io::Error,
+    },
+
+    /// The file extension was not `.toml` or `.json`.
+    UnknownFormat {
+        /// The path of the file.
+        path: PathBuf,
+    },
+
+    /// The profile was not valid.
+    Parse {
+        /// The path of the file, if the profile was loaded from one.
+        path: Option<PathBuf>,
+
+        /// The line of the error, starting from 1.
+        line: usize,
+
+        /// The column of the error, in characters, starting from 1.
+        column: usize,
+
+        /// What was wrong.
+        message: String,
+    },
+}
+
+impl ProfileError {
+    /// Make a parse error from a TOML error, finding the line and column in `input`.
+    pub(crate) fn from_toml(error: toml::de::Error, input: &str) -> Self {
+        let offset = error.span().map_or(0, |span| span.start);
+        Self::from_toml_offset(offset, error.message().trim_end().into(), input)
+    }
+
+    /// Make a parse error at a byte offset in TOML `input`, finding its line and column.
+    pub(crate) fn from_toml_offset(offset: usize, message: String, input: &str) -> Self {
+        let offset = offset.min(input.len());
+        let before = &input[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        let column = before[line_start..].chars().count() + 1;
+
+        Self::Parse {
+            path: None,
+            line,
+            column,
+            message,
+        }
+    }
+
+    /// Make a parse error from a JSON error.
+    pub(crate) fn from_json(error: serde_json::Error) -> Self {
+        let message = error.to_string();
+
+        // serde_json appends the position to the message.
+        let suffix = format!(" at line {} column {}", error.line(), error.column());
+        let message = message.strip_suffix(&suffix).unwrap_or(&message).into();
+
+        Self::Parse {
+            path: None,
+            line: error.line(),
+            column: error.column(),
+            message,
+        }
+    }
+
+    /// Set the path of a parse error.
+    pub(crate) fn with_path(mut self, new_path: PathBuf) -> Self {
+        if let Self::Parse { path, .. } = &mut self {
+            *path = Some(new_path);
+        }
+        self
+    }
+}
+
+impl std::fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { path, .. } => write!(f, "failed to read \"{}\"", path.display()),
+            Self::UnknownFormat { path } => {
+                write!(f, "\"{}\" is not a .toml or .json file", path.display())
+            }
+            Self::Parse {
+                path: Some(path),
+                line,
+                column,
+                message,
+            } => write!(f, "{}:{line}:{column}: {message}", path.display()),
+            Self::Parse {
+                path: None,
+                line,
+                column,
+                message,
+            } => write!(f, "line {line}, column {column}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
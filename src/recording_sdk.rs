@@ -0,0 +1,528 @@
+use crate::Backend;
+use crate::Call;
+use crate::CallResult;
+use crate::ColorPercent;
+use crate::DeviceType;
+use crate::Error;
+use crate::KeyName;
+use crate::KeyboardBitmap;
+#[cfg(any(target_os = "windows", feature = "rustdoc", feature = "dynamic"))]
+use crate::NativeBackend;
+use crate::Sdk;
+use crate::TargetDevice;
+use crate::TraceEvent;
+use crate::TRACE_HEADER;
+use std::cell::RefCell;
+use std::ffi::CStr;
+use std::io::Write;
+use std::ops::Deref;
+use std::os::raw::c_int;
+use std::rc::Rc;
+use std::time::Instant;
+
+/// Writes calls to a trace.
+struct Recorder {
+    trace: Box<dyn Write>,
+    start: Instant,
+
+    /// The first error that happened while writing, after which nothing else is written.
+    error: Option<std::io::Error>,
+}
+
+impl Recorder {
+    fn write_line(&mut self, line: std::fmt::Arguments<'_>) {
+        if self.error.is_none() {
+            if let Err(error) = writeln!(self.trace, "{line}") {
+                self.error = Some(error);
+            }
+        }
+    }
+}
+
+/// A backend that writes every call made to another backend to a trace.
+struct RecordingBackend<B> {
+    backend: B,
+    recorder: Rc<RefCell<Recorder>>,
+}
+
+impl<B> RecordingBackend<B>
+where
+    B: Backend,
+{
+    /// Make a call on the inner backend, and write it to the trace.
+    fn call(&self, call: Call) -> CallResult {
+        let time = self.recorder.borrow().start.elapsed();
+        let result = call.issue(&self.backend);
+
+        let event = TraceEvent {
+            time,
+            call,
+            result: result.clone(),
+        };
+        self.recorder
+            .borrow_mut()
+            .write_line(format_args!("{event}"));
+
+        result
+    }
+
+    /// Make a call that does not get a value.
+    fn call_success(&self, call: Call) -> bool {
+        self.call(call) == CallResult::Success(true)
+    }
+
+    /// Make a call that gets a string.
+    fn call_string(&self, call: Call) -> Option<String> {
+        match self.call(call) {
+            CallResult::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl<B> Backend for RecordingBackend<B>
+where
+    B: Backend,
+{
+    fn init(&self) -> bool {
+        self.call_success(Call::Init)
+    }
+
+    fn init_with_name(&self, name: &CStr) -> bool {
+        self.call_success(Call::InitWithName {
+            name: name.to_string_lossy().into_owned(),
+        })
+    }
+
+    fn get_sdk_version(&self) -> Option<(c_int, c_int, c_int)> {
+        match self.call(Call::GetSdkVersion) {
+            CallResult::Version(version) => Some(version),
+            _ => None,
+        }
+    }
+
+    fn get_config_option_number(&self, path: &str, default: f64) -> Option<f64> {
+        let call = Call::GetConfigOptionNumber {
+            path: path.to_string(),
+            default,
+        };
+        match self.call(call) {
+            CallResult::Number(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn get_config_option_bool(&self, path: &str, default: bool) -> Option<bool> {
+        let call = Call::GetConfigOptionBool {
+            path: path.to_string(),
+            default,
+        };
+        match self.call(call) {
+            CallResult::Bool(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn get_config_option_color(
+        &self,
+        path: &str,
+        default: (c_int, c_int, c_int),
+    ) -> Option<(c_int, c_int, c_int)> {
+        let call = Call::GetConfigOptionColor {
+            path: path.to_string(),
+            default,
+        };
+        match self.call(call) {
+            CallResult::Color(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn get_config_option_rect(
+        &self,
+        path: &str,
+        default: (c_int, c_int, c_int, c_int),
+    ) -> Option<(c_int, c_int, c_int, c_int)> {
+        let call = Call::GetConfigOptionRect {
+            path: path.to_string(),
+            default,
+        };
+        match self.call(call) {
+            CallResult::Rect(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn get_config_option_string(&self, path: &str, default: &str) -> Option<String> {
+        self.call_string(Call::GetConfigOptionString {
+            path: path.to_string(),
+            default: default.to_string(),
+        })
+    }
+
+    fn get_config_option_key_input(&self, path: &str, default: &str) -> Option<String> {
+        self.call_string(Call::GetConfigOptionKeyInput {
+            path: path.to_string(),
+            default: default.to_string(),
+        })
+    }
+
+    fn get_config_option_select(
+        &self,
+        path: &str,
+        default: &str,
+        options: &[&str],
+    ) -> Option<String> {
+        self.call_string(Call::GetConfigOptionSelect {
+            path: path.to_string(),
+            default: default.to_string(),
+            options: options.iter().map(|option| option.to_string()).collect(),
+        })
+    }
+
+    fn get_config_option_range(
+        &self,
+        path: &str,
+        default: c_int,
+        min: c_int,
+        max: c_int,
+    ) -> Option<c_int> {
+        let call = Call::GetConfigOptionRange {
+            path: path.to_string(),
+            default,
+            min,
+            max,
+        };
+        match self.call(call) {
+            CallResult::Range(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn set_config_option_label(&self, path: &str, label: &str) -> bool {
+        self.call_success(Call::SetConfigOptionLabel {
+            path: path.to_string(),
+            label: label.to_string(),
+        })
+    }
+
+    fn set_target_device(&self, target_device: TargetDevice) -> bool {
+        self.call_success(Call::SetTargetDevice { target_device })
+    }
+
+    fn save_current_lighting(&self) -> bool {
+        self.call_success(Call::SaveCurrentLighting)
+    }
+
+    fn set_lighting(&self, color: ColorPercent) -> bool {
+        self.call_success(Call::SetLighting { color })
+    }
+
+    fn restore_lighting(&self) -> bool {
+        self.call_success(Call::RestoreLighting)
+    }
+
+    fn flash_lighting(&self, color: ColorPercent, duration: c_int, interval: c_int) -> bool {
+        self.call_success(Call::FlashLighting {
+            color,
+            duration,
+            interval,
+        })
+    }
+
+    fn pulse_lighting(&self, color: ColorPercent, duration: c_int, interval: c_int) -> bool {
+        self.call_success(Call::PulseLighting {
+            color,
+            duration,
+            interval,
+        })
+    }
+
+    fn stop_effects(&self) -> bool {
+        self.call_success(Call::StopEffects)
+    }
+
+    fn set_lighting_from_bitmap(&self, bitmap: &KeyboardBitmap) -> bool {
+        self.call_success(Call::SetLightingFromBitmap {
+            bitmap: Box::new(bitmap.clone()),
+        })
+    }
+
+    fn set_lighting_for_key_with_scan_code(&self, scan_code: c_int, color: ColorPercent) -> bool {
+        self.call_success(Call::SetLightingForKeyWithScanCode { scan_code, color })
+    }
+
+    fn set_lighting_for_key_with_hid_code(&self, hid_code: c_int, color: ColorPercent) -> bool {
+        self.call_success(Call::SetLightingForKeyWithHidCode { hid_code, color })
+    }
+
+    fn set_lighting_for_key_with_quartz_code(
+        &self,
+        quartz_code: c_int,
+        color: ColorPercent,
+    ) -> bool {
+        self.call_success(Call::SetLightingForKeyWithQuartzCode { quartz_code, color })
+    }
+
+    fn set_lighting_for_key_with_key_name(&self, key: KeyName, color: ColorPercent) -> bool {
+        self.call_success(Call::SetLightingForKeyWithKeyName { key, color })
+    }
+
+    fn save_lighting_for_key(&self, key: KeyName) -> bool {
+        self.call_success(Call::SaveLightingForKey { key })
+    }
+
+    fn restore_lighting_for_key(&self, key: KeyName) -> bool {
+        self.call_success(Call::RestoreLightingForKey { key })
+    }
+
+    fn exclude_keys_from_bitmap(&self, keys: &[KeyName]) -> bool {
+        self.call_success(Call::ExcludeKeysFromBitmap {
+            keys: keys.to_vec(),
+        })
+    }
+
+    fn flash_single_key(
+        &self,
+        key: KeyName,
+        color: ColorPercent,
+        duration: c_int,
+        interval: c_int,
+    ) -> bool {
+        self.call_success(Call::FlashSingleKey {
+            key,
+            color,
+            duration,
+            interval,
+        })
+    }
+
+    fn pulse_single_key(
+        &self,
+        key: KeyName,
+        start_color: ColorPercent,
+        end_color: ColorPercent,
+        duration: c_int,
+        is_infinite: bool,
+    ) -> bool {
+        self.call_success(Call::PulseSingleKey {
+            key,
+            start_color,
+            end_color,
+            duration,
+            is_infinite,
+        })
+    }
+
+    fn stop_effects_on_key(&self, key: KeyName) -> bool {
+        self.call_success(Call::StopEffectsOnKey { key })
+    }
+
+    fn set_lighting_for_target_zone(
+        &self,
+        device: DeviceType,
+        zone: c_int,
+        color: ColorPercent,
+    ) -> bool {
+        self.call_success(Call::SetLightingForTargetZone {
+            device,
+            zone,
+            color,
+        })
+    }
+
+    fn shutdown(&self) {
+        self.call(Call::Shutdown);
+    }
+}
+
+/// An [`Sdk`] that writes every call made through it to a [`Trace`](crate::Trace).
+///
+/// This derefs to an [`Sdk`], so it can be used anywhere an sdk can.
+/// Each call is written as a line of the trace as soon as it returns, with the time since the recording sdk was created.
+/// The initialization call is the first line, and the shutdown call is written when the recording sdk is dropped.
+///
+/// Failing to write the trace does not fail any calls.
+/// Use [`RecordingSdk::finish`] to shut down the sdk and check whether the trace was written.
+pub struct RecordingSdk {
+    sdk: Sdk,
+    recorder: Rc<RefCell<Recorder>>,
+}
+
+impl RecordingSdk {
+    /// Create a new recording sdk with no name, using the [`NativeBackend`].
+    ///
+    /// # Errors
+    /// Returns an error if the sdk could not be created, like [`Sdk::new`].
+    #[cfg(any(target_os = "windows", feature = "rustdoc", feature = "dynamic"))]
+    pub fn new<W>(trace: W) -> Result<Self, Error>
+    where
+        W: Write + 'static,
+    {
+        Self::new_with_backend(NativeBackend::new()?, trace)
+    }
+
+    /// Create a new recording sdk with a name, using the [`NativeBackend`].
+    ///
+    /// # Errors
+    /// Returns an error if the sdk could not be created, like [`Sdk::new_with_name`].
+    #[cfg(any(target_os = "windows", feature = "rustdoc", feature = "dynamic"))]
+    pub fn new_with_name<W>(name: &str, trace: W) -> Result<Self, Error>
+    where
+        W: Write + 'static,
+    {
+        Self::new_with_backend_and_name(NativeBackend::new()?, name, trace)
+    }
+
+    /// Create a new recording sdk with no name, using the given backend.
+    ///
+    /// # Errors
+    /// Returns an error if the backend could not be initialized.
+    pub fn new_with_backend<B, W>(backend: B, trace: W) -> Result<Self, Error>
+    where
+        B: Backend + 'static,
+        W: Write + 'static,
+    {
+        Self::init(backend, None, trace)
+    }
+
+    /// Create a new recording sdk with a name, using the given backend.
+    ///
+    /// # Errors
+    /// Returns an error if the name contains interior NULs or the backend could not be initialized.
+    pub fn new_with_backend_and_name<B, W>(backend: B, name: &str, trace: W) -> Result<Self, Error>
+    where
+        B: Backend + 'static,
+        W: Write + 'static,
+    {
+        Self::init(backend, Some(name), trace)
+    }
+
+    fn init<B, W>(backend: B, name: Option<&str>, trace: W) -> Result<Self, Error>
+    where
+        B: Backend + 'static,
+        W: Write + 'static,
+    {
+        let recorder = Rc::new(RefCell::new(Recorder {
+            trace: Box::new(trace),
+            start: Instant::now(),
+            error: None,
+        }));
+        recorder
+            .borrow_mut()
+            .write_line(format_args!("{TRACE_HEADER}"));
+
+        let backend = RecordingBackend {
+            backend,
+            recorder: recorder.clone(),
+        };
+        let sdk = match name {
+            Some(name) => Sdk::new_with_backend_and_name(backend, name)?,
+            None => Sdk::new_with_backend(backend)?,
+        };
+
+        Ok(Self { sdk, recorder })
+    }
+
+    /// Shut down the sdk and flush the trace.
+    ///
+    /// # Errors
+    /// Returns the first error that happened while writing the trace.
+    pub fn finish(self) -> std::io::Result<()> {
+        let Self { sdk, recorder } = self;
+        drop(sdk);
+
+        let mut recorder = recorder.borrow_mut();
+        if let Some(error) = recorder.error.take() {
+            return Err(error);
+        }
+        recorder.trace.flush()
+    }
+}
+
+impl Deref for RecordingSdk {
+    type Target = Sdk;
+
+    fn deref(&self) -> &Self::Target {
+        &self.sdk
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MockBackend;
+    use crate::Trace;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::sync::PoisonError;
+
+    /// A writer that can be read after it is given away.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn record() {
+        let backend = MockBackend::new();
+        backend.set_failing("LogiLedSetLightingForTargetZone", true);
+        let buffer = SharedBuffer::default();
+        let sdk = RecordingSdk::new_with_backend_and_name(backend.clone(), "game", buffer.clone())
+            .expect("failed to init recording sdk");
+
+        sdk.set_lighting(ColorPercent::RED)
+            .expect("failed to set lighting");
+        assert_eq!(
+            sdk.config_number("lighting/speed", 1.5)
+                .expect("failed to get config number"),
+            1.5
+        );
+        assert!(sdk
+            .set_lighting_for_target_zone(DeviceType::Mouse, 1, ColorPercent::GREEN)
+            .is_err());
+        sdk.finish().expect("failed to finish trace");
+
+        let text = String::from_utf8(buffer.0.lock().expect("buffer poisoned").clone())
+            .expect("trace is not UTF-8");
+        let trace: Trace = text.parse().expect("failed to parse trace");
+        let lines: Vec<_> = trace
+            .events()
+            .iter()
+            .map(|event| {
+                let line = event.to_string();
+                line.split_once(' ').expect("missing time").1.to_string()
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                "LogiLedInitWithName \"game\" = true",
+                "LogiLedSetLighting 100,0,0 = true",
+                "LogiLedGetConfigOptionNumber \"lighting/speed\" 1.5 = 1.5",
+                "LogiLedSetLightingForTargetZone Mouse 1 0,100,0 = false",
+                "LogiLedShutdown",
+            ]
+        );
+        assert!(trace
+            .events()
+            .windows(2)
+            .all(|events| events[0].time <= events[1].time));
+
+        // The calls still reach the backend.
+        assert_eq!(backend.lighting(), Some(ColorPercent::RED));
+        assert!(!backend.is_initialized());
+    }
+}
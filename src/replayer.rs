@@ -0,0 +1,246 @@
+use crate::Call;
+use crate::Clock;
+use crate::Error;
+use crate::Sdk;
+use crate::StopHandle;
+use crate::SystemClock;
+use crate::Trace;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The longest time to sleep before checking whether a stop was requested.
+const MAX_SLEEP: Duration = Duration::from_millis(100);
+
+/// Plays a [`Trace`] back through an [`Sdk`], making each call at the time it was recorded.
+///
+/// Calls are made directly on the backend of the sdk, with the exact arguments that were recorded.
+/// `LogiLedInit`, `LogiLedInitWithName` and `LogiLedShutdown` are skipped,
+/// as the sdk is already initialized and shuts itself down when dropped.
+/// Time comes from a [`Clock`], so replays can be tested deterministically with a [`ManualClock`](crate::ManualClock).
+pub struct Replayer<C = SystemClock> {
+    clock: C,
+    speed: f64,
+    stop_requested: Arc<AtomicBool>,
+}
+
+impl Replayer<SystemClock> {
+    /// Create a new replayer that plays traces in real time.
+    pub fn new() -> Self {
+        Self::with_clock(SystemClock::new())
+    }
+}
+
+impl<C> Replayer<C>
+where
+    C: Clock,
+{
+    /// Create a new replayer that uses the given clock.
+    pub fn with_clock(clock: C) -> Self {
+        Self {
+            clock,
+            speed: 1.0,
+            stop_requested: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Set how fast to play traces, where 1 is the speed they were recorded at and 2 is twice as fast.
+    ///
+    /// A speed of [`f64::INFINITY`] makes every call without waiting.
+    /// Speeds that are not positive are treated as 1.
+    pub fn set_speed(&mut self, speed: f64) {
+        self.speed = if speed > 0.0 { speed } else { 1.0 };
+    }
+
+    /// Get how fast traces are played.
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// Get a handle that can stop a replay from another thread.
+    pub fn stop_handle(&self) -> StopHandle {
+        StopHandle::new(self.stop_requested.clone())
+    }
+
+    /// Make every call in a trace, blocking until the trace ends or is stopped with a [`StopHandle`].
+    ///
+    /// Calls that failed when they were recorded may fail again without stopping the replay.
+    ///
+    /// # Errors
+    /// Returns an error if a call fails that succeeded when it was recorded.
+    pub fn replay(&self, sdk: &Sdk, trace: &Trace) -> Result<(), Error> {
+        self.stop_requested.store(false, Ordering::SeqCst);
+        let start = self.clock.now();
+        for event in trace.events() {
+            if matches!(
+                event.call,
+                Call::Init | Call::InitWithName { .. } | Call::Shutdown
+            ) {
+                continue;
+            }
+
+            let due = event.time.div_f64(self.speed);
+            loop {
+                if self.stop_requested.swap(false, Ordering::SeqCst) {
+                    return Ok(());
+                }
+
+                let elapsed = self.clock.now().saturating_sub(start);
+                if elapsed >= due {
+                    break;
+                }
+                self.clock.sleep((due - elapsed).min(MAX_SLEEP));
+            }
+
+            let result = event.call.issue(sdk.backend());
+            if result.is_failure() && !event.result.is_failure() {
+                return Err(Error::CallFailed {
+                    function: event.call.function(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Replayer<SystemClock> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> std::fmt::Debug for Replayer<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Replayer")
+            .field("speed", &self.speed)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ColorPercent;
+    use crate::KeyName;
+    use crate::ManualClock;
+    use crate::MockBackend;
+    use crate::MockCall;
+
+    const TRACE: &str = "logiled-trace 1
+0 LogiLedInitWithName \"game\" = true
+1000 LogiLedSetLighting 100,0,0 = true
+3000 LogiLedSetLightingForKeyWithKeyName W 0,0,100 = true
+4000 LogiLedSetLightingForTargetZone Mouse 1 0,100,0 = false
+5000 LogiLedShutdown
+";
+
+    /// A clock that records how long it was asked to sleep.
+    #[derive(Debug, Clone, Default)]
+    struct SleepClock {
+        clock: ManualClock,
+        slept: Arc<std::sync::Mutex<Vec<Duration>>>,
+    }
+
+    impl Clock for SleepClock {
+        fn now(&self) -> Duration {
+            self.clock.now()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            self.slept.lock().expect("sleeps poisoned").push(duration);
+            self.clock.sleep(duration);
+        }
+    }
+
+    #[test]
+    fn replay() {
+        let trace: Trace = TRACE.parse().expect("failed to parse trace");
+        let backend = MockBackend::new();
+        let sdk = Sdk::new_with_backend(backend.clone()).expect("failed to init mock sdk");
+        backend.take_calls();
+
+        let clock = SleepClock::default();
+        let mut replayer = Replayer::with_clock(clock.clone());
+        replayer.set_speed(2.0);
+        replayer.replay(&sdk, &trace).expect("failed to replay");
+
+        assert_eq!(
+            backend.take_calls(),
+            [
+                MockCall::SetLighting {
+                    color: ColorPercent::RED
+                },
+                MockCall::SetLightingForKeyWithKeyName {
+                    key: KeyName::W,
+                    color: ColorPercent::BLUE
+                },
+                MockCall::SetLightingForTargetZone {
+                    device: crate::DeviceType::Mouse,
+                    zone: 1,
+                    color: ColorPercent::GREEN
+                },
+            ]
+        );
+        assert_eq!(
+            *clock.slept.lock().expect("sleeps poisoned"),
+            [
+                Duration::from_micros(500),
+                Duration::from_micros(1000),
+                Duration::from_micros(500)
+            ]
+        );
+        assert_eq!(clock.now(), Duration::from_millis(2));
+
+        // A call that succeeded when it was recorded must succeed again.
+        backend.set_failing("LogiLedSetLightingForKeyWithKeyName", true);
+        replayer.set_speed(f64::INFINITY);
+        assert_eq!(
+            replayer.replay(&sdk, &trace),
+            Err(Error::CallFailed {
+                function: "LogiLedSetLightingForKeyWithKeyName"
+            })
+        );
+        assert_eq!(clock.now(), Duration::from_millis(2));
+    }
+
+    #[test]
+    fn stop() {
+        let trace: Trace = TRACE.parse().expect("failed to parse trace");
+        let backend = MockBackend::new();
+        let sdk = Sdk::new_with_backend(backend.clone()).expect("failed to init mock sdk");
+        backend.take_calls();
+
+        // Stop as soon as the replay first sleeps.
+        struct StoppingClock {
+            clock: ManualClock,
+            stop_handle: std::cell::OnceCell<StopHandle>,
+        }
+
+        impl Clock for StoppingClock {
+            fn now(&self) -> Duration {
+                self.clock.now()
+            }
+
+            fn sleep(&self, duration: Duration) {
+                if let Some(stop_handle) = self.stop_handle.get() {
+                    stop_handle.stop();
+                }
+                self.clock.sleep(duration);
+            }
+        }
+
+        let replayer = Replayer::with_clock(StoppingClock {
+            clock: ManualClock::new(),
+            stop_handle: std::cell::OnceCell::new(),
+        });
+        assert!(replayer
+            .clock
+            .stop_handle
+            .set(replayer.stop_handle())
+            .is_ok());
+        replayer.replay(&sdk, &trace).expect("failed to replay");
+        assert!(backend.take_calls().is_empty());
+    }
+}
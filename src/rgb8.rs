@@ -0,0 +1,164 @@
+use crate::css_colors::css_color;
+use crate::ParseColorError;
+use std::str::FromStr;
+
+/// An RGB color, with values from 0-255.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgb8 {
+    /// Red
+    pub r: u8,
+
+    /// Green
+    pub g: u8,
+
+    /// Blue
+    pub b: u8,
+}
+
+impl Rgb8 {
+    /// Create a new color.
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Get the channels as values from 0 to 1.
+    pub(crate) fn to_unit(self) -> [f32; 3] {
+        [self.r, self.g, self.b].map(|value| f32::from(value) / 255.0)
+    }
+
+    /// Make a color from channels with values from 0 to 1, rounding to the nearest value.
+    pub(crate) fn from_unit([r, g, b]: [f32; 3]) -> Self {
+        let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Self {
+            r: channel(r),
+            g: channel(g),
+            b: channel(b),
+        }
+    }
+}
+
+impl FromStr for Rgb8 {
+    type Err = ParseColorError;
+
+    /// Parse a `#rrggbb` or `#rgb` hex color, or a CSS color name like `rebeccapurple`.
+    ///
+    /// Case and surrounding whitespace are ignored.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let error = || ParseColorError::new(input);
+        let trimmed = input.trim();
+
+        let hex = match trimmed.strip_prefix('#') {
+            Some(hex) => hex,
+            None => return css_color(trimmed).ok_or_else(error),
+        };
+        // from_str_radix accepts a leading sign, so check the digits first.
+        if !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(error());
+        }
+        let value = u32::from_str_radix(hex, 16).map_err(|_| error())?;
+
+        let color = match hex.len() {
+            3 => {
+                let digit = |shift: u32| ((value >> shift) & 0xF) as u8 * 17;
+                Self::new(digit(8), digit(4), digit(0))
+            }
+            6 => {
+                let byte = |shift: u32| (value >> shift) as u8;
+                Self::new(byte(16), byte(8), byte(0))
+            }
+            _ => return Err(error()),
+        };
+
+        Ok(color)
+    }
+}
+
+impl std::fmt::Display for Rgb8 {
+    /// Format as a `#rrggbb` hex color.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Rgb8 {
+    /// Serialize as a `"#rrggbb"` hex color.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Rgb8 {
+    /// Deserialize from a hex color or CSS color name.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct Rgb8Visitor;
+
+        impl serde::de::Visitor<'_> for Rgb8Visitor {
+            type Value = Rgb8;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a hex color or a CSS color name")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                value
+                    .parse()
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Str(value), &self))
+            }
+        }
+
+        deserializer.deserialize_str(Rgb8Visitor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let purple = Rgb8::new(0x66, 0x33, 0x99);
+        assert_eq!("#663399".parse(), Ok(purple));
+        assert_eq!(" #663399 ".parse(), Ok(purple));
+        assert_eq!("#639".parse(), Ok(purple));
+        assert_eq!("RebeccaPurple".parse(), Ok(purple));
+        assert_eq!(purple.to_string(), "#663399");
+
+        for input in [
+            "",
+            "#",
+            "#6633",
+            "#66339g",
+            "663399",
+            "#+63399",
+            "notacolor",
+        ] {
+            assert_eq!(
+                input.parse::<Rgb8>(),
+                Err(ParseColorError::new(input)),
+                "{input:?}"
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let color = Rgb8::new(0xFF, 0x88, 0x00);
+        let json = serde_json::to_string(&color).expect("failed to serialize");
+        assert_eq!(json, "\"#ff8800\"");
+        let parsed: Rgb8 = serde_json::from_str(&json).expect("failed to deserialize");
+        assert_eq!(parsed, color);
+        assert!(serde_json::from_str::<Rgb8>("\"#ff880\"").is_err());
+    }
+}
@@ -1,165 +1,481 @@
+use crate::color_percent::byte_to_percent;
+use crate::color_percent::percent_to_byte;
 use crate::sys;
+use crate::Backend;
 use crate::ColorPercent;
 use crate::DeviceType;
+use crate::Error;
+use crate::Frame;
 use crate::KeyName;
+use crate::KeyboardBitmap;
+use crate::LightingSnapshotGuard;
+#[cfg(any(target_os = "windows", feature = "rustdoc", feature = "dynamic"))]
+use crate::NativeBackend;
 pub use crate::TargetDevice;
-use crate::SDK_LOCK;
 use std::ffi::CString;
 use std::os::raw::c_int;
-use std::sync::MutexGuard;
-use std::sync::TryLockError;
 use std::time::Duration;
 
+/// The minimum size of the buffers used to receive string config options, in UTF-16 code units.
+pub const CONFIG_STRING_CAPACITY: usize = 256;
+
 /// Entry to Api.
 ///
-/// This serves as proof of initalization and prevents the API from being used by other threads.
-pub struct Sdk(MutexGuard<'static, ()>);
+/// This serves as proof of initalization.
+/// The [`NativeBackend`] also prevents the API from being used by other threads.
+pub struct Sdk {
+    backend: Box<dyn Backend>,
+}
 
 impl Sdk {
-    /// Create a new sdk instance with no name.
+    /// Create a new sdk instance with no name, using the [`NativeBackend`].
     ///
-    /// # Returns
-    /// Returns None if the sdk could not be initialized.
-    pub fn new() -> Option<Self> {
-        let guard = match SDK_LOCK.try_lock() {
-            Ok(guard) => guard,
-            Err(TryLockError::WouldBlock) => return None,
-            Err(TryLockError::Poisoned(e)) => e.into_inner(),
-        };
-
-        let init = unsafe { sys::LogiLedInit() };
-        if !init {
-            return None;
-        }
-
-        Some(Sdk(guard))
+    /// # Errors
+    /// Returns an error if the sdk is already in use in this process or could not be initialized.
+    /// With the `dynamic` feature, this also returns an error if the library could not be loaded.
+    #[cfg(any(target_os = "windows", feature = "rustdoc", feature = "dynamic"))]
+    pub fn new() -> Result<Self, Error> {
+        Self::new_with_backend(NativeBackend::new()?)
     }
 
     /// Create a new sdk instance with a name, where the name is the name of the application using the sdk.
     ///
-    /// # Panics
-    /// Panics if the name contains interior NULs.
+    /// This uses the [`NativeBackend`].
     ///
-    /// # Returns
-    /// Returns None if the sdk could not be initialized.
-    pub fn new_with_name(name: &str) -> Option<Self> {
-        let guard = match SDK_LOCK.try_lock() {
-            Ok(guard) => guard,
-            Err(TryLockError::WouldBlock) => return None,
-            Err(TryLockError::Poisoned(e)) => e.into_inner(),
-        };
+    /// # Errors
+    /// Returns an error if the name contains interior NULs,
+    /// or if the sdk is already in use in this process or could not be initialized.
+    /// With the `dynamic` feature, this also returns an error if the library could not be loaded.
+    #[cfg(any(target_os = "windows", feature = "rustdoc", feature = "dynamic"))]
+    pub fn new_with_name(name: &str) -> Result<Self, Error> {
+        let name = CString::new(name).map_err(|_| Error::InteriorNul)?;
+        Self::init(NativeBackend::new()?, Some(name))
+    }
+
+    /// Create a new sdk instance with no name, using the given backend.
+    ///
+    /// # Errors
+    /// Returns an error if the backend could not be initialized.
+    pub fn new_with_backend<B>(backend: B) -> Result<Self, Error>
+    where
+        B: Backend + 'static,
+    {
+        Self::init(backend, None)
+    }
+
+    /// Create a new sdk instance with a name, using the given backend.
+    ///
+    /// # Errors
+    /// Returns an error if the name contains interior NULs or the backend could not be initialized.
+    pub fn new_with_backend_and_name<B>(backend: B, name: &str) -> Result<Self, Error>
+    where
+        B: Backend + 'static,
+    {
+        let name = CString::new(name).map_err(|_| Error::InteriorNul)?;
+        Self::init(backend, Some(name))
+    }
 
-        let name = CString::new(name).expect("name contains interior NUL");
-        let init = unsafe { sys::LogiLedInitWithName(name.as_ptr()) };
+    fn init<B>(backend: B, name: Option<CString>) -> Result<Self, Error>
+    where
+        B: Backend + 'static,
+    {
+        let init = match name {
+            Some(name) => backend.init_with_name(&name),
+            None => backend.init(),
+        };
         if !init {
-            return None;
+            return Err(Error::InitFailed);
         }
 
-        Some(Sdk(guard))
+        Ok(Self {
+            backend: Box::new(backend),
+        })
+    }
+
+    /// Get the backend, to make calls that skip the checks done by the sdk functions.
+    pub(crate) fn backend(&self) -> &dyn Backend {
+        &*self.backend
     }
 
     /// Returns the sdk version.
     ///
     /// # Returns
     /// Returns a tuple of the major, minor and build numbers if successful.
-    /// Returns None if the version could not be found.
-    pub fn get_version(&self) -> Option<(u32, u32, u32)> {
-        let mut major = 0;
-        let mut minor = 0;
-        let mut build = 0;
-
-        let valid = unsafe { sys::LogiLedGetSdkVersion(&mut major, &mut minor, &mut build) };
-        if !valid {
-            return None;
-        }
+    ///
+    /// # Errors
+    /// Returns an error if the version could not be found.
+    pub fn get_version(&self) -> Result<(u32, u32, u32), Error> {
+        let (major, minor, build) =
+            check_some(self.backend.get_sdk_version(), "LogiLedGetSdkVersion")?;
 
         // i32 -> u32, transmute
-        Some((major as u32, minor as u32, build as u32))
+        Ok((major as u32, minor as u32, build as u32))
     }
 
-    /// Selects the target devices.
+    /// Get a number config option, registering it with the given default if it does not exist.
+    ///
+    /// Config options are shown in LG HUB's settings page for the application.
+    /// Paths use `/` to separate groups, like `"lighting/speed"`.
+    ///
+    /// # Errors
+    /// Returns an error if the call fails or the path contains interior NULs.
+    pub fn config_number(&self, path: &str, default: f64) -> Result<f64, Error> {
+        check_nul(path)?;
+
+        check_some(
+            self.backend.get_config_option_number(path, default),
+            "LogiLedGetConfigOptionNumber",
+        )
+    }
+
+    /// Get a bool config option, registering it with the given default if it does not exist.
+    ///
+    /// # Errors
+    /// Returns an error if the call fails or the path contains interior NULs.
+    pub fn config_bool(&self, path: &str, default: bool) -> Result<bool, Error> {
+        check_nul(path)?;
+
+        check_some(
+            self.backend.get_config_option_bool(path, default),
+            "LogiLedGetConfigOptionBool",
+        )
+    }
+
+    /// Get a color config option, registering it with the given default if it does not exist.
+    ///
+    /// # Errors
+    /// Returns an error if the call fails or the path contains interior NULs.
+    pub fn config_color(&self, path: &str, default: ColorPercent) -> Result<ColorPercent, Error> {
+        check_nul(path)?;
+
+        let default = (
+            c_int::from(percent_to_byte(default.r)),
+            c_int::from(percent_to_byte(default.g)),
+            c_int::from(percent_to_byte(default.b)),
+        );
+        let (r, g, b) = check_some(
+            self.backend.get_config_option_color(path, default),
+            "LogiLedGetConfigOptionColor",
+        )?;
+
+        let clamp = |value: c_int| byte_to_percent(value.clamp(0, 255) as u8);
+        Ok(ColorPercent {
+            r: clamp(r),
+            g: clamp(g),
+            b: clamp(b),
+        })
+    }
+
+    /// Get a rectangle config option, registering it with the given default if it does not exist.
+    ///
+    /// Rectangles are tuples of the x, y, width and height.
+    ///
+    /// # Errors
+    /// Returns an error if the call fails or the path contains interior NULs.
+    pub fn config_rect(
+        &self,
+        path: &str,
+        default: (i32, i32, i32, i32),
+    ) -> Result<(i32, i32, i32, i32), Error> {
+        check_nul(path)?;
+
+        check_some(
+            self.backend.get_config_option_rect(path, default),
+            "LogiLedGetConfigOptionRect",
+        )
+    }
+
+    /// Get a string config option, registering it with the given default if it does not exist.
+    ///
+    /// Values longer than `CONFIG_STRING_CAPACITY` UTF-16 code units, or the default's length, are truncated.
+    ///
+    /// # Errors
+    /// Returns an error if the call fails or the path or default contain interior NULs.
+    pub fn config_string(&self, path: &str, default: &str) -> Result<String, Error> {
+        check_nul(path)?;
+        check_nul(default)?;
+
+        check_some(
+            self.backend.get_config_option_string(path, default),
+            "LogiLedGetConfigOptionString",
+        )
+    }
+
+    /// Get a key input config option, registering it with the given default if it does not exist.
+    ///
+    /// The value is the name of the key combination, like `"CTRL+A"`.
+    ///
+    /// # Errors
+    /// Returns an error if the call fails or the path or default contain interior NULs.
+    pub fn config_key_input(&self, path: &str, default: &str) -> Result<String, Error> {
+        check_nul(path)?;
+        check_nul(default)?;
+
+        check_some(
+            self.backend.get_config_option_key_input(path, default),
+            "LogiLedGetConfigOptionKeyInput",
+        )
+    }
+
+    /// Get a select config option, registering it with the given default and options if it does not exist.
     ///
     /// # Returns
-    /// Returns true if the target devices were selected.
-    pub fn set_target(&self, target_device: TargetDevice) -> bool {
-        // u32 -> i32, transmute
-        unsafe { sys::LogiLedSetTargetDevice(target_device.bits() as c_int) }
+    /// Returns the chosen option.
+    ///
+    /// # Errors
+    /// Returns an error if the call fails, the default is not one of the options,
+    /// or any of the strings contain interior NULs.
+    pub fn config_select(
+        &self,
+        path: &str,
+        default: &str,
+        options: &[&str],
+    ) -> Result<String, Error> {
+        if !options.contains(&default) {
+            return Err(Error::InvalidConfigDefault);
+        }
+        check_nul(path)?;
+        for option in options {
+            check_nul(option)?;
+        }
+        // Options are joined together with NULs before being passed to the sdk.
+        let values_len = options
+            .iter()
+            .map(|option| option.encode_utf16().count() + 1)
+            .sum::<usize>()
+            + 1;
+        c_int::try_from(values_len).map_err(|_| Error::LengthOutOfRange)?;
+
+        check_some(
+            self.backend
+                .get_config_option_select(path, default, options),
+            "LogiLedGetConfigOptionSelect",
+        )
+    }
+
+    /// Get a ranged integer config option, registering it with the given default and bounds if it does not exist.
+    ///
+    /// # Errors
+    /// Returns an error if the call fails, the path contains interior NULs, or the default is not in range.
+    pub fn config_range(&self, path: &str, default: i32, min: i32, max: i32) -> Result<i32, Error> {
+        if default < min || default > max {
+            return Err(Error::InvalidConfigDefault);
+        }
+        check_nul(path)?;
+
+        check_some(
+            self.backend
+                .get_config_option_range(path, default, min, max),
+            "LogiLedGetConfigOptionRange",
+        )
+    }
+
+    /// Set the label shown for a config option or group.
+    ///
+    /// # Errors
+    /// Returns an error if the call fails or the path or label contain interior NULs.
+    pub fn set_config_label(&self, path: &str, label: &str) -> Result<(), Error> {
+        check_nul(path)?;
+        check_nul(label)?;
+
+        check(
+            self.backend.set_config_option_label(path, label),
+            "LogiLedSetConfigOptionLabel",
+        )
+    }
+
+    /// Selects the target devices.
+    ///
+    /// # Errors
+    /// Returns an error if the target devices could not be selected.
+    pub fn set_target(&self, target_device: TargetDevice) -> Result<(), Error> {
+        check(
+            self.backend.set_target_device(target_device),
+            "LogiLedSetTargetDevice",
+        )
+    }
+
+    /// Saves the current lighting of all devices, restoring it when the returned guard is dropped.
+    ///
+    /// The sdk only keeps one saved state,
+    /// so saving again while a guard is alive replaces the state that the older guard will restore.
+    ///
+    /// # Errors
+    /// Returns an error if the lighting could not be saved.
+    pub fn save_lighting(&self) -> Result<LightingSnapshotGuard<'_>, Error> {
+        check(
+            self.backend.save_current_lighting(),
+            "LogiLedSaveCurrentLighting",
+        )?;
+
+        Ok(LightingSnapshotGuard::new(self))
+    }
+
+    /// Restores the lighting of all devices saved by [`Sdk::save_lighting`].
+    ///
+    /// # Errors
+    /// Returns an error if the call fails.
+    pub(crate) fn restore_lighting(&self) -> Result<(), Error> {
+        check(self.backend.restore_lighting(), "LogiLedRestoreLighting")
     }
 
     /// Sets the lighting.
     ///
-    /// # Returns
-    /// Returns true if successful.
-    pub fn set_lighting(&self, color: ColorPercent) -> bool {
-        unsafe { sys::LogiLedSetLighting(color.r.into(), color.g.into(), color.b.into()) }
+    /// # Errors
+    /// Returns an error if the call fails.
+    pub fn set_lighting(&self, color: ColorPercent) -> Result<(), Error> {
+        check(self.backend.set_lighting(color), "LogiLedSetLighting")
     }
 
-    /// Set the lighting for a keyboard key by key name.
+    /// Sets the lighting of the entire keyboard from a bitmap.
     ///
-    /// # Returns
-    /// Returns true if successful.
-    pub fn set_lighting_for_key_with_name(&self, key: KeyName, color: ColorPercent) -> bool {
-        unsafe {
-            sys::LogiLedSetLightingForKeyWithKeyName(
-                key,
-                color.r.into(),
-                color.g.into(),
-                color.b.into(),
-            )
+    /// This only affects per-key RGB devices.
+    ///
+    /// # Errors
+    /// Returns an error if the call fails.
+    pub fn set_lighting_from_bitmap(&self, bitmap: &KeyboardBitmap) -> Result<(), Error> {
+        check(
+            self.backend.set_lighting_from_bitmap(bitmap),
+            "LogiLedSetLightingFromBitmap",
+        )
+    }
+
+    /// Sets the lighting of every device from a frame.
+    ///
+    /// The background color of the frame is sent to every device with [`Sdk::set_lighting`],
+    /// so devices without per-key lighting show it.
+    /// This also overrides keys that are excluded from bitmaps.
+    /// The keys in the bitmap are then sent with [`Sdk::set_lighting_from_bitmap`],
+    /// and any other keys that were set in the frame, like the G keys, are sent one by one.
+    ///
+    /// # Errors
+    /// Returns an error if any of the calls fail.
+    pub fn set_lighting_from_frame(&self, frame: &Frame) -> Result<(), Error> {
+        self.set_lighting(frame.background())?;
+        self.set_lighting_from_bitmap(&frame.to_bitmap())?;
+        for (key, color) in frame.iter() {
+            if KeyboardBitmap::key_position(key).is_none() {
+                self.set_lighting_for_key_with_name(key, color)?;
+            }
         }
+
+        Ok(())
     }
 
-    /// Sets the lighting for a keyboard key by scan code.
+    /// Sets the keys that will be left untouched by [`Sdk::set_lighting_from_bitmap`].
     ///
-    /// # Returns
-    /// Returns true if successful.
-    pub fn set_lighting_for_key_with_scan_code(&self, scan_code: u32, color: ColorPercent) -> bool {
-        unsafe {
-            sys::LogiLedSetLightingForKeyWithScanCode(
-                scan_code as c_int,
-                color.r.into(),
-                color.g.into(),
-                color.b.into(),
-            )
+    /// Each call replaces the previous exclusion list, it does not add to it.
+    /// Pass an empty list to clear all exclusions.
+    /// The list stays in effect for all later bitmap calls until it is replaced,
+    /// so excluded keys can be driven with the per-key functions on top of a bitmap frame.
+    /// Duplicate keys are removed before being passed to the SDK.
+    ///
+    /// # Errors
+    /// Returns an error if the call fails or there are too many keys.
+    pub fn exclude_keys_from_bitmap<I>(&self, keys: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = KeyName>,
+    {
+        let mut key_list: Vec<KeyName> = Vec::new();
+        for key in keys {
+            if !key_list.contains(&key) {
+                key_list.push(key);
+            }
         }
+        c_int::try_from(key_list.len()).map_err(|_| Error::LengthOutOfRange)?;
+
+        check(
+            self.backend.exclude_keys_from_bitmap(&key_list),
+            "LogiLedExcludeKeysFromBitmap",
+        )
+    }
+
+    /// Set the lighting for a keyboard key by key name.
+    ///
+    /// # Errors
+    /// Returns an error if the call fails.
+    pub fn set_lighting_for_key_with_name(
+        &self,
+        key: KeyName,
+        color: ColorPercent,
+    ) -> Result<(), Error> {
+        check(
+            self.backend.set_lighting_for_key_with_key_name(key, color),
+            "LogiLedSetLightingForKeyWithKeyName",
+        )
+    }
+
+    /// Sets the lighting for a keyboard key by scan code.
+    ///
+    /// Use [`KeyName::scan_code`] to get the scan code of a key.
+    ///
+    /// # Errors
+    /// Returns an error if the call fails.
+    pub fn set_lighting_for_key_with_scan_code(
+        &self,
+        scan_code: u32,
+        color: ColorPercent,
+    ) -> Result<(), Error> {
+        check(
+            self.backend
+                .set_lighting_for_key_with_scan_code(scan_code as c_int, color),
+            "LogiLedSetLightingForKeyWithScanCode",
+        )
     }
 
     /// Sets the lighting for a keyboard key by HID code.
     ///
-    /// # Returns
-    /// Returns true if successful.
-    pub fn set_lighting_for_key_with_hid_code(&self, hid_code: u32, color: ColorPercent) -> bool {
-        unsafe {
-            sys::LogiLedSetLightingForKeyWithHidCode(
-                hid_code as c_int,
-                color.r.into(),
-                color.g.into(),
-                color.b.into(),
-            )
-        }
+    /// Use [`KeyName::hid_usage`] to get the HID code of a key.
+    ///
+    /// # Errors
+    /// Returns an error if the call fails.
+    pub fn set_lighting_for_key_with_hid_code(
+        &self,
+        hid_code: u32,
+        color: ColorPercent,
+    ) -> Result<(), Error> {
+        check(
+            self.backend
+                .set_lighting_for_key_with_hid_code(hid_code as c_int, color),
+            "LogiLedSetLightingForKeyWithHidCode",
+        )
+    }
+
+    /// Sets the lighting for a keyboard key by macOS virtual key code.
+    ///
+    /// Use [`KeyName::quartz_code`] or [`KEY_CODES`](crate::KEY_CODES) to translate from other key codes.
+    ///
+    /// # Errors
+    /// Returns an error if the call fails.
+    pub fn set_lighting_for_key_with_quartz_code(
+        &self,
+        quartz_code: u32,
+        color: ColorPercent,
+    ) -> Result<(), Error> {
+        check(
+            self.backend
+                .set_lighting_for_key_with_quartz_code(quartz_code as c_int, color),
+            "LogiLedSetLightingForKeyWithQuartzCode",
+        )
     }
 
     /// Sets the lighting for a specific device's target zone.
     ///
     /// A zone number is generally different per device, read the offical SDK docs for more info.
     ///
-    /// # Returns
-    /// Returns true if successful.
+    /// # Errors
+    /// Returns an error if the call fails.
     pub fn set_lighting_for_target_zone(
         &self,
         device: DeviceType,
         zone: u32,
         color: ColorPercent,
-    ) -> bool {
-        unsafe {
-            sys::LogiLedSetLightingForTargetZone(
-                device,
-                zone as c_int,
-                color.r.into(),
-                color.g.into(),
-                color.b.into(),
-            )
-        }
+    ) -> Result<(), Error> {
+        check(
+            self.backend
+                .set_lighting_for_target_zone(device, zone as c_int, color),
+            "LogiLedSetLightingForTargetZone",
+        )
     }
 
     /// Save the current lighting, play the effect, and restore the lighting.
@@ -168,33 +484,21 @@ impl Sdk {
     /// If omitted, it will run until manually stopped.
     /// The interval is the time between flashes.
     ///
-    /// # Returns
-    /// Returns false if the call fails or any of the time values are too large.
+    /// # Errors
+    /// Returns an error if the call fails or any of the time values are too large.
     pub fn flash_lighting(
         &self,
         color: ColorPercent,
         duration: Option<Duration>,
         interval: Duration,
-    ) -> bool {
-        let duration = match duration.map(|duration| c_int::try_from(duration.as_millis())) {
-            Some(Ok(duration)) if duration != sys::LOGI_LED_DURATION_INFINITE as c_int => duration,
-            Some(Err(_)) | Some(Ok(_)) => return false,
-            None => sys::LOGI_LED_DURATION_INFINITE as c_int,
-        };
-        let interval = match c_int::try_from(interval.as_millis()) {
-            Ok(interval) => interval,
-            Err(_) => return false,
-        };
+    ) -> Result<(), Error> {
+        let duration = effect_duration_to_millis(duration)?;
+        let interval = duration_to_millis(interval)?;
 
-        unsafe {
-            sys::LogiLedFlashLighting(
-                color.r.into(),
-                color.g.into(),
-                color.b.into(),
-                duration,
-                interval,
-            )
-        }
+        check(
+            self.backend.flash_lighting(color, duration, interval),
+            "LogiLedFlashLighting",
+        )
     }
 
     /// Start a flashing effect on the given key.
@@ -203,51 +507,42 @@ impl Sdk {
     /// If omitted, it will run until manually stopped.
     /// The interval is the time between flashes.
     ///
-    /// # Returns
-    /// Returns false if the call fails or any of the time values are too large.
+    /// # Errors
+    /// Returns an error if the call fails or any of the time values are too large.
     pub fn flash_single_key(
         &self,
         key: KeyName,
         color: ColorPercent,
         duration: Option<Duration>,
         interval: Duration,
-    ) -> bool {
-        let duration = match duration.map(|duration| c_int::try_from(duration.as_millis())) {
-            Some(Ok(duration)) if duration != sys::LOGI_LED_DURATION_INFINITE as c_int => duration,
-            Some(Err(_)) | Some(Ok(_)) => return false,
-            None => sys::LOGI_LED_DURATION_INFINITE as c_int,
-        };
-        let interval = match c_int::try_from(interval.as_millis()) {
-            Ok(interval) => interval,
-            Err(_) => return false,
-        };
+    ) -> Result<(), Error> {
+        let duration = effect_duration_to_millis(duration)?;
+        let interval = duration_to_millis(interval)?;
 
-        unsafe {
-            sys::LogiLedFlashSingleKey(
-                key,
-                color.r.into(),
-                color.g.into(),
-                color.b.into(),
-                duration,
-                interval,
-            )
-        }
+        check(
+            self.backend
+                .flash_single_key(key, color, duration, interval),
+            "LogiLedFlashSingleKey",
+        )
     }
 
     /// Stops all current LED effects.
     ///
-    /// # Returns
-    /// Returns false if the call fails.
-    pub fn stop_effects(&self) -> bool {
-        unsafe { sys::LogiLedStopEffects() }
+    /// # Errors
+    /// Returns an error if the call fails.
+    pub fn stop_effects(&self) -> Result<(), Error> {
+        check(self.backend.stop_effects(), "LogiLedStopEffects")
     }
 
     /// Stops all LED effects on one key.
     ///
-    /// # Returns
-    /// Returns false if the call fails.
-    pub fn stop_effects_on_key(&self, key: KeyName) -> bool {
-        unsafe { sys::LogiLedStopEffectsOnKey(key) }
+    /// # Errors
+    /// Returns an error if the call fails.
+    pub fn stop_effects_on_key(&self, key: KeyName) -> Result<(), Error> {
+        check(
+            self.backend.stop_effects_on_key(key),
+            "LogiLedStopEffectsOnKey",
+        )
     }
 
     /// Save the current lighting, pulse the lighting, then restore the lighting.
@@ -256,41 +551,29 @@ impl Sdk {
     /// If omitted, it will run until manually stopped.
     /// The interval is the time between pulses.
     ///
-    /// # Returns
-    /// Returns false if the call fails or any of the time values are too large.
+    /// # Errors
+    /// Returns an error if the call fails or any of the time values are too large.
     pub fn pulse_lighting(
         &self,
         color: ColorPercent,
         duration: Option<Duration>,
         interval: Duration,
-    ) -> bool {
-        let duration = match duration.map(|duration| c_int::try_from(duration.as_millis())) {
-            Some(Ok(duration)) if duration != sys::LOGI_LED_DURATION_INFINITE as c_int => duration,
-            Some(Err(_)) | Some(Ok(_)) => return false,
-            None => sys::LOGI_LED_DURATION_INFINITE as c_int,
-        };
-        let interval = match c_int::try_from(interval.as_millis()) {
-            Ok(interval) => interval,
-            Err(_) => return false,
-        };
+    ) -> Result<(), Error> {
+        let duration = effect_duration_to_millis(duration)?;
+        let interval = duration_to_millis(interval)?;
 
-        unsafe {
-            sys::LogiLedPulseLighting(
-                color.r.into(),
-                color.g.into(),
-                color.b.into(),
-                duration,
-                interval,
-            )
-        }
+        check(
+            self.backend.pulse_lighting(color, duration, interval),
+            "LogiLedPulseLighting",
+        )
     }
 
     /// Start a pulsing effect on the given key.
     ///
     /// duration controls how long the pulses occur overall.
     ///
-    /// # Returns
-    /// Returns false if the call fails or any of the time values are too large.
+    /// # Errors
+    /// Returns an error if the call fails or any of the time values are too large.
     pub fn pulse_single_key(
         &self,
         key: KeyName,
@@ -298,48 +581,294 @@ impl Sdk {
         end_color: ColorPercent,
         duration: Duration,
         is_infinite: bool,
-    ) -> bool {
-        let duration = match c_int::try_from(duration.as_millis()) {
-            Ok(duration) => duration,
-            Err(_) => return false,
-        };
+    ) -> Result<(), Error> {
+        let duration = duration_to_millis(duration)?;
 
-        unsafe {
-            sys::LogiLedPulseSingleKey(
-                key,
-                start_color.r.into(),
-                start_color.g.into(),
-                start_color.b.into(),
-                end_color.r.into(),
-                end_color.g.into(),
-                end_color.b.into(),
-                duration,
-                is_infinite,
-            )
-        }
+        check(
+            self.backend
+                .pulse_single_key(key, start_color, end_color, duration, is_infinite),
+            "LogiLedPulseSingleKey",
+        )
     }
 
     /// Saves the current lighting config for the given key.
     ///
-    /// # Returns
-    /// Returns false if the call fails.
-    pub fn save_lighting_for_key(&self, key: KeyName) -> bool {
-        unsafe { sys::LogiLedSaveLightingForKey(key) }
+    /// # Errors
+    /// Returns an error if the call fails.
+    pub fn save_lighting_for_key(&self, key: KeyName) -> Result<(), Error> {
+        check(
+            self.backend.save_lighting_for_key(key),
+            "LogiLedSaveLightingForKey",
+        )
     }
 
     /// Restores the current lighting config for the given key.
     ///
-    /// # Returns
-    /// Returns false if the call fails.
-    pub fn restore_lighting_for_key(&self, key: KeyName) -> bool {
-        unsafe { sys::LogiLedRestoreLightingForKey(key) }
+    /// # Errors
+    /// Returns an error if the call fails.
+    pub fn restore_lighting_for_key(&self, key: KeyName) -> Result<(), Error> {
+        check(
+            self.backend.restore_lighting_for_key(key),
+            "LogiLedRestoreLightingForKey",
+        )
     }
 }
 
 impl Drop for Sdk {
     fn drop(&mut self) {
-        unsafe {
-            sys::LogiLedShutdown();
+        self.backend.shutdown();
+    }
+}
+
+/// Turn the return value of an sdk function into a result.
+fn check(valid: bool, function: &'static str) -> Result<(), Error> {
+    if !valid {
+        return Err(Error::CallFailed { function });
+    }
+
+    Ok(())
+}
+
+/// Turn the optional return value of an sdk function into a result.
+fn check_some<T>(value: Option<T>, function: &'static str) -> Result<T, Error> {
+    value.ok_or(Error::CallFailed { function })
+}
+
+/// Ensure that a string does not contain interior NULs.
+fn check_nul(value: &str) -> Result<(), Error> {
+    if value.contains('\0') {
+        return Err(Error::InteriorNul);
+    }
+
+    Ok(())
+}
+
+/// Convert a duration into milliseconds for the sdk.
+fn duration_to_millis(duration: Duration) -> Result<c_int, Error> {
+    c_int::try_from(duration.as_millis()).map_err(|_| Error::DurationOutOfRange)
+}
+
+/// Convert an optional effect duration into milliseconds for the sdk, where None means infinite.
+///
+/// A duration that would be sent as the infinite sentinel is rejected.
+fn effect_duration_to_millis(duration: Option<Duration>) -> Result<c_int, Error> {
+    match duration {
+        Some(duration) => {
+            let duration = duration_to_millis(duration)?;
+            if duration == sys::LOGI_LED_DURATION_INFINITE as c_int {
+                return Err(Error::DurationOutOfRange);
+            }
+            Ok(duration)
         }
+        None => Ok(sys::LOGI_LED_DURATION_INFINITE as c_int),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MockBackend;
+    use crate::MockCall;
+    use crate::MockConfigValue;
+
+    #[test]
+    fn effect_durations() {
+        assert_eq!(
+            effect_duration_to_millis(None),
+            Ok(sys::LOGI_LED_DURATION_INFINITE as c_int)
+        );
+        assert_eq!(
+            effect_duration_to_millis(Some(Duration::from_millis(100))),
+            Ok(100)
+        );
+        assert_eq!(
+            effect_duration_to_millis(Some(Duration::ZERO)),
+            Err(Error::DurationOutOfRange)
+        );
+        assert_eq!(
+            duration_to_millis(Duration::from_secs(u64::MAX)),
+            Err(Error::DurationOutOfRange)
+        );
+    }
+
+    #[test]
+    fn init_and_shutdown() {
+        let backend = MockBackend::new();
+        backend.set_failing("LogiLedInitWithName", true);
+        assert!(matches!(
+            Sdk::new_with_backend_and_name(backend.clone(), "Test"),
+            Err(Error::InitFailed)
+        ));
+        backend.set_failing("LogiLedInitWithName", false);
+
+        assert!(matches!(
+            Sdk::new_with_backend_and_name(backend.clone(), "Te\0st"),
+            Err(Error::InteriorNul)
+        ));
+
+        let sdk = Sdk::new_with_backend_and_name(backend.clone(), "Test")
+            .expect("failed to init mock sdk");
+        assert!(backend.is_initialized());
+        assert_eq!(backend.name().as_deref(), Some("Test"));
+        assert_eq!(sdk.get_version(), Ok((9, 0, 0)));
+
+        drop(sdk);
+        assert!(!backend.is_initialized());
+        assert_eq!(backend.calls().last(), Some(&MockCall::Shutdown));
+    }
+
+    #[cfg(all(feature = "dynamic", target_os = "linux"))]
+    #[test]
+    fn missing_library() {
+        use crate::sys::dynamic::LoadError;
+
+        assert_eq!(Sdk::new().err(), Some(Error::Load(LoadError::NotFound)));
+        assert!(matches!(
+            NativeBackend::from_library_path("libc.so.6"),
+            Err(Error::Load(LoadError::MissingSymbols { .. }))
+        ));
+    }
+
+    #[test]
+    fn lighting() {
+        let backend = MockBackend::new();
+        let sdk = Sdk::new_with_backend(backend.clone()).expect("failed to init mock sdk");
+        let red = ColorPercent::new_rgb(255, 0, 0);
+        let blue = ColorPercent::new_rgb(0, 0, 255);
+
+        sdk.set_target(TargetDevice::PerKeyRgb)
+            .expect("failed to set target");
+        assert_eq!(backend.target_device(), TargetDevice::PerKeyRgb);
+
+        sdk.set_lighting(red).expect("failed to set lighting");
+        assert_eq!(backend.key_color(KeyName::W), Some(red));
+        assert_eq!(backend.zone_color(DeviceType::Mouse, 1), Some(red));
+
+        sdk.set_lighting_for_key_with_name(KeyName::W, blue)
+            .expect("failed to set lighting for key with name");
+        assert_eq!(backend.key_color(KeyName::W), Some(blue));
+
+        let quartz_code = KeyName::A.quartz_code().expect("A has a quartz code");
+        sdk.set_lighting_for_key_with_quartz_code(quartz_code, blue)
+            .expect("failed to set lighting for key with quartz code");
+        assert_eq!(backend.key_color(KeyName::A), Some(blue));
+
+        sdk.set_lighting_for_key_with_hid_code(0x16, blue)
+            .expect("failed to set lighting for key with hid code");
+        assert_eq!(backend.key_color(KeyName::S), Some(blue));
+
+        sdk.set_lighting_for_key_with_scan_code(0x20, blue)
+            .expect("failed to set lighting for key with scan code");
+        assert_eq!(backend.key_color(KeyName::D), Some(blue));
+
+        sdk.set_lighting_for_target_zone(DeviceType::Mouse, 1, blue)
+            .expect("failed to set lighting for target zone");
+        assert_eq!(backend.zone_color(DeviceType::Mouse, 1), Some(blue));
+
+        backend.set_failing("LogiLedSetLighting", true);
+        assert_eq!(
+            sdk.set_lighting(blue),
+            Err(Error::CallFailed {
+                function: "LogiLedSetLighting"
+            })
+        );
+    }
+
+    #[test]
+    fn bitmap_exclusions() {
+        let backend = MockBackend::new();
+        let sdk = Sdk::new_with_backend(backend.clone()).expect("failed to init mock sdk");
+        let red = ColorPercent::new_rgb(255, 0, 0);
+        let blue = ColorPercent::new_rgb(0, 0, 255);
+
+        sdk.set_lighting_for_key_with_name(KeyName::W, blue)
+            .expect("failed to set lighting for key with name");
+        sdk.exclude_keys_from_bitmap([KeyName::W, KeyName::W, KeyName::A])
+            .expect("failed to exclude keys from bitmap");
+        assert_eq!(backend.excluded_keys(), [KeyName::W, KeyName::A]);
+
+        sdk.set_lighting_from_bitmap(&KeyboardBitmap::new_filled(red))
+            .expect("failed to set lighting from bitmap");
+        assert_eq!(backend.key_color(KeyName::W), Some(blue));
+        assert_eq!(backend.key_color(KeyName::Q), Some(red));
+
+        sdk.exclude_keys_from_bitmap([])
+            .expect("failed to exclude keys from bitmap");
+        sdk.set_lighting_from_bitmap(&KeyboardBitmap::new_filled(red))
+            .expect("failed to set lighting from bitmap");
+        assert_eq!(backend.key_color(KeyName::W), Some(red));
+    }
+
+    #[test]
+    fn effects() {
+        let backend = MockBackend::new();
+        let sdk = Sdk::new_with_backend(backend.clone()).expect("failed to init mock sdk");
+        let red = ColorPercent::new_rgb(255, 0, 0);
+        backend.take_calls();
+
+        sdk.flash_lighting(red, None, Duration::from_millis(100))
+            .expect("failed to flash lighting");
+        assert_eq!(
+            sdk.pulse_lighting(red, Some(Duration::ZERO), Duration::from_millis(100)),
+            Err(Error::DurationOutOfRange)
+        );
+        assert_eq!(
+            sdk.flash_single_key(
+                KeyName::L,
+                red,
+                Some(Duration::from_secs(u64::MAX)),
+                Duration::from_millis(100)
+            ),
+            Err(Error::DurationOutOfRange)
+        );
+        assert_eq!(
+            backend.take_calls(),
+            [MockCall::FlashLighting {
+                color: red,
+                duration: 0,
+                interval: 100
+            }]
+        );
+    }
+
+    #[test]
+    fn config() {
+        let backend = MockBackend::new();
+        let sdk = Sdk::new_with_backend(backend.clone()).expect("failed to init mock sdk");
+
+        assert_eq!(sdk.config_number("lighting/speed", 1.5), Ok(1.5));
+        backend.set_config("lighting/speed", MockConfigValue::Number(2.0));
+        assert_eq!(sdk.config_number("lighting/speed", 1.5), Ok(2.0));
+
+        let color = ColorPercent::new_percent(100, 50, 0).unwrap();
+        assert_eq!(sdk.config_color("lighting/color", color), Ok(color));
+        assert_eq!(
+            backend.config("lighting/color"),
+            Some(MockConfigValue::Color((255, 128, 0)))
+        );
+
+        assert_eq!(
+            sdk.config_select("lighting/mode", "Flash", &["Solid", "Flash"]),
+            Ok("Flash".to_string())
+        );
+        assert_eq!(
+            sdk.config_select("lighting/mode", "Pulse", &["Solid", "Flash"]),
+            Err(Error::InvalidConfigDefault)
+        );
+        assert_eq!(
+            sdk.config_range("lighting/brightness", 200, 0, 100),
+            Err(Error::InvalidConfigDefault)
+        );
+        assert_eq!(
+            sdk.config_string("lighting/na\0me", "Test"),
+            Err(Error::InteriorNul)
+        );
+
+        sdk.set_config_label("lighting", "Lighting")
+            .expect("failed to set config label");
+        assert_eq!(
+            backend.config_label("lighting").as_deref(),
+            Some("Lighting")
+        );
     }
 }
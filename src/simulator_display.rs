@@ -0,0 +1,353 @@
+use crate::Clock;
+use crate::ColorPercent;
+use crate::DeviceType;
+use crate::KeyName;
+use crate::KeyboardLayout;
+use crate::SimulatorBackend;
+use crate::StopHandle;
+use crate::DEFAULT_FRAME_RATE;
+use std::fmt::Write as _;
+use std::io::Write;
+use std::os::raw::c_int;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The number of terminal columns for one key unit.
+const COLUMNS_PER_UNIT: f32 = 4.0;
+
+/// The number of terminal columns for one zone.
+const ZONE_COLUMNS: usize = 5;
+
+/// How long to wait for a change when nothing is animating, so a stop is noticed quickly.
+const IDLE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The zone devices drawn below the keyboard.
+const ZONE_DEVICES: &[DeviceType] = &[DeviceType::Mouse, DeviceType::Headset];
+
+/// Short labels that fit on keys, for keys whose names are too long.
+const KEY_LABELS: &[(KeyName, &str)] = &[
+    (KeyName::ESC, "Esc"),
+    (KeyName::PRINT_SCREEN, "PrS"),
+    (KeyName::SCROLL_LOCK, "ScL"),
+    (KeyName::PAUSE_BREAK, "Pau"),
+    (KeyName::TILDE, "`"),
+    (KeyName::ONE, "1"),
+    (KeyName::TWO, "2"),
+    (KeyName::THREE, "3"),
+    (KeyName::FOUR, "4"),
+    (KeyName::FIVE, "5"),
+    (KeyName::SIX, "6"),
+    (KeyName::SEVEN, "7"),
+    (KeyName::EIGHT, "8"),
+    (KeyName::NINE, "9"),
+    (KeyName::ZERO, "0"),
+    (KeyName::MINUS, "-"),
+    (KeyName::EQUALS, "="),
+    (KeyName::BACKSPACE, "Bksp"),
+    (KeyName::INSERT, "Ins"),
+    (KeyName::HOME, "Hom"),
+    (KeyName::PAGE_UP, "PgU"),
+    (KeyName::NUM_LOCK, "Num"),
+    (KeyName::NUM_SLASH, "/"),
+    (KeyName::NUM_ASTERISK, "*"),
+    (KeyName::NUM_MINUS, "-"),
+    (KeyName::TAB, "Tab"),
+    (KeyName::OPEN_BRACKET, "["),
+    (KeyName::CLOSE_BRACKET, "]"),
+    (KeyName::BACKSLASH, "\\"),
+    (KeyName::KEYBOARD_DELETE, "Del"),
+    (KeyName::END, "End"),
+    (KeyName::PAGE_DOWN, "PgD"),
+    (KeyName::NUM_SEVEN, "7"),
+    (KeyName::NUM_EIGHT, "8"),
+    (KeyName::NUM_NINE, "9"),
+    (KeyName::NUM_PLUS, "+"),
+    (KeyName::CAPS_LOCK, "Caps"),
+    (KeyName::SEMICOLON, ";"),
+    (KeyName::APOSTROPHE, "'"),
+    (KeyName::ENTER, "Ent"),
+    (KeyName::NUM_FOUR, "4"),
+    (KeyName::NUM_FIVE, "5"),
+    (KeyName::NUM_SIX, "6"),
+    (KeyName::LEFT_SHIFT, "Shift"),
+    (KeyName::COMMA, ","),
+    (KeyName::PERIOD, "."),
+    (KeyName::FORWARD_SLASH, "/"),
+    (KeyName::RIGHT_SHIFT, "Shift"),
+    (KeyName::ARROW_UP, "↑"),
+    (KeyName::NUM_ONE, "1"),
+    (KeyName::NUM_TWO, "2"),
+    (KeyName::NUM_THREE, "3"),
+    (KeyName::NUM_ENTER, "Ent"),
+    (KeyName::LEFT_CONTROL, "Ctrl"),
+    (KeyName::LEFT_WINDOWS, "Win"),
+    (KeyName::LEFT_ALT, "Alt"),
+    (KeyName::SPACE, "Space"),
+    (KeyName::RIGHT_ALT, "Alt"),
+    (KeyName::RIGHT_WINDOWS, "Win"),
+    (KeyName::APPLICATION_SELECT, "Menu"),
+    (KeyName::RIGHT_CONTROL, "Ctrl"),
+    (KeyName::ARROW_LEFT, "←"),
+    (KeyName::ARROW_DOWN, "↓"),
+    (KeyName::ARROW_RIGHT, "→"),
+    (KeyName::NUM_ZERO, "0"),
+    (KeyName::NUM_PERIOD, "."),
+    (KeyName::G_1, "G1"),
+    (KeyName::G_2, "G2"),
+    (KeyName::G_3, "G3"),
+    (KeyName::G_4, "G4"),
+    (KeyName::G_5, "G5"),
+    (KeyName::G_6, "G6"),
+    (KeyName::G_7, "G7"),
+    (KeyName::G_8, "G8"),
+    (KeyName::G_9, "G9"),
+    (KeyName::G_LOGO, "Lg"),
+    (KeyName::G_BADGE, "Bdg"),
+];
+
+/// Get the label drawn on a key.
+fn key_label(key: KeyName) -> &'static str {
+    KEY_LABELS
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map_or_else(|| key.name(), |(_, label)| label)
+}
+
+/// A cell of the terminal grid.
+#[derive(Debug, Clone, Copy)]
+struct Cell {
+    /// The background color, or `None` to use the terminal's colors.
+    color: Option<ColorPercent>,
+    text: char,
+}
+
+impl Cell {
+    /// A cell with nothing in it.
+    const EMPTY: Self = Self {
+        color: None,
+        text: ' ',
+    };
+}
+
+/// Draws the lighting of a [`SimulatorBackend`] in a terminal, using ANSI truecolor escapes.
+///
+/// The keyboard is drawn from a [`KeyboardLayout`], with the mouse and headset zones below it.
+/// Call [`SimulatorDisplay::render`] to draw a single frame,
+/// or [`SimulatorDisplay::run`] to redraw the lighting as it changes until stopped with a [`StopHandle`].
+#[derive(Debug)]
+pub struct SimulatorDisplay {
+    layout: KeyboardLayout,
+    frame_interval: Duration,
+    stop_requested: Arc<AtomicBool>,
+}
+
+impl SimulatorDisplay {
+    /// Create a new display of a full-size ANSI keyboard.
+    pub fn new() -> Self {
+        let mut display = Self {
+            layout: KeyboardLayout::ansi(),
+            frame_interval: Duration::ZERO,
+            stop_requested: Arc::new(AtomicBool::new(false)),
+        };
+        display.set_frame_rate(DEFAULT_FRAME_RATE);
+        display
+    }
+
+    /// Set the layout used to draw the keyboard.
+    pub fn set_layout(&mut self, layout: KeyboardLayout) {
+        self.layout = layout;
+    }
+
+    /// Set how often effects are redrawn, in frames per second.
+    ///
+    /// A frame rate of 0 is treated as 1.
+    pub fn set_frame_rate(&mut self, frame_rate: u32) {
+        self.frame_interval = Duration::from_secs(1) / frame_rate.max(1);
+    }
+
+    /// Get a handle that stops [`SimulatorDisplay::run`] from another thread.
+    pub fn stop_handle(&self) -> StopHandle {
+        StopHandle::new(self.stop_requested.clone())
+    }
+
+    /// Draw the current lighting of a simulator.
+    ///
+    /// # Returns
+    /// Returns the lines of the drawing, each ending with a newline.
+    /// Every line resets its colors and clears the rest of the terminal line.
+    pub fn render<C>(&self, backend: &SimulatorBackend<C>) -> String
+    where
+        C: Clock,
+    {
+        let mut rows = self.render_keyboard(backend);
+        rows.push(Vec::new());
+        for device in ZONE_DEVICES.iter().copied() {
+            rows.push(render_zones(backend, device));
+        }
+
+        let mut output = String::new();
+        for row in rows {
+            let mut current = None;
+            for cell in row {
+                match (cell.color, current) {
+                    (new, current) if new == current => {}
+                    (Some(color), _) => {
+                        let rgb = color.to_rgb();
+                        let luminance = (0.299 * f32::from(rgb.r))
+                            + (0.587 * f32::from(rgb.g))
+                            + (0.114 * f32::from(rgb.b));
+                        let foreground = if luminance > 128.0 { 0 } else { 255 };
+                        let _ = write!(
+                            output,
+                            "\x1b[48;2;{};{};{}m\x1b[38;2;{foreground};{foreground};{foreground}m",
+                            rgb.r, rgb.g, rgb.b
+                        );
+                        current = Some(color);
+                    }
+                    (None, _) => {
+                        output.push_str("\x1b[0m");
+                        current = None;
+                    }
+                }
+                output.push(cell.text);
+            }
+            output.push_str("\x1b[0m\x1b[K\n");
+        }
+
+        output
+    }
+
+    /// Draw the keyboard onto a grid of cells.
+    fn render_keyboard<C>(&self, backend: &SimulatorBackend<C>) -> Vec<Vec<Cell>>
+    where
+        C: Clock,
+    {
+        let Some(bounds) = self.layout.bounds() else {
+            return Vec::new();
+        };
+        let column = |x: f32| ((x - bounds.x) * COLUMNS_PER_UNIT).round() as usize;
+        let row = |y: f32| (y - bounds.y).round() as usize;
+
+        let width = column(bounds.x + bounds.width);
+        let height = row(bounds.y + bounds.height);
+        let mut grid = vec![vec![Cell::EMPTY; width]; height];
+        for (key, rect) in self.layout.iter() {
+            let color = backend.key_color(key);
+            let (left, right) = (column(rect.x), column(rect.x + rect.width));
+            let (top, bottom) = (row(rect.y), row(rect.y + rect.height));
+
+            // Leave a gap of one column between keys.
+            let right = right.saturating_sub(1).max(left + 1);
+            let bottom = bottom.max(top + 1);
+
+            let label: Vec<char> = key_label(key).chars().take(right - left).collect();
+            let label_row = top + ((bottom - top - 1) / 2);
+            let label_start = left + ((right - left - label.len()) / 2);
+            for (y, cells) in grid.iter_mut().enumerate().take(bottom).skip(top) {
+                for (x, cell) in cells.iter_mut().enumerate().take(right).skip(left) {
+                    let text = if y == label_row && x >= label_start {
+                        label.get(x - label_start).copied().unwrap_or(' ')
+                    } else {
+                        ' '
+                    };
+                    *cell = Cell {
+                        color: Some(color),
+                        text,
+                    };
+                }
+            }
+        }
+
+        grid
+    }
+
+    /// Redraw the lighting of a simulator whenever it changes, until stopped with a [`StopHandle`].
+    ///
+    /// The terminal is cleared first, and every frame is drawn from the top left corner.
+    /// While effects are playing, frames are drawn at the frame rate.
+    ///
+    /// # Errors
+    /// Returns an error if writing to `out` fails.
+    pub fn run<C, W>(&self, backend: &SimulatorBackend<C>, mut out: W) -> std::io::Result<()>
+    where
+        C: Clock,
+        W: Write,
+    {
+        out.write_all(b"\x1b[2J")?;
+
+        let mut drawn_generation = None;
+        while !self.stop_requested.swap(false, Ordering::SeqCst) {
+            let generation = backend.generation();
+            let has_effects = backend.has_effects();
+            if has_effects || drawn_generation != Some(generation) {
+                write!(out, "\x1b[H{}", self.render(backend))?;
+                out.flush()?;
+                drawn_generation = Some(generation);
+            }
+
+            let timeout = if has_effects {
+                self.frame_interval
+            } else {
+                IDLE_INTERVAL
+            };
+            backend.wait_for_change(generation, timeout);
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for SimulatorDisplay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Draw the zones of a device as a row of numbered blocks after the name of the device.
+fn render_zones<C>(backend: &SimulatorBackend<C>, device: DeviceType) -> Vec<Cell>
+where
+    C: Clock,
+{
+    let mut row: Vec<Cell> = format!("{:<10}", device.name())
+        .chars()
+        .map(|text| Cell { color: None, text })
+        .collect();
+    for zone in 0..device.zone_count() {
+        let color = backend.zone_color(device, zone as c_int);
+        let label = format!("{zone:^width$}", width = ZONE_COLUMNS - 1);
+        row.extend(label.chars().map(|text| Cell {
+            color: Some(color),
+            text,
+        }));
+        row.push(Cell::EMPTY);
+    }
+
+    row
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ManualClock;
+    use crate::Sdk;
+
+    #[test]
+    fn render() {
+        let backend = SimulatorBackend::with_clock(ManualClock::new());
+        let sdk = Sdk::new_with_backend(backend.clone()).expect("failed to init simulator");
+        sdk.set_lighting_for_key_with_name(KeyName::ESC, ColorPercent::RED)
+            .expect("failed to set key");
+        sdk.set_lighting_for_target_zone(DeviceType::Headset, 1, ColorPercent::GREEN)
+            .expect("failed to set zone");
+
+        let display = SimulatorDisplay::new();
+        let output = display.render(&backend);
+        assert!(output.contains("\x1b[48;2;255;0;0m"));
+        assert!(output.contains("\x1b[48;2;0;255;0m"));
+        assert!(output.contains("Esc"));
+        assert!(output.contains("Headset"));
+        assert!(output.lines().all(|line| line.ends_with("\x1b[0m\x1b[K")));
+    }
+}
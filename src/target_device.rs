@@ -13,3 +13,79 @@ bitflags::bitflags! {
         const All = sys::LOGI_DEVICETYPE_ALL;
     }
 }
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for TargetDevice {
+    /// Serialize as a list of flag names, like `["Monochrome", "Rgb"]`.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.iter_names().map(|(name, _)| name))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for TargetDevice {
+    /// Deserialize from a list of flag names, like `["Monochrome", "Rgb"]`.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct TargetDeviceVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for TargetDeviceVisitor {
+            type Value = TargetDevice;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a list of target device names")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let mut target_device = TargetDevice::empty();
+                while let Some(name) = seq.next_element::<std::borrow::Cow<'_, str>>()? {
+                    let flag = TargetDevice::from_name(&name).ok_or_else(|| {
+                        serde::de::Error::invalid_value(
+                            serde::de::Unexpected::Str(&name),
+                            &"one of Monochrome, PerKeyRgb, Rgb or All",
+                        )
+                    })?;
+                    target_device |= flag;
+                }
+
+                Ok(target_device)
+            }
+        }
+
+        deserializer.deserialize_seq(TargetDeviceVisitor)
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn serde_round_trip() {
+        for bits in 0..=TargetDevice::All.bits() {
+            let target_device = TargetDevice::from_bits_truncate(bits);
+            let json = serde_json::to_string(&target_device).expect("failed to serialize");
+            let parsed: TargetDevice = serde_json::from_str(&json).expect("failed to deserialize");
+            assert_eq!(parsed, target_device);
+        }
+
+        assert_eq!(
+            serde_json::to_string(&(TargetDevice::Monochrome | TargetDevice::Rgb))
+                .expect("failed to serialize"),
+            r#"["Monochrome","Rgb"]"#
+        );
+        assert_eq!(
+            serde_json::from_str::<TargetDevice>(r#"["All"]"#).expect("failed to deserialize"),
+            TargetDevice::All
+        );
+        assert!(serde_json::from_str::<TargetDevice>(r#"["Monitor"]"#).is_err());
+    }
+}
@@ -0,0 +1,986 @@
+use crate::sys;
+use crate::Call;
+use crate::CallResult;
+use crate::ColorPercent;
+use crate::DeviceType;
+use crate::KeyName;
+use crate::KeyboardBitmap;
+use crate::TargetDevice;
+use crate::TraceError;
+use std::fmt::Write as _;
+use std::os::raw::c_int;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// The first line of every trace, naming the format and its version.
+pub const TRACE_HEADER: &str = "logiled-trace 1";
+
+/// The size of a bitmap, in bytes.
+const BITMAP_SIZE: usize = sys::LOGI_LED_BITMAP_SIZE as usize;
+
+/// A list of calls made to the sdk, with when they were made and what they returned.
+///
+/// Traces are recorded with a [`RecordingSdk`](crate::RecordingSdk) and played back with a [`Replayer`](crate::Replayer).
+///
+/// # Format
+/// A trace is a text file that starts with the line [`TRACE_HEADER`], followed by one call per line.
+/// Empty lines and lines starting with `#` are ignored.
+///
+/// ```text
+/// logiled-trace 1
+/// 0 LogiLedInitWithName "game" = true
+/// 15 LogiLedGetConfigOptionNumber "lighting/speed" 1.5 = 2
+/// 1520 LogiLedSetLighting 100,0,0 = true
+/// 34000 LogiLedExcludeKeysFromBitmap W A = true
+/// 51200 LogiLedSetLightingForTargetZone Mouse 1 0,100,0 = false
+/// 90000 LogiLedShutdown
+/// ```
+///
+/// Each call has the time since recording started in microseconds, the name of the sdk function,
+/// the arguments in the order the sdk function takes them, then `=` and what the call returned.
+/// `LogiLedShutdown` returns nothing, so it has no `=`.
+/// Values are separated by single spaces, and are written as:
+///  * integers and numbers in decimal, like `-2` or `1.5`, and bools as `true` or `false`;
+///  * strings in double quotes, escaping `"` and `\` with `\`, and control characters as `\n`, `\r`, `\t` or `\u{7f}`;
+///  * colors as comma-separated red, green and blue percentages, like `100,50,0`;
+///  * color config options as comma-separated red, green and blue values from 0-255,
+///    and rectangle config options as the comma-separated x, y, width and height;
+///  * keys and device types by name, like `NUM_ENTER` or `Mouse`;
+///  * target devices as flag names separated by `|`, like `PerKeyRgb|Rgb`;
+///  * bitmaps as their 504 BGRA bytes in lowercase hex.
+///
+/// The keys of `LogiLedExcludeKeysFromBitmap` and the options of `LogiLedGetConfigOptionSelect` are written as separate values at the end of the arguments.
+/// Calls that do not get a value return `true` or `false`.
+/// Calls that get a value return the value, or `none` if they failed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Trace {
+    events: Vec<TraceEvent>,
+}
+
+impl Trace {
+    /// Create an empty trace.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a trace from a file.
+    ///
+    /// # Errors
+    /// Returns an error if the file could not be read or is not a valid trace.
+    pub fn load<P>(path: P) -> Result<Self, TraceError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let input = std::fs::read_to_string(path).map_err(|error| TraceError::Io {
+            path: path.into(),
+            error,
+        })?;
+
+        input
+            .parse()
+            .map_err(|error: TraceError| error.with_path(path.into()))
+    }
+
+    /// Get the calls in this trace, in the order they were made.
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+
+    /// Add a call to the end of this trace.
+    pub fn push(&mut self, event: TraceEvent) {
+        self.events.push(event);
+    }
+}
+
+impl From<Vec<TraceEvent>> for Trace {
+    fn from(events: Vec<TraceEvent>) -> Self {
+        Self { events }
+    }
+}
+
+impl FromStr for Trace {
+    type Err = TraceError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut lines = input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        match lines.next() {
+            Some((_, TRACE_HEADER)) => {}
+            Some((line, _)) => {
+                return Err(TraceError::Parse {
+                    path: None,
+                    line,
+                    message: format!("expected \"{TRACE_HEADER}\""),
+                });
+            }
+            None => {
+                return Err(TraceError::Parse {
+                    path: None,
+                    line: 1,
+                    message: "the trace is empty".into(),
+                });
+            }
+        }
+
+        let events = lines
+            .map(|(line, input)| {
+                parse_event(input).map_err(|message| TraceError::Parse {
+                    path: None,
+                    line,
+                    message,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { events })
+    }
+}
+
+impl std::fmt::Display for Trace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{TRACE_HEADER}")?;
+        for event in self.events.iter() {
+            writeln!(f, "{event}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A call in a [`Trace`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceEvent {
+    /// The time since recording started.
+    pub time: Duration,
+
+    /// The call.
+    pub call: Call,
+
+    /// What the call returned.
+    pub result: CallResult,
+}
+
+impl std::fmt::Display for TraceEvent {
+    /// Format this event as a line of a trace, without the line ending.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut line = format!("{} {}", self.time.as_micros(), self.call.function());
+        let mut push = |value: &dyn std::fmt::Display| {
+            let _ = write!(line, " {value}");
+        };
+
+        match &self.call {
+            Call::Init
+            | Call::GetSdkVersion
+            | Call::SaveCurrentLighting
+            | Call::RestoreLighting
+            | Call::StopEffects
+            | Call::Shutdown => {}
+            Call::InitWithName { name } => push(&Quoted(name)),
+            Call::GetConfigOptionNumber { path, default } => {
+                push(&Quoted(path));
+                push(default);
+            }
+            Call::GetConfigOptionBool { path, default } => {
+                push(&Quoted(path));
+                push(default);
+            }
+            Call::GetConfigOptionColor { path, default } => {
+                push(&Quoted(path));
+                push(&Ints(&[default.0, default.1, default.2]));
+            }
+            Call::GetConfigOptionRect { path, default } => {
+                push(&Quoted(path));
+                push(&Ints(&[default.0, default.1, default.2, default.3]));
+            }
+            Call::GetConfigOptionString { path, default }
+            | Call::GetConfigOptionKeyInput { path, default } => {
+                push(&Quoted(path));
+                push(&Quoted(default));
+            }
+            Call::GetConfigOptionSelect {
+                path,
+                default,
+                options,
+            } => {
+                push(&Quoted(path));
+                push(&Quoted(default));
+                for option in options {
+                    push(&Quoted(option));
+                }
+            }
+            Call::GetConfigOptionRange {
+                path,
+                default,
+                min,
+                max,
+            } => {
+                push(&Quoted(path));
+                push(default);
+                push(min);
+                push(max);
+            }
+            Call::SetConfigOptionLabel { path, label } => {
+                push(&Quoted(path));
+                push(&Quoted(label));
+            }
+            Call::SetTargetDevice { target_device } => push(&Target(*target_device)),
+            Call::SetLighting { color } => push(&Percent(*color)),
+            Call::FlashLighting {
+                color,
+                duration,
+                interval,
+            }
+            | Call::PulseLighting {
+                color,
+                duration,
+                interval,
+            } => {
+                push(&Percent(*color));
+                push(duration);
+                push(interval);
+            }
+            Call::SetLightingFromBitmap { bitmap } => push(&Hex(bitmap.as_bgra_bytes())),
+            Call::SetLightingForKeyWithScanCode {
+                scan_code: code,
+                color,
+            }
+            | Call::SetLightingForKeyWithHidCode {
+                hid_code: code,
+                color,
+            }
+            | Call::SetLightingForKeyWithQuartzCode {
+                quartz_code: code,
+                color,
+            } => {
+                push(code);
+                push(&Percent(*color));
+            }
+            Call::SetLightingForKeyWithKeyName { key, color } => {
+                push(&key.name());
+                push(&Percent(*color));
+            }
+            Call::SaveLightingForKey { key }
+            | Call::RestoreLightingForKey { key }
+            | Call::StopEffectsOnKey { key } => push(&key.name()),
+            Call::ExcludeKeysFromBitmap { keys } => {
+                for key in keys {
+                    push(&key.name());
+                }
+            }
+            Call::FlashSingleKey {
+                key,
+                color,
+                duration,
+                interval,
+            } => {
+                push(&key.name());
+                push(&Percent(*color));
+                push(duration);
+                push(interval);
+            }
+            Call::PulseSingleKey {
+                key,
+                start_color,
+                end_color,
+                duration,
+                is_infinite,
+            } => {
+                push(&key.name());
+                push(&Percent(*start_color));
+                push(&Percent(*end_color));
+                push(duration);
+                push(is_infinite);
+            }
+            Call::SetLightingForTargetZone {
+                device,
+                zone,
+                color,
+            } => {
+                push(&device.name());
+                push(zone);
+                push(&Percent(*color));
+            }
+        }
+
+        match &self.result {
+            CallResult::Unit => {}
+            CallResult::Success(value) | CallResult::Bool(value) => {
+                push(&format_args!("= {value}"))
+            }
+            CallResult::Failed => push(&"= none"),
+            CallResult::Version((major, minor, build)) => {
+                push(&format_args!("= {}", Ints(&[*major, *minor, *build])))
+            }
+            CallResult::Number(value) => push(&format_args!("= {value}")),
+            CallResult::Color((r, g, b)) => push(&format_args!("= {}", Ints(&[*r, *g, *b]))),
+            CallResult::Rect((x, y, width, height)) => {
+                push(&format_args!("= {}", Ints(&[*x, *y, *width, *height])))
+            }
+            CallResult::String(value) => push(&format_args!("= {}", Quoted(value))),
+            CallResult::Range(value) => push(&format_args!("= {value}")),
+        }
+
+        f.write_str(&line)
+    }
+}
+
+/// A string, written in double quotes.
+struct Quoted<'a>(&'a str);
+
+impl std::fmt::Display for Quoted<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_char('"')?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c.is_control() => write!(f, "\\u{{{:x}}}", u32::from(c))?,
+                c => f.write_char(c)?,
+            }
+        }
+        f.write_char('"')
+    }
+}
+
+/// Integers, separated by commas.
+struct Ints<'a>(&'a [c_int]);
+
+impl std::fmt::Display for Ints<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, value) in self.0.iter().enumerate() {
+            if index != 0 {
+                f.write_char(',')?;
+            }
+            write!(f, "{value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A color, written as percentages.
+struct Percent(ColorPercent);
+
+impl std::fmt::Display for Percent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{},{}", self.0.r, self.0.g, self.0.b)
+    }
+}
+
+/// Target devices, written as flag names separated by `|`.
+struct Target(TargetDevice);
+
+impl std::fmt::Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_empty() {
+            return f.write_str("0x0");
+        }
+
+        let mut flags = String::new();
+        bitflags::parser::to_writer(&self.0, &mut flags)?;
+        f.write_str(&flags.replace(' ', ""))
+    }
+}
+
+/// Bytes, written in lowercase hex.
+struct Hex<'a>(&'a [u8]);
+
+impl std::fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A value on a line of a trace.
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    /// A value without quotes.
+    Word(&'a str),
+
+    /// A string in double quotes, with escapes removed.
+    Quoted(String),
+}
+
+/// Split a line of a trace into values.
+fn tokenize(line: &str) -> Result<Vec<Token<'_>>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = line.trim_start();
+    while !rest.is_empty() {
+        if let Some(quoted) = rest.strip_prefix('"') {
+            let mut value = String::new();
+            let mut chars = quoted.char_indices();
+            let end = loop {
+                let (index, c) = chars.next().ok_or("unterminated string")?;
+                match c {
+                    '"' => break index + 1,
+                    '\\' => {
+                        let (_, escape) = chars.next().ok_or("unterminated string")?;
+                        value.push(match escape {
+                            '"' => '"',
+                            '\\' => '\\',
+                            'n' => '\n',
+                            'r' => '\r',
+                            't' => '\t',
+                            'u' => {
+                                let rest = chars.as_str();
+                                let hex = rest
+                                    .strip_prefix('{')
+                                    .and_then(|rest| rest.split_once('}'))
+                                    .map(|(hex, _)| hex)
+                                    .ok_or("invalid unicode escape")?;
+                                let c = u32::from_str_radix(hex, 16)
+                                    .ok()
+                                    .and_then(char::from_u32)
+                                    .ok_or("invalid unicode escape")?;
+                                // Skip the braces and the digits.
+                                for _ in 0..hex.len() + 2 {
+                                    chars.next();
+                                }
+                                c
+                            }
+                            escape => return Err(format!("invalid escape \"\\{escape}\"")),
+                        });
+                    }
+                    c => value.push(c),
+                }
+            };
+
+            rest = &quoted[end..];
+            if !rest.is_empty() && !rest.starts_with(' ') {
+                return Err("expected a space after a string".into());
+            }
+            tokens.push(Token::Quoted(value));
+        } else {
+            let end = rest.find(' ').unwrap_or(rest.len());
+            tokens.push(Token::Word(&rest[..end]));
+            rest = &rest[end..];
+        }
+        rest = rest.trim_start();
+    }
+
+    Ok(tokens)
+}
+
+/// Reads values from a line of a trace.
+struct LineParser<'a> {
+    tokens: std::iter::Peekable<std::vec::IntoIter<Token<'a>>>,
+}
+
+impl<'a> LineParser<'a> {
+    /// Returns true if there are no more arguments.
+    fn at_result(&mut self) -> bool {
+        matches!(self.tokens.peek(), None | Some(Token::Word("=")))
+    }
+
+    /// Read a value without quotes.
+    fn word(&mut self, what: &str) -> Result<&'a str, String> {
+        match self.tokens.next() {
+            Some(Token::Word(word)) if word != "=" => Ok(word),
+            _ => Err(format!("expected {what}")),
+        }
+    }
+
+    /// Read a value without quotes, parsing it with `parse`.
+    fn parse<T>(&mut self, what: &str, parse: impl FnOnce(&str) -> Option<T>) -> Result<T, String> {
+        let word = self.word(what)?;
+        parse(word).ok_or_else(|| format!("expected {what}, found \"{word}\""))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        match self.tokens.next() {
+            Some(Token::Quoted(value)) => Ok(value),
+            _ => Err("expected a string".into()),
+        }
+    }
+
+    fn int(&mut self) -> Result<c_int, String> {
+        self.parse("an integer", |word| word.parse().ok())
+    }
+
+    fn number(&mut self) -> Result<f64, String> {
+        self.parse("a number", |word| word.parse().ok())
+    }
+
+    fn bool(&mut self) -> Result<bool, String> {
+        self.parse("true or false", |word| word.parse().ok())
+    }
+
+    fn ints<const N: usize>(&mut self, what: &str) -> Result<[c_int; N], String> {
+        self.parse(what, |word| {
+            let values: Vec<c_int> = word
+                .split(',')
+                .map(|value| value.parse().ok())
+                .collect::<Option<_>>()?;
+            values.try_into().ok()
+        })
+    }
+
+    fn color(&mut self) -> Result<ColorPercent, String> {
+        self.parse("a color", |word| {
+            let mut values = word.split(',').map(|value| value.parse().ok());
+            let color =
+                ColorPercent::new_percent(values.next()??, values.next()??, values.next()??)?;
+            values.next().is_none().then_some(color)
+        })
+    }
+
+    fn key(&mut self) -> Result<KeyName, String> {
+        self.parse("a key name", KeyName::from_name)
+    }
+
+    fn device(&mut self) -> Result<DeviceType, String> {
+        self.parse("a device type", DeviceType::from_name)
+    }
+
+    fn target_device(&mut self) -> Result<TargetDevice, String> {
+        self.parse("target devices", |word| {
+            bitflags::parser::from_str(word).ok()
+        })
+    }
+
+    fn bitmap(&mut self) -> Result<Box<KeyboardBitmap>, String> {
+        self.parse("a bitmap", |word| {
+            if word.len() != BITMAP_SIZE * 2 {
+                return None;
+            }
+
+            let mut data = [0; BITMAP_SIZE];
+            for (byte, hex) in data.iter_mut().zip(word.as_bytes().chunks(2)) {
+                *byte = u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?;
+            }
+            Some(Box::new(KeyboardBitmap::from_bgra_bytes(data)))
+        })
+    }
+
+    /// Read `=` and a value with `parse`, or `none`.
+    fn result(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<CallResult, String>,
+    ) -> Result<CallResult, String> {
+        if self.tokens.next() != Some(Token::Word("=")) {
+            return Err("expected \"=\" and a result".into());
+        }
+        if self.tokens.peek() == Some(&Token::Word("none")) {
+            self.tokens.next();
+            return Ok(CallResult::Failed);
+        }
+        parse(self)
+    }
+}
+
+/// Parse a line of a trace.
+fn parse_event(line: &str) -> Result<TraceEvent, String> {
+    let mut parser = LineParser {
+        tokens: tokenize(line)?.into_iter().peekable(),
+    };
+
+    let time = parser.parse("a time in microseconds", |word| word.parse().ok())?;
+    let function = parser.word("a function name")?;
+
+    let success = |parser: &mut LineParser<'_>| parser.bool().map(CallResult::Success);
+    let string = |parser: &mut LineParser<'_>| parser.string().map(CallResult::String);
+    let (call, result) = match function {
+        "LogiLedInit" => (Call::Init, parser.result(success)?),
+        "LogiLedInitWithName" => {
+            let name = parser.string()?;
+            (Call::InitWithName { name }, parser.result(success)?)
+        }
+        "LogiLedGetSdkVersion" => {
+            let result = parser.result(|parser| {
+                let [major, minor, build] = parser.ints("a version")?;
+                Ok(CallResult::Version((major, minor, build)))
+            })?;
+            (Call::GetSdkVersion, result)
+        }
+        "LogiLedGetConfigOptionNumber" => {
+            let path = parser.string()?;
+            let default = parser.number()?;
+            let result = parser.result(|parser| parser.number().map(CallResult::Number))?;
+            (Call::GetConfigOptionNumber { path, default }, result)
+        }
+        "LogiLedGetConfigOptionBool" => {
+            let path = parser.string()?;
+            let default = parser.bool()?;
+            let result = parser.result(|parser| parser.bool().map(CallResult::Bool))?;
+            (Call::GetConfigOptionBool { path, default }, result)
+        }
+        "LogiLedGetConfigOptionColor" => {
+            let path = parser.string()?;
+            let [r, g, b] = parser.ints("a color")?;
+            let result = parser.result(|parser| {
+                let [r, g, b] = parser.ints("a color")?;
+                Ok(CallResult::Color((r, g, b)))
+            })?;
+            let default = (r, g, b);
+            (Call::GetConfigOptionColor { path, default }, result)
+        }
+        "LogiLedGetConfigOptionRect" => {
+            let path = parser.string()?;
+            let [x, y, width, height] = parser.ints("a rectangle")?;
+            let result = parser.result(|parser| {
+                let [x, y, width, height] = parser.ints("a rectangle")?;
+                Ok(CallResult::Rect((x, y, width, height)))
+            })?;
+            let default = (x, y, width, height);
+            (Call::GetConfigOptionRect { path, default }, result)
+        }
+        "LogiLedGetConfigOptionString" => {
+            let path = parser.string()?;
+            let default = parser.string()?;
+            let result = parser.result(string)?;
+            (Call::GetConfigOptionString { path, default }, result)
+        }
+        "LogiLedGetConfigOptionKeyInput" => {
+            let path = parser.string()?;
+            let default = parser.string()?;
+            let result = parser.result(string)?;
+            (Call::GetConfigOptionKeyInput { path, default }, result)
+        }
+        "LogiLedGetConfigOptionSelect" => {
+            let path = parser.string()?;
+            let default = parser.string()?;
+            let mut options = Vec::new();
+            while !parser.at_result() {
+                options.push(parser.string()?);
+            }
+            let result = parser.result(string)?;
+            let call = Call::GetConfigOptionSelect {
+                path,
+                default,
+                options,
+            };
+            (call, result)
+        }
+        "LogiLedGetConfigOptionRange" => {
+            let path = parser.string()?;
+            let default = parser.int()?;
+            let min = parser.int()?;
+            let max = parser.int()?;
+            let result = parser.result(|parser| parser.int().map(CallResult::Range))?;
+            let call = Call::GetConfigOptionRange {
+                path,
+                default,
+                min,
+                max,
+            };
+            (call, result)
+        }
+        "LogiLedSetConfigOptionLabel" => {
+            let path = parser.string()?;
+            let label = parser.string()?;
+            let result = parser.result(success)?;
+            (Call::SetConfigOptionLabel { path, label }, result)
+        }
+        "LogiLedSetTargetDevice" => {
+            let target_device = parser.target_device()?;
+            let result = parser.result(success)?;
+            (Call::SetTargetDevice { target_device }, result)
+        }
+        "LogiLedSaveCurrentLighting" => (Call::SaveCurrentLighting, parser.result(success)?),
+        "LogiLedSetLighting" => {
+            let color = parser.color()?;
+            (Call::SetLighting { color }, parser.result(success)?)
+        }
+        "LogiLedRestoreLighting" => (Call::RestoreLighting, parser.result(success)?),
+        "LogiLedFlashLighting" | "LogiLedPulseLighting" => {
+            let color = parser.color()?;
+            let duration = parser.int()?;
+            let interval = parser.int()?;
+            let result = parser.result(success)?;
+            let call = if function == "LogiLedFlashLighting" {
+                Call::FlashLighting {
+                    color,
+                    duration,
+                    interval,
+                }
+            } else {
+                Call::PulseLighting {
+                    color,
+                    duration,
+                    interval,
+                }
+            };
+            (call, result)
+        }
+        "LogiLedStopEffects" => (Call::StopEffects, parser.result(success)?),
+        "LogiLedSetLightingFromBitmap" => {
+            let bitmap = parser.bitmap()?;
+            let result = parser.result(success)?;
+            (Call::SetLightingFromBitmap { bitmap }, result)
+        }
+        "LogiLedSetLightingForKeyWithScanCode" => {
+            let scan_code = parser.int()?;
+            let color = parser.color()?;
+            let result = parser.result(success)?;
+            (
+                Call::SetLightingForKeyWithScanCode { scan_code, color },
+                result,
+            )
+        }
+        "LogiLedSetLightingForKeyWithHidCode" => {
+            let hid_code = parser.int()?;
+            let color = parser.color()?;
+            let result = parser.result(success)?;
+            (
+                Call::SetLightingForKeyWithHidCode { hid_code, color },
+                result,
+            )
+        }
+        "LogiLedSetLightingForKeyWithQuartzCode" => {
+            let quartz_code = parser.int()?;
+            let color = parser.color()?;
+            let result = parser.result(success)?;
+            let call = Call::SetLightingForKeyWithQuartzCode { quartz_code, color };
+            (call, result)
+        }
+        "LogiLedSetLightingForKeyWithKeyName" => {
+            let key = parser.key()?;
+            let color = parser.color()?;
+            let result = parser.result(success)?;
+            (Call::SetLightingForKeyWithKeyName { key, color }, result)
+        }
+        "LogiLedSaveLightingForKey" => {
+            let key = parser.key()?;
+            (Call::SaveLightingForKey { key }, parser.result(success)?)
+        }
+        "LogiLedRestoreLightingForKey" => {
+            let key = parser.key()?;
+            (Call::RestoreLightingForKey { key }, parser.result(success)?)
+        }
+        "LogiLedExcludeKeysFromBitmap" => {
+            let mut keys = Vec::new();
+            while !parser.at_result() {
+                keys.push(parser.key()?);
+            }
+            (
+                Call::ExcludeKeysFromBitmap { keys },
+                parser.result(success)?,
+            )
+        }
+        "LogiLedFlashSingleKey" => {
+            let key = parser.key()?;
+            let color = parser.color()?;
+            let duration = parser.int()?;
+            let interval = parser.int()?;
+            let result = parser.result(success)?;
+            let call = Call::FlashSingleKey {
+                key,
+                color,
+                duration,
+                interval,
+            };
+            (call, result)
+        }
+        "LogiLedPulseSingleKey" => {
+            let key = parser.key()?;
+            let start_color = parser.color()?;
+            let end_color = parser.color()?;
+            let duration = parser.int()?;
+            let is_infinite = parser.bool()?;
+            let result = parser.result(success)?;
+            let call = Call::PulseSingleKey {
+                key,
+                start_color,
+                end_color,
+                duration,
+                is_infinite,
+            };
+            (call, result)
+        }
+        "LogiLedStopEffectsOnKey" => {
+            let key = parser.key()?;
+            (Call::StopEffectsOnKey { key }, parser.result(success)?)
+        }
+        "LogiLedSetLightingForTargetZone" => {
+            let device = parser.device()?;
+            let zone = parser.int()?;
+            let color = parser.color()?;
+            let result = parser.result(success)?;
+            let call = Call::SetLightingForTargetZone {
+                device,
+                zone,
+                color,
+            };
+            (call, result)
+        }
+        "LogiLedShutdown" => (Call::Shutdown, CallResult::Unit),
+        function => return Err(format!("unknown function \"{function}\"")),
+    };
+
+    if parser.tokens.next().is_some() {
+        return Err(format!("too many values for {function}"));
+    }
+
+    Ok(TraceEvent {
+        time: Duration::from_micros(time),
+        call,
+        result,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut bitmap = KeyboardBitmap::new();
+        assert!(bitmap.set_key(KeyName::ESC, ColorPercent::RED));
+        let calls = [
+            (
+                Call::InitWithName {
+                    name: "game \"1\"\n\u{7f}".into(),
+                },
+                CallResult::Success(true),
+            ),
+            (Call::GetSdkVersion, CallResult::Version((9, 1, 2))),
+            (
+                Call::GetConfigOptionNumber {
+                    path: "lighting/speed".into(),
+                    default: 1.5,
+                },
+                CallResult::Number(0.1),
+            ),
+            (
+                Call::GetConfigOptionColor {
+                    path: "lighting/color".into(),
+                    default: (255, 0, 0),
+                },
+                CallResult::Failed,
+            ),
+            (
+                Call::GetConfigOptionSelect {
+                    path: "lighting/mode".into(),
+                    default: "Solid".into(),
+                    options: vec!["Solid".into(), "Flash pulse".into()],
+                },
+                CallResult::String("Flash pulse".into()),
+            ),
+            (
+                Call::SetTargetDevice {
+                    target_device: TargetDevice::PerKeyRgb | TargetDevice::Rgb,
+                },
+                CallResult::Success(true),
+            ),
+            (
+                Call::SetLightingFromBitmap {
+                    bitmap: Box::new(bitmap),
+                },
+                CallResult::Success(false),
+            ),
+            (
+                Call::ExcludeKeysFromBitmap { keys: Vec::new() },
+                CallResult::Success(true),
+            ),
+            (
+                Call::PulseSingleKey {
+                    key: KeyName::NUM_ENTER,
+                    start_color: ColorPercent::RED,
+                    end_color: ColorPercent::BLACK,
+                    duration: 1000,
+                    is_infinite: true,
+                },
+                CallResult::Success(true),
+            ),
+            (Call::Shutdown, CallResult::Unit),
+        ];
+        let trace: Trace = calls
+            .into_iter()
+            .enumerate()
+            .map(|(index, (call, result))| TraceEvent {
+                time: Duration::from_millis(index as u64 * 16),
+                call,
+                result,
+            })
+            .collect::<Vec<_>>()
+            .into();
+
+        let text = trace.to_string();
+        assert_eq!(
+            text.lines().nth(1),
+            Some("0 LogiLedInitWithName \"game \\\"1\\\"\\n\\u{7f}\" = true")
+        );
+        assert_eq!(
+            text.lines().nth(5),
+            Some("64000 LogiLedGetConfigOptionSelect \"lighting/mode\" \"Solid\" \"Solid\" \"Flash pulse\" = \"Flash pulse\"")
+        );
+        assert_eq!(text.lines().last(), Some("144000 LogiLedShutdown"));
+
+        let parsed: Trace = text.parse().expect("failed to parse trace");
+        assert_eq!(parsed, trace);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let trace: Trace =
+            "# A comment\n\nlogiled-trace 1\n\n10 LogiLedSetLighting 100,0,0 = true\n"
+                .parse()
+                .expect("failed to parse trace");
+        assert_eq!(
+            trace.events(),
+            [TraceEvent {
+                time: Duration::from_micros(10),
+                call: Call::SetLighting {
+                    color: ColorPercent::RED
+                },
+                result: CallResult::Success(true),
+            }]
+        );
+
+        for (input, line, message) in [
+            ("", 1, "the trace is empty"),
+            ("logiled-trace 2", 1, "expected \"logiled-trace 1\""),
+            (
+                "logiled-trace 1\n0 LogiLedSetLighting 101,0,0 = true",
+                2,
+                "expected a color, found \"101,0,0\"",
+            ),
+            (
+                "logiled-trace 1\n\n0 LogiLedSetLighting 100,0,0",
+                3,
+                "expected \"=\" and a result",
+            ),
+            (
+                "logiled-trace 1\n0 LogiLedStopEffects = true false",
+                2,
+                "too many values for LogiLedStopEffects",
+            ),
+            (
+                "logiled-trace 1\n0 LogiLedInitWithName \"game = true",
+                2,
+                "unterminated string",
+            ),
+            (
+                "logiled-trace 1\n0 LogiLedSetLightingForKey W 0,0,0 = true",
+                2,
+                "unknown function \"LogiLedSetLightingForKey\"",
+            ),
+        ] {
+            match input.parse::<Trace>() {
+                Err(TraceError::Parse {
+                    line: error_line,
+                    message: error_message,
+                    ..
+                }) => {
+                    assert_eq!(
+                        (error_line, error_message.as_str()),
+                        (line, message),
+                        "{input}"
+                    );
+                }
+                result => panic!("expected a parse error for {input:?}, got {result:?}"),
+            }
+        }
+    }
+}
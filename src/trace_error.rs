@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+/// An error that may occur while loading a [`Trace`](crate::Trace).
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum TraceError {
+    /// The file could not be read.
+    Io {
+        /// The path of the file.
+        path: PathBuf,
+
+        /// The error.
+        error: std::io::Error,
+    },
+
+    /// The trace was not valid.
+    Parse {
+        /// The path of the file, if the trace was loaded from one.
+        path: Option<PathBuf>,
+
+        /// The line of the error, starting from 1.
+        line: usize,
+
+        /// What was wrong.
+        message: String,
+    },
+}
+
+impl TraceError {
+    /// Set the path of a parse error.
+    pub(crate) fn with_path(mut self, new_path: PathBuf) -> Self {
+        if let Self::Parse { path, .. } = &mut self {
+            *path = Some(new_path);
+        }
+        self
+    }
+}
+
+impl std::fmt::Display for TraceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { path, .. } => write!(f, "failed to read \"{}\"", path.display()),
+            Self::Parse {
+                path: Some(path),
+                line,
+                message,
+            } => write!(f, "{}:{line}: {message}", path.display()),
+            Self::Parse {
+                path: None,
+                line,
+                message,
+            } => write!(f, "line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for TraceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { error, .. } => Some(error),
+            Self::Parse { .. } => None,
+        }
+    }
+}